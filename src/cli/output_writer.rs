@@ -95,6 +95,8 @@ mod tests {
             pdf_input: None,
             input_guardrails_enabled: None,
             output_guardrails_enabled: None,
+            guardrail_provider_errors: None,
+            json_repairs: None,
         }
     }
 
@@ -44,6 +44,7 @@ impl LlmClient {
     ///     api_key: None,
     ///     timeout_secs: 30,
     ///     response_format: None,
+    ///     history: &[],
     /// };
     ///
     /// let response = client.invoke(params).await?;
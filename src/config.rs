@@ -1,4 +1,7 @@
-use crate::{error::CliError, guardrails::GuardrailConfig};
+use crate::{
+    error::CliError,
+    guardrails::{CanaryPlacement, GuardrailConfig, SpotlightingConfig},
+};
 use serde::{Deserialize, Serialize};
 use std::{fs, path::Path};
 
@@ -87,6 +90,22 @@ pub struct ConfigFileRequest {
     /// Guardrail configuration (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub guardrails: Option<GuardrailConfig>,
+
+    /// Spotlighting transform for untrusted user content (optional, default: disabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spotlighting: Option<SpotlightingConfig>,
+
+    /// Canary token injection (optional: "system_prompt" or "user_document", default: disabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canary: Option<CanaryPlacement>,
+
+    /// Extract JSON from fenced or chatty responses before validation (optional, default: false)
+    #[serde(default)]
+    pub lenient_json: bool,
+
+    /// Follow-up attempts when a response fails the json-schema response format (optional, default: 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_retries: Option<u32>,
 }
 
 impl ConfigFileRequest {
@@ -4,8 +4,8 @@
 //! Follows the Builder pattern for testability and reusability.
 
 use crate::{
-    config::ConfigFileRequest, constants::llm_defaults, error::CliError, model_registry,
-    schema_validator, EvaluationConfig, Provider, ResponseFormat,
+    config::ConfigFileRequest, constants::llm_defaults, error::CliError, model_registry, providers,
+    schema_compat, schema_dialect, schema_validator, EvaluationConfig, Provider, ResponseFormat,
 };
 use std::path::PathBuf;
 
@@ -39,6 +39,10 @@ pub struct ConfigBuilder {
     pub pdf_input: Option<PathBuf>,
     pub input_guardrails: Option<crate::GuardrailProviderConfig>,
     pub output_guardrails: Option<crate::GuardrailProviderConfig>,
+    pub canary: Option<crate::CanaryPlacement>,
+    pub spotlighting: Option<crate::SpotlightingConfig>,
+    pub lenient_json: Option<bool>,
+    pub schema_retries: Option<u32>,
 
     // Source tracking (for metadata reproducibility)
     pub system_prompt_file: Option<PathBuf>,
@@ -130,6 +134,19 @@ impl ConfigBuilder {
             });
         }
 
+        if self.canary.is_none() {
+            self.canary = file_config.canary;
+        }
+        if self.spotlighting.is_none() {
+            self.spotlighting = file_config.spotlighting.clone();
+        }
+        if self.lenient_json.is_none() {
+            self.lenient_json = Some(file_config.lenient_json);
+        }
+        if self.schema_retries.is_none() {
+            self.schema_retries = file_config.schema_retries;
+        }
+
         // Handle response_format from config file (only if not set via CLI)
         if self.response_format.is_none() {
             if let Some(format_str) = &file_config.response_format {
@@ -285,6 +302,30 @@ impl ConfigBuilder {
         self
     }
 
+    /// Enable canary token injection
+    pub fn canary(mut self, placement: crate::CanaryPlacement) -> Self {
+        self.canary = Some(placement);
+        self
+    }
+
+    /// Set spotlighting transform for untrusted user content
+    pub fn spotlighting(mut self, spotlighting: crate::SpotlightingConfig) -> Self {
+        self.spotlighting = Some(spotlighting);
+        self
+    }
+
+    /// Set whether to extract JSON from fenced or chatty responses
+    pub fn lenient_json(mut self, lenient_json: bool) -> Self {
+        self.lenient_json = Some(lenient_json);
+        self
+    }
+
+    /// Set follow-up attempts when a response fails the json-schema response format
+    pub fn schema_retries(mut self, schema_retries: u32) -> Self {
+        self.schema_retries = Some(schema_retries);
+        self
+    }
+
     /// Build the final EvaluationConfig, applying defaults and validation
     ///
     /// # Errors
@@ -356,6 +397,47 @@ impl ConfigBuilder {
             )));
         }
 
+        let schema_retries = self.schema_retries.unwrap_or(0);
+        if schema_retries > llm_defaults::MAX_SCHEMA_RETRIES {
+            return Err(CliError::InvalidArguments(format!(
+                "schema_retries must be <= {}, got {schema_retries}",
+                llm_defaults::MAX_SCHEMA_RETRIES
+            )));
+        }
+        if schema_retries > 0
+            && !matches!(
+                self.response_format,
+                Some(ResponseFormat::JsonSchema { .. })
+            )
+        {
+            log::warn!(
+                "schema_retries is set but response_format is not json-schema; retries are disabled"
+            );
+        }
+
+        // Warn about schema features the provider's structured-output mode can't honor:
+        // first the lossy rewrites applied to the request schema, then anything left over
+        if let Some(ResponseFormat::JsonSchema { json_schema }) = &self.response_format {
+            let provider = self
+                .provider
+                .unwrap_or_else(|| providers::detect_provider_type(&api_url));
+            let strict = json_schema.strict.unwrap_or(false);
+            let translated =
+                schema_dialect::translate_schema(&json_schema.schema, provider, strict);
+            for change in &translated.changes {
+                log::warn!(
+                    "Schema '{}' rewritten for {provider:?}: {change}",
+                    json_schema.name
+                );
+            }
+            for issue in schema_compat::check_compatibility(&translated.schema, provider, strict) {
+                log::warn!(
+                    "Schema '{}' may not be supported by {provider:?}: {issue}",
+                    json_schema.name
+                );
+            }
+        }
+
         // Auto-detect context limit from model registry if not explicitly set
         // Validate user-provided limit first (early return on error)
         if let Some(limit) = self.context_limit {
@@ -405,6 +487,10 @@ impl ConfigBuilder {
             pdf_input: self.pdf_input,
             input_guardrails: self.input_guardrails,
             output_guardrails: self.output_guardrails,
+            canary: self.canary,
+            spotlighting: self.spotlighting,
+            lenient_json: self.lenient_json.unwrap_or(false),
+            schema_retries,
             system_prompt_file: self.system_prompt_file,
             user_prompt_file: self.user_prompt_file,
         })
@@ -432,7 +518,7 @@ pub fn load_json_schema(schema_path: &PathBuf, strict: bool) -> Result<ResponseF
             "Invalid JSON in schema file '{}': {e}\n\
             Please verify:\n\
             - File contains valid JSON (try 'jq . {}' or 'python -m json.tool {}')\n\
-            - Schema follows JSON Schema Draft 7+ specification\n\
+            - Schema follows a supported JSON Schema draft (4, 6, 7, 2019-09 or 2020-12)\n\
             - All quotes and braces are properly matched",
             schema_path.display(),
             schema_path.display(),
@@ -449,13 +535,15 @@ pub fn load_json_schema(schema_path: &PathBuf, strict: bool) -> Result<ResponseF
         ))
     })?;
 
-    // Validate against JSON Schema Draft 7 metaschema (comprehensive)
+    // Inline sibling files referenced via relative $ref so the schema is self-contained
+    let schema = schema_validator::bundle_file_refs(&schema, schema_path)?;
+
+    // Validate against the metaschema of the declared draft (comprehensive)
     schema_validator::validate_json_schema(&schema).map_err(|e| {
         CliError::InvalidArguments(format!(
             "Schema file '{}' validation failed:\n{e}\n\n\
-            Your schema does not conform to JSON Schema Draft 7 specification.\n\
             References:\n\
-            - JSON Schema specification: https://json-schema.org/draft-07/json-schema-release-notes.html\n\
+            - JSON Schema specification: https://json-schema.org/specification\n\
             - Schema validator: https://www.jsonschemavalidator.net/\n\
             - Examples: See examples/schemas/ directory",
             schema_path.display()
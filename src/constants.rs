@@ -58,6 +58,9 @@ pub mod llm_defaults {
 
     /// Default timeout for LLM API calls in seconds
     pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+    /// Maximum self-correcting retries after a response fails its JSON Schema
+    pub const MAX_SCHEMA_RETRIES: u32 = 10;
 }
 
 /// HTTP client configuration
@@ -26,6 +26,13 @@ pub enum CliError {
 
     #[error("PDF processing failed: {0}")]
     PdfProcessingFailed(String),
+
+    /// The evaluation produced an error output (e.g. a guardrail rejection); `code` is its error code
+    #[error("Evaluation failed ({code}): {message}")]
+    EvaluationFailed { code: String, message: String },
+
+    #[error("Response does not match type {type_name}: {message}")]
+    ResponseTypeMismatch { type_name: String, message: String },
 }
 
 impl CliError {
@@ -39,6 +46,8 @@ impl CliError {
             Self::InvalidArguments(_) => "INVALID_ARGUMENTS",
             Self::AuthenticationFailed(_) => "AUTH_FAILED",
             Self::PdfProcessingFailed(_) => "PDF_PROCESSING_FAILED",
+            Self::EvaluationFailed { .. } => "EVALUATION_FAILED",
+            Self::ResponseTypeMismatch { .. } => "RESPONSE_TYPE_MISMATCH",
         }
     }
 
@@ -52,6 +61,8 @@ impl CliError {
             Self::InvalidArguments(_) => 6,
             Self::AuthenticationFailed(_) => 7,
             Self::PdfProcessingFailed(_) => 8,
+            Self::EvaluationFailed { .. } => 9,
+            Self::ResponseTypeMismatch { .. } => 4,
         }
     }
 }
@@ -0,0 +1,505 @@
+//! Verdict caching for guardrail providers
+//!
+//! Wraps any provider and reuses its verdict when the same provider config
+//! sees the same content (and conversation context) again. Entries live in a
+//! process-wide in-memory LRU or as JSON files in a directory, and expire
+//! after a TTL.
+
+use crate::{
+    error::CliError,
+    guardrails::{
+        hybrid::errored_providers,
+        provider::{GuardrailProvider, GuardrailResult, ValidationContext},
+    },
+};
+use async_trait::async_trait;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    io::Write,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Default number of verdicts kept by the in-memory backend
+const DEFAULT_CAPACITY: usize = 1000;
+
+/// Default verdict lifetime (24 hours)
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 86_400;
+
+/// Where cached verdicts are stored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CacheBackend {
+    /// Process-wide LRU (shared by every evaluation in the process)
+    Memory {
+        #[serde(default = "default_capacity")]
+        capacity: usize,
+    },
+
+    /// One JSON file per verdict in `path` (shared across processes)
+    Disk { path: PathBuf },
+}
+
+impl Default for CacheBackend {
+    fn default() -> Self {
+        Self::Memory {
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+}
+
+fn default_capacity() -> usize {
+    DEFAULT_CAPACITY
+}
+
+/// A cached verdict with the time it was stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Seconds since the Unix epoch
+    stored_at: u64,
+    result: GuardrailResult,
+}
+
+type MemoryStore = Arc<Mutex<LruCache<String, CacheEntry>>>;
+
+/// In-memory stores, one per cached provider config (keyed by its fingerprint)
+static MEMORY_STORES: Lazy<Mutex<HashMap<String, MemoryStore>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+enum Store {
+    Memory(MemoryStore),
+    Disk(PathBuf),
+}
+
+/// Guardrail wrapper that caches the wrapped provider's verdicts
+///
+/// Only successful validations are cached; errors, and composite verdicts in
+/// which a member provider errored, always reach the provider again.
+/// Validations carrying a canary token bypass the cache, since the token is
+/// different on every request.
+pub struct CachedGuardrail {
+    inner: Box<dyn GuardrailProvider>,
+    fingerprint: String,
+    store: Store,
+    ttl: Duration,
+}
+
+impl CachedGuardrail {
+    /// Wrap `inner`, whose configuration is identified by `fingerprint`
+    ///
+    /// Use the serialised provider config as fingerprint so that any config
+    /// change (model, categories, policy...) invalidates previous verdicts.
+    /// A `ttl` of zero keeps verdicts forever.
+    pub fn new(
+        inner: Box<dyn GuardrailProvider>,
+        fingerprint: impl Into<String>,
+        backend: &CacheBackend,
+        ttl: Duration,
+    ) -> Result<Self, CliError> {
+        let fingerprint = hex_digest(&[fingerprint.into().as_bytes()]);
+        let store = match backend {
+            CacheBackend::Memory { capacity } => {
+                let capacity = NonZeroUsize::new(*capacity).ok_or_else(|| {
+                    CliError::InvalidArguments("Cache capacity must be at least 1".to_string())
+                })?;
+                let mut stores = MEMORY_STORES.lock().unwrap_or_else(|e| e.into_inner());
+                let store = stores
+                    .entry(fingerprint.clone())
+                    .or_insert_with(|| Arc::new(Mutex::new(LruCache::new(capacity))));
+                Store::Memory(Arc::clone(store))
+            }
+            CacheBackend::Disk { path } => {
+                std::fs::create_dir_all(path).map_err(|e| {
+                    CliError::InvalidArguments(format!(
+                        "Failed to create cache directory '{}': {e}",
+                        path.display()
+                    ))
+                })?;
+                Store::Disk(path.clone())
+            }
+        };
+
+        Ok(Self {
+            inner,
+            fingerprint,
+            store,
+            ttl,
+        })
+    }
+
+    /// Cache key: hash of provider config, content and the context fields
+    /// providers read (stage, prompts, source document)
+    fn key(&self, content: &str, context: &ValidationContext<'_>) -> String {
+        let stage = format!("{:?}", context.stage);
+        hex_digest(&[
+            self.fingerprint.as_bytes(),
+            stage.as_bytes(),
+            context.system_prompt.unwrap_or_default().as_bytes(),
+            context.user_prompt.unwrap_or_default().as_bytes(),
+            context.source_text.unwrap_or_default().as_bytes(),
+            content.as_bytes(),
+        ])
+    }
+
+    fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        self.ttl.is_zero() || now_secs().saturating_sub(entry.stored_at) < self.ttl.as_secs()
+    }
+
+    fn get(&self, key: &str) -> Option<GuardrailResult> {
+        let entry = match &self.store {
+            Store::Memory(store) => {
+                let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+                let entry = store.get(key).cloned()?;
+                if !self.is_fresh(&entry) {
+                    store.pop(key);
+                    return None;
+                }
+                entry
+            }
+            Store::Disk(dir) => {
+                let path = dir.join(format!("{key}.json"));
+                let data = std::fs::read(&path).ok()?;
+                let entry: CacheEntry = match serde_json::from_slice(&data) {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        log::warn!("Ignoring corrupt cache entry '{}': {e}", path.display());
+                        return None;
+                    }
+                };
+                if !self.is_fresh(&entry) {
+                    let _ = std::fs::remove_file(&path);
+                    return None;
+                }
+                entry
+            }
+        };
+        Some(entry.result)
+    }
+
+    fn put(&self, key: String, result: &GuardrailResult) {
+        let entry = CacheEntry {
+            stored_at: now_secs(),
+            result: result.clone(),
+        };
+        match &self.store {
+            Store::Memory(store) => {
+                store
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .put(key, entry);
+            }
+            Store::Disk(dir) => {
+                // Write to a temp file and rename so readers never see partial entries
+                let written = tempfile::NamedTempFile::new_in(dir).and_then(|mut file| {
+                    file.write_all(&serde_json::to_vec(&entry)?)?;
+                    file.persist(dir.join(format!("{key}.json")))
+                        .map_err(|e| e.error)?;
+                    Ok(())
+                });
+                if let Err(e) = written {
+                    log::warn!("Failed to write cache entry in '{}': {e}", dir.display());
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl GuardrailProvider for CachedGuardrail {
+    async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        self.validate_with_context(content, &ValidationContext::default())
+            .await
+    }
+
+    async fn validate_with_context(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
+        if context.canary.is_some() {
+            return self.inner.validate_with_context(content, context).await;
+        }
+
+        let key = self.key(content, context);
+        if let Some(mut result) = self.get(&key) {
+            log::debug!("Guardrail cache hit for {}", self.inner.name());
+            result.cache_hit = true;
+            return Ok(result);
+        }
+
+        let result = self.inner.validate_with_context(content, context).await?;
+        // A composite turns provider errors into a verdict (fail_open/warn/fail_closed);
+        // don't let a transient outage stand in for the real verdict until the TTL expires
+        let errored = errored_providers(&result);
+        if errored.is_empty() {
+            self.put(key, &result);
+        } else {
+            log::debug!(
+                "Not caching {} verdict: provider errors in {}",
+                self.inner.name(),
+                errored.join(", ")
+            );
+        }
+        Ok(result)
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+/// Fingerprint of a provider config and the contents of the files it references
+///
+/// Pass the result to [`CachedGuardrail::new`] so that editing a patterns, schema or
+/// policy file invalidates verdicts just like editing the config itself.
+pub fn config_fingerprint(config: &str, files: &[&Path]) -> Result<String, CliError> {
+    let mut parts = vec![hex_digest(&[config.as_bytes()])];
+    for path in files {
+        let contents = std::fs::read(path).map_err(|e| {
+            CliError::FileNotFound(format!(
+                "Failed to read '{}' for the guardrail cache fingerprint: {e}",
+                path.display()
+            ))
+        })?;
+        parts.push(hex_digest(&[
+            path.as_os_str().as_encoded_bytes(),
+            &contents,
+        ]));
+    }
+    Ok(parts.join(":"))
+}
+
+/// Hex-encoded SHA-256 of length-prefixed parts (so part boundaries matter)
+fn hex_digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Provider that counts its calls and passes everything
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl GuardrailProvider for CountingProvider {
+        async fn validate(&self, _content: &str) -> Result<GuardrailResult, CliError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(GuardrailResult::without_quality_score(true, vec![], vec![]))
+        }
+
+        fn name(&self) -> &str {
+            "CountingProvider"
+        }
+    }
+
+    fn cached(
+        fingerprint: &str,
+        backend: &CacheBackend,
+        ttl: Duration,
+    ) -> (CachedGuardrail, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let inner = Box::new(CountingProvider {
+            calls: Arc::clone(&calls),
+        });
+        (
+            CachedGuardrail::new(inner, fingerprint, backend, ttl).unwrap(),
+            calls,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_hit() {
+        let (guardrail, calls) = cached(
+            "test_memory_cache_hit",
+            &CacheBackend::default(),
+            Duration::from_secs(60),
+        );
+
+        let first = guardrail.validate("same content").await.unwrap();
+        let second = guardrail.validate("same content").await.unwrap();
+        assert!(!first.cache_hit);
+        assert!(second.cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        guardrail.validate("other content").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_shared_across_instances() {
+        let backend = CacheBackend::default();
+        let (first, _) = cached("test_shared", &backend, Duration::from_secs(60));
+        first.validate("content").await.unwrap();
+
+        // A fresh provider built from the same config reuses the verdict
+        let (second, calls) = cached("test_shared", &backend, Duration::from_secs(60));
+        assert!(second.validate("content").await.unwrap().cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // A different config does not
+        let (other, calls) = cached("test_shared_other", &backend, Duration::from_secs(60));
+        assert!(!other.validate("content").await.unwrap().cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_evicts_least_recently_used() {
+        let backend = CacheBackend::Memory { capacity: 1 };
+        let (guardrail, calls) = cached("test_lru", &backend, Duration::from_secs(60));
+        guardrail.validate("a").await.unwrap();
+        guardrail.validate("b").await.unwrap();
+        assert!(!guardrail.validate("a").await.unwrap().cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_context_is_part_of_key() {
+        let (guardrail, calls) = cached(
+            "test_context_key",
+            &CacheBackend::default(),
+            Duration::from_secs(60),
+        );
+        let first = ValidationContext::output("sys", "question one", "answer");
+        let second = ValidationContext::output("sys", "question two", "answer");
+
+        guardrail
+            .validate_with_context("answer", &first)
+            .await
+            .unwrap();
+        let result = guardrail
+            .validate_with_context("answer", &second)
+            .await
+            .unwrap();
+        assert!(!result.cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_persists_and_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = CacheBackend::Disk {
+            path: dir.path().to_path_buf(),
+        };
+
+        let (first, _) = cached("test_disk", &backend, Duration::from_secs(60));
+        first.validate("content").await.unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let (second, calls) = cached("test_disk", &backend, Duration::from_secs(60));
+        assert!(second.validate("content").await.unwrap().cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // Backdate the entry past its TTL
+        let path = std::fs::read_dir(dir.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let mut entry: CacheEntry = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        entry.stored_at -= 120;
+        std::fs::write(&path, serde_json::to_vec(&entry).unwrap()).unwrap();
+
+        assert!(!second.validate("content").await.unwrap().cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// Provider reporting a composite member error (as a fail_open composite does)
+    struct ErroredProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl GuardrailProvider for ErroredProvider {
+        async fn validate(&self, _content: &str) -> Result<GuardrailResult, CliError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let warning = crate::guardrails::provider::Violation {
+                rule: crate::guardrails::hybrid::RULE_PROVIDER_ERROR.to_string(),
+                severity: crate::guardrails::provider::Severity::Low,
+                message: "timed out".to_string(),
+                location: None,
+                provider: Some("LlamaGuard".to_string()),
+            };
+            Ok(GuardrailResult::without_quality_score(
+                true,
+                vec![],
+                vec![warning],
+            ))
+        }
+
+        fn name(&self) -> &str {
+            "ErroredProvider"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_errors_not_cached() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let inner = Box::new(ErroredProvider {
+            calls: Arc::clone(&calls),
+        });
+        let guardrail = CachedGuardrail::new(
+            inner,
+            "test_provider_errors_not_cached",
+            &CacheBackend::default(),
+            Duration::from_secs(60),
+        )
+        .unwrap();
+
+        guardrail.validate("content").await.unwrap();
+        assert!(!guardrail.validate("content").await.unwrap().cache_hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_config_fingerprint_covers_file_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("patterns.txt");
+        std::fs::write(&path, "secret").unwrap();
+        let first = config_fingerprint("{}", &[path.as_path()]).unwrap();
+        assert_eq!(first, config_fingerprint("{}", &[path.as_path()]).unwrap());
+
+        std::fs::write(&path, "secret\npassword").unwrap();
+        assert_ne!(first, config_fingerprint("{}", &[path.as_path()]).unwrap());
+
+        let missing = dir.path().join("missing.txt");
+        assert!(config_fingerprint("{}", &[missing.as_path()]).is_err());
+    }
+
+    #[test]
+    fn test_serde_backend() {
+        let backend: CacheBackend = toml::from_str(r#"type = "memory""#).unwrap();
+        assert_eq!(backend, CacheBackend::default());
+
+        let backend: CacheBackend =
+            toml::from_str("type = \"disk\"\npath = \"/tmp/verdicts\"").unwrap();
+        assert_eq!(
+            backend,
+            CacheBackend::Disk {
+                path: PathBuf::from("/tmp/verdicts")
+            }
+        );
+    }
+}
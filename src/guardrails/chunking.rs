@@ -0,0 +1,411 @@
+//! Chunked validation for LLM-based guardrails
+//!
+//! Classifier models have small context windows (Llama Prompt Guard 2: 512
+//! tokens) and silently truncate longer input. Long content is split into
+//! overlapping chunks, the chunks are classified in parallel, and the chunk
+//! verdicts are aggregated back into a single result.
+
+use crate::{
+    constants::token_estimation::{CHARS_PER_TOKEN, SAFETY_MARGIN},
+    error::CliError,
+    guardrails::provider::{GuardrailResult, ProviderSpecificResult, Severity},
+};
+use futures::stream::{FuturesOrdered, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// How chunk verdicts are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkAggregation {
+    /// Fail if any chunk fails; report the violations of every failing chunk
+    #[default]
+    Any,
+
+    /// Report only the worst chunk (most severe violation, highest confidence)
+    Max,
+}
+
+/// Chunking settings for an LLM-based guardrail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkingConfig {
+    /// Maximum chunk size in (estimated) tokens; 0 disables chunking
+    pub chunk_tokens: usize,
+
+    /// Tokens shared by consecutive chunks (so attacks spanning a boundary are seen whole)
+    #[serde(default)]
+    pub overlap_tokens: usize,
+
+    /// How chunk verdicts are combined (default: any)
+    #[serde(default)]
+    pub aggregation: ChunkAggregation,
+
+    /// Maximum number of chunks classified concurrently
+    #[serde(default = "default_max_parallel")]
+    pub max_parallel: usize,
+}
+
+fn default_max_parallel() -> usize {
+    4
+}
+
+impl ChunkingConfig {
+    /// Chunking with the given size and overlap, default aggregation and parallelism
+    pub fn new(chunk_tokens: usize, overlap_tokens: usize) -> Self {
+        Self {
+            chunk_tokens,
+            overlap_tokens,
+            aggregation: ChunkAggregation::default(),
+            max_parallel: default_max_parallel(),
+        }
+    }
+
+    /// Chunking disabled (content is always sent whole)
+    pub fn disabled() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Split `content` into overlapping chunks, returned as (byte offset, chunk) pairs
+    ///
+    /// Returns a single chunk when chunking is disabled or the content fits.
+    pub fn split<'a>(&self, content: &'a str) -> Vec<(usize, &'a str)> {
+        let chunk_chars = tokens_to_bytes(self.chunk_tokens);
+        if chunk_chars == 0 || content.len() <= chunk_chars {
+            return vec![(0, content)];
+        }
+        // Keep at least half of each chunk new, so chunking always advances
+        let overlap = tokens_to_bytes(self.overlap_tokens).min(chunk_chars / 2);
+
+        let mut chunks = vec![];
+        let mut start = 0;
+        loop {
+            let end = chunk_end(content, start, chunk_chars);
+            chunks.push((start, &content[start..end]));
+            if end == content.len() {
+                return chunks;
+            }
+            start = floor_char_boundary(content, end.saturating_sub(overlap).max(start + 1));
+        }
+    }
+
+    /// Make room for `context` sent alongside every chunk
+    ///
+    /// `context` is cut to its last `chunk_tokens / 2` tokens; the chunks get the rest of
+    /// the budget. Returns the (possibly truncated) context and the chunking to split the
+    /// content with. Disabled chunking leaves both unchanged.
+    pub fn reserve_context<'a>(&self, context: &'a str) -> (&'a str, ChunkingConfig) {
+        if self.chunk_tokens == 0 {
+            return (context, *self);
+        }
+        let budget = self.chunk_tokens / 2;
+        let max_len = tokens_to_bytes(budget);
+        let context = if context.len() > max_len {
+            let start = ceil_char_boundary(context, context.len() - max_len);
+            log::debug!(
+                "Truncating {} bytes of chunk context to {}",
+                context.len(),
+                context.len() - start
+            );
+            &context[start..]
+        } else {
+            context
+        };
+        let reserved = bytes_to_tokens(context.len()).min(budget);
+        let chunking = ChunkingConfig {
+            chunk_tokens: self.chunk_tokens - reserved,
+            ..*self
+        };
+        (context, chunking)
+    }
+}
+
+/// Approximate byte budget for a token count (conservative)
+fn tokens_to_bytes(tokens: usize) -> usize {
+    (tokens as f64 * CHARS_PER_TOKEN / SAFETY_MARGIN) as usize
+}
+
+/// End of a chunk starting at `start`, preferring to break at whitespace
+fn chunk_end(content: &str, start: usize, max_len: usize) -> usize {
+    let limit = start + max_len;
+    if limit >= content.len() {
+        return content.len();
+    }
+    let limit = floor_char_boundary(content, limit);
+    // Look back at most 10% of the chunk for a word boundary
+    let search_from = floor_char_boundary(content, limit - max_len / 10);
+    match content[search_from..limit].rfind(char::is_whitespace) {
+        Some(pos) if search_from + pos > start => search_from + pos,
+        _ => limit,
+    }
+}
+
+/// Approximate token count of `len` bytes (inverse of [`tokens_to_bytes`])
+fn bytes_to_tokens(len: usize) -> usize {
+    (len as f64 * SAFETY_MARGIN / CHARS_PER_TOKEN).ceil() as usize
+}
+
+fn ceil_char_boundary(content: &str, mut index: usize) -> usize {
+    while !content.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+fn floor_char_boundary(content: &str, mut index: usize) -> usize {
+    while !content.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Validate `content` chunk by chunk and aggregate the verdicts
+///
+/// Chunks are validated concurrently (up to `max_parallel` at a time). Any
+/// chunk error fails the whole validation. Violations of a chunk are located
+/// at the chunk's byte range (`Position {start}-{end}`).
+pub async fn validate_in_chunks<'a, F, Fut>(
+    content: &'a str,
+    config: &ChunkingConfig,
+    validate_chunk: F,
+) -> Result<GuardrailResult, CliError>
+where
+    F: Fn(&'a str) -> Fut,
+    Fut: Future<Output = Result<GuardrailResult, CliError>>,
+{
+    let chunks = config.split(content);
+    if chunks.len() == 1 {
+        return validate_chunk(content).await;
+    }
+
+    log::debug!(
+        "Validating {} bytes in {} chunks",
+        content.len(),
+        chunks.len()
+    );
+
+    // Keep at most `max_parallel` chunk validations in flight, in chunk order
+    let max_parallel = config.max_parallel.max(1);
+    let mut pending = chunks.into_iter();
+    let mut in_flight = FuturesOrdered::new();
+    let mut results = vec![];
+    loop {
+        while in_flight.len() < max_parallel {
+            let Some((offset, chunk)) = pending.next() else {
+                break;
+            };
+            in_flight.push_back(validate_located(offset, chunk, validate_chunk(chunk)));
+        }
+        match in_flight.next().await {
+            Some(result) => results.push(result?),
+            None => break,
+        }
+    }
+
+    Ok(aggregate(results, config.aggregation))
+}
+
+/// Await a chunk validation and locate its violations at the chunk's byte range
+async fn validate_located(
+    offset: usize,
+    chunk: &str,
+    validation: impl Future<Output = Result<GuardrailResult, CliError>>,
+) -> Result<GuardrailResult, CliError> {
+    let mut result = validation.await?;
+    let location = format!("Position {}-{}", offset, offset + chunk.len());
+    for violation in result.violations.iter_mut().chain(&mut result.warnings) {
+        violation.location.get_or_insert_with(|| location.clone());
+    }
+    Ok(result)
+}
+
+/// Combine chunk verdicts
+fn aggregate(mut results: Vec<GuardrailResult>, aggregation: ChunkAggregation) -> GuardrailResult {
+    let worst = results
+        .iter()
+        .enumerate()
+        .max_by_key(|(idx, result)| (severity_rank(result), std::cmp::Reverse(*idx)))
+        .map(|(idx, _)| idx)
+        .unwrap_or_default();
+
+    match aggregation {
+        ChunkAggregation::Max => results.swap_remove(worst),
+        ChunkAggregation::Any => {
+            let provider_specific = results[worst].provider_specific.clone();
+            let quality_score = results
+                .iter()
+                .filter_map(|r| r.quality_score)
+                .min_by(f32::total_cmp);
+            let mut merged = GuardrailResult::without_quality_score(true, vec![], vec![]);
+            for result in results {
+                merged.passed &= result.passed;
+                merged.violations.extend(result.violations);
+                merged.warnings.extend(result.warnings);
+            }
+            merged.quality_score = quality_score;
+            merged.provider_specific = provider_specific;
+            merged
+        }
+    }
+}
+
+/// Ordering key for the "worst" chunk: failed first, then most severe
+/// violation, then highest classifier confidence
+fn severity_rank(result: &GuardrailResult) -> (bool, Option<Severity>, u32) {
+    let confidence = match &result.provider_specific {
+        Some(ProviderSpecificResult::LlamaPromptGuard(r)) if r.malicious => r.confidence,
+        Some(ProviderSpecificResult::InjectionScorer(r)) if r.malicious => r.confidence,
+        _ => 0.0,
+    };
+    (
+        !result.passed,
+        result.violations.iter().map(|v| v.severity).max(),
+        (confidence.clamp(0.0, 1.0) * 1_000_000.0) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guardrails::provider::Violation;
+
+    fn verdict(chunk: &str) -> GuardrailResult {
+        if chunk.contains("ATTACK") {
+            GuardrailResult::without_quality_score(
+                false,
+                vec![Violation {
+                    rule: "PROMPT_INJECTION".to_string(),
+                    severity: Severity::Critical,
+                    message: "attack".to_string(),
+                    location: None,
+                    provider: None,
+                }],
+                vec![],
+            )
+        } else {
+            GuardrailResult::without_quality_score(true, vec![], vec![])
+        }
+    }
+
+    #[test]
+    fn test_split_short_content_is_single_chunk() {
+        let config = ChunkingConfig::new(512, 64);
+        assert_eq!(config.split("short"), vec![(0, "short")]);
+        assert_eq!(
+            ChunkingConfig::disabled().split(&"x".repeat(10_000)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_split_covers_content_with_overlap() {
+        let content = "word ".repeat(2_000);
+        let config = ChunkingConfig::new(100, 20);
+        let chunks = config.split(&content);
+        assert!(chunks.len() > 1);
+
+        let max_len = tokens_to_bytes(100);
+        for window in chunks.windows(2) {
+            let (start, chunk) = window[0];
+            let (next_start, _) = window[1];
+            assert!(chunk.len() <= max_len);
+            // Consecutive chunks overlap
+            assert!(next_start < start + chunk.len());
+            assert_eq!(&content[start..start + chunk.len()], chunk);
+        }
+        let (last_start, last) = chunks.last().unwrap();
+        assert_eq!(last_start + last.len(), content.len());
+    }
+
+    #[test]
+    fn test_reserve_context_shares_budget() {
+        let config = ChunkingConfig::new(100, 10);
+        let (context, chunking) = config.reserve_context("short question");
+        assert_eq!(context, "short question");
+        assert_eq!(chunking.chunk_tokens, 100 - bytes_to_tokens(14));
+
+        let long = format!("{}the question", "é".repeat(1_000));
+        let (context, chunking) = config.reserve_context(&long);
+        assert!(context.ends_with("the question"));
+        assert!(context.len() <= tokens_to_bytes(50));
+        assert_eq!(chunking.chunk_tokens, 50);
+
+        let (context, chunking) = ChunkingConfig::disabled().reserve_context(&long);
+        assert_eq!(
+            (context, chunking),
+            (long.as_str(), ChunkingConfig::disabled())
+        );
+    }
+
+    #[test]
+    fn test_split_respects_char_boundaries() {
+        let content = "é".repeat(5_000);
+        let chunks = ChunkingConfig::new(100, 10).split(&content);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|(_, c)| c.chars().all(|ch| ch == 'é')));
+    }
+
+    #[tokio::test]
+    async fn test_any_reports_offending_chunk_offset() {
+        let content = format!("{}ATTACK{}", "safe ".repeat(400), "safe ".repeat(400));
+        let config = ChunkingConfig::new(100, 10);
+
+        let result =
+            validate_in_chunks(&content, &config, |chunk| async move { Ok(verdict(chunk)) })
+                .await
+                .unwrap();
+
+        assert!(!result.passed);
+        let attack = content.find("ATTACK").unwrap();
+        for violation in &result.violations {
+            let location = violation.location.as_deref().unwrap();
+            let range = location.strip_prefix("Position ").unwrap();
+            let (start, end) = range.split_once('-').unwrap();
+            let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+            assert!(start <= attack && attack + "ATTACK".len() <= end);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_keeps_worst_chunk_only() {
+        let content = format!("ATTACK {} ATTACK", "safe ".repeat(800));
+        let config = ChunkingConfig {
+            aggregation: ChunkAggregation::Max,
+            ..ChunkingConfig::new(100, 0)
+        };
+
+        let result =
+            validate_in_chunks(&content, &config, |chunk| async move { Ok(verdict(chunk)) })
+                .await
+                .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0]
+            .location
+            .as_deref()
+            .unwrap()
+            .starts_with("Position 0-"));
+    }
+
+    #[tokio::test]
+    async fn test_single_chunk_has_no_location() {
+        let result = validate_in_chunks(
+            "ATTACK",
+            &ChunkingConfig::new(512, 64),
+            |chunk| async move { Ok(verdict(chunk)) },
+        )
+        .await
+        .unwrap();
+        assert!(!result.passed);
+        assert!(result.violations[0].location.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chunk_error_fails_validation() {
+        let content = "word ".repeat(2_000);
+        let result = validate_in_chunks(&content, &ChunkingConfig::new(100, 0), |_| async {
+            Err(CliError::InvalidResponse("down".to_string()))
+        })
+        .await;
+        assert!(result.is_err());
+    }
+}
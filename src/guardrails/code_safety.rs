@@ -0,0 +1,573 @@
+use crate::{
+    error::CliError,
+    guardrails::{
+        patterns::parse_severity,
+        provider::{
+            GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity, Violation,
+        },
+    },
+};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+/// Built-in rules (same format as `rules_file`)
+const DEFAULT_CODE_RULES: &str = include_str!("default_patterns/default_code_rules.txt");
+
+/// Language name for rules that apply to every code block
+const ANY_LANGUAGE: &str = "any";
+
+/// Configuration for the code and command safety guardrail
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodeSafetyConfig {
+    /// Skip the built-in rules (only `rules_file` is used)
+    #[serde(default)]
+    pub disable_default_rules: bool,
+
+    /// Additional rules file (`languages<TAB>rule<TAB>pattern<TAB>description<TAB>severity`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules_file: Option<PathBuf>,
+
+    /// Severity per rule name, overriding the rules file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub severity_overrides: BTreeMap<String, Severity>,
+
+    /// Rule names that are not checked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_rules: Vec<String>,
+
+    /// Scan the whole content as code when it has no fenced code block
+    #[serde(default)]
+    pub scan_unfenced: bool,
+}
+
+/// A dangerous-construct rule
+#[derive(Debug, Clone)]
+pub struct CodeRule {
+    /// Languages the rule applies to (normalized); empty = any language
+    pub languages: Vec<String>,
+    /// Rule name reported in violations
+    pub rule: String,
+    pub regex: Regex,
+    pub description: String,
+    pub severity: Severity,
+}
+
+impl CodeRule {
+    /// Rules apply to their languages, and to every block whose language is unknown
+    fn applies_to(&self, language: Option<&str>) -> bool {
+        match language {
+            Some(language) => {
+                self.languages.is_empty() || self.languages.iter().any(|l| l == language)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Parse code rules from string content
+///
+/// Format (tab-delimited, `#` comments and blank lines skipped):
+/// ```text
+/// # languages<TAB>rule<TAB>pattern<TAB>description<TAB>severity
+/// shell    CURL_TO_SHELL    \bcurl\b[^|]*\|\s*sh\b    Downloaded script piped to a shell    critical
+/// sql,python    RAW_SQL    (?i)\bexecute\s*\(\s*f"    SQL built with an f-string    medium
+/// ```
+///
+/// Languages are comma-separated (aliases such as `bash` or `py` are accepted) or `any`.
+pub fn parse_code_rules(content: &str) -> Result<Vec<CodeRule>, CliError> {
+    let mut rules = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_number = idx + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split('\t').map(|s| s.trim()).collect();
+        if parts.len() != 5 {
+            return Err(CliError::InvalidResponse(format!(
+                "Invalid code rule format at line {}: expected 5 tab-separated fields (languages<TAB>rule<TAB>pattern<TAB>description<TAB>severity), got {}",
+                line_number,
+                parts.len()
+            )));
+        }
+
+        let languages = parts[0]
+            .split(',')
+            .map(|l| l.trim().to_lowercase())
+            .filter(|l| !l.is_empty() && l != ANY_LANGUAGE)
+            .map(|l| normalize_language(&l).unwrap_or(l))
+            .collect();
+
+        let regex = Regex::new(parts[2]).map_err(|e| {
+            let pattern = parts[2];
+            CliError::InvalidResponse(format!(
+                "Line {line_number}: Invalid regex pattern '{pattern}': {e}"
+            ))
+        })?;
+
+        let severity = parse_severity(parts[4])
+            .map_err(|e| CliError::InvalidResponse(format!("Line {line_number}: {e}")))?;
+
+        rules.push(CodeRule {
+            languages,
+            rule: parts[1].to_string(),
+            regex,
+            description: parts[3].to_string(),
+            severity,
+        });
+    }
+
+    Ok(rules)
+}
+
+/// A code block found in the content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeBlock {
+    /// Normalized language (None when unknown)
+    pub language: Option<String>,
+    /// True when the language was inferred from the code rather than the fence
+    pub detected: bool,
+    /// Byte offset where the code starts in the content
+    pub start: usize,
+    /// Byte offset where the code ends in the content (exclusive)
+    pub end: usize,
+}
+
+/// Code safety specific result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeSafetyResult {
+    /// Code blocks that were scanned, in content order
+    pub blocks: Vec<CodeBlock>,
+}
+
+/// Code and command safety guardrail: flags dangerous constructs in fenced
+/// code blocks (destructive commands, piping downloads to a shell, disabled
+/// TLS verification, destructive SQL, ...)
+pub struct CodeSafetyGuardrail {
+    config: CodeSafetyConfig,
+    rules: Vec<CodeRule>,
+}
+
+impl CodeSafetyGuardrail {
+    /// Create the guardrail from the built-in rules and the optional rules file
+    ///
+    /// Returns an error if the rules file cannot be read or parsed.
+    pub fn new(config: CodeSafetyConfig) -> Result<Self, CliError> {
+        let mut rules = if config.disable_default_rules {
+            vec![]
+        } else {
+            parse_code_rules(DEFAULT_CODE_RULES)?
+        };
+        if let Some(path) = &config.rules_file {
+            let content = fs::read_to_string(path).map_err(|e| {
+                CliError::FileNotFound(format!(
+                    "Code safety guardrail: Failed to read rules file '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            rules.extend(parse_code_rules(&content)?);
+        }
+
+        rules.retain(|rule| !config.disabled_rules.contains(&rule.rule));
+        for rule in &mut rules {
+            if let Some(severity) = config.severity_overrides.get(&rule.rule) {
+                rule.severity = *severity;
+            }
+        }
+
+        Ok(Self { config, rules })
+    }
+
+    /// Scan every code block with the rules for its language
+    fn check(&self, content: &str) -> GuardrailResult {
+        let mut blocks = extract_code_blocks(content);
+        if blocks.is_empty() && self.config.scan_unfenced && !content.trim().is_empty() {
+            let language = detect_language(content);
+            blocks.push(CodeBlock {
+                detected: language.is_some(),
+                language,
+                start: 0,
+                end: content.len(),
+            });
+        }
+
+        let mut violations = vec![];
+        for block in &blocks {
+            let code = &content[block.start..block.end];
+            let language = block.language.as_deref();
+            for rule in self.rules.iter().filter(|r| r.applies_to(language)) {
+                for m in rule.regex.find_iter(code) {
+                    let start = block.start + m.start();
+                    let end = block.start + m.end();
+                    // One report per location (several lines may share a rule)
+                    if violations
+                        .iter()
+                        .any(|(s, e, v): &(usize, usize, Violation)| {
+                            *s == start && *e == end && v.rule == rule.rule
+                        })
+                    {
+                        continue;
+                    }
+                    violations.push((
+                        start,
+                        end,
+                        Violation {
+                            rule: rule.rule.clone(),
+                            severity: rule.severity,
+                            message: format!(
+                                "{} in {} code: {}",
+                                rule.description,
+                                language.unwrap_or("unknown"),
+                                m.as_str().trim()
+                            ),
+                            location: Some(format!("Position {start}-{end}")),
+                            provider: None,
+                        },
+                    ));
+                }
+            }
+        }
+        violations.sort_by_key(|(start, end, _)| (*start, *end));
+        let violations: Vec<Violation> = violations.into_iter().map(|(_, _, v)| v).collect();
+
+        GuardrailResult::with_provider_specific(
+            violations.is_empty(),
+            violations,
+            vec![],
+            ProviderSpecificResult::CodeSafety(CodeSafetyResult { blocks }),
+        )
+    }
+}
+
+#[async_trait]
+impl GuardrailProvider for CodeSafetyGuardrail {
+    async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        Ok(self.check(content))
+    }
+
+    fn name(&self) -> &str {
+        "CodeSafetyGuardrail"
+    }
+}
+
+/// Canonical language name for a fence info string or alias
+fn normalize_language(tag: &str) -> Option<String> {
+    let tag = tag.to_lowercase();
+    let language = match tag.as_str() {
+        "sh" | "bash" | "zsh" | "shell" | "console" | "shell-session" | "shellsession"
+        | "terminal" | "ksh" | "fish" => "shell",
+        "ps" | "ps1" | "powershell" | "pwsh" => "powershell",
+        "bat" | "cmd" | "batch" => "batch",
+        "sql" | "mysql" | "postgres" | "postgresql" | "psql" | "sqlite" | "plsql" | "tsql" => "sql",
+        "py" | "python" | "python3" => "python",
+        "js" | "javascript" | "node" | "jsx" | "ts" | "typescript" | "tsx" | "mjs" => "javascript",
+        "rb" | "ruby" => "ruby",
+        "go" | "golang" => "go",
+        "rs" | "rust" => "rust",
+        "php" => "php",
+        "java" => "java",
+        "c" | "cpp" | "c++" | "h" | "hpp" => "c",
+        "yaml" | "yml" => "yaml",
+        "dockerfile" | "docker" => "dockerfile",
+        "" | "text" | "txt" | "plaintext" => return None,
+        other => other,
+    };
+    Some(language.to_string())
+}
+
+static SQL_STATEMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?im)^\s*(?:select|insert\s+into|update|delete\s+from|create\s+(?:table|index|database)|drop|alter\s+table|truncate)\b").unwrap()
+});
+static PYTHON_CODE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*(?:def \w+\(|import \w+|from [\w.]+ import |class \w+[:(])").unwrap()
+});
+static JAVASCRIPT_CODE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)(?:^\s*(?:const|let|var) \w+\s*=|\bfunction\s*\w*\s*\(|=>\s*\{|\brequire\(|\bconsole\.log\()").unwrap()
+});
+static SHELL_CODE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*(?:\$ |sudo |apt(?:-get)? |yum |brew |curl |wget |rm |cd |ls |echo |export |chmod |chown |mkdir |dd |git |docker |kubectl )").unwrap()
+});
+
+/// Infer the language of untagged code
+fn detect_language(code: &str) -> Option<String> {
+    let first_line = code.trim_start().lines().next().unwrap_or_default();
+    if let Some(interpreter) = first_line.strip_prefix("#!") {
+        let program = interpreter
+            .split_whitespace()
+            .last()
+            .and_then(|p| p.rsplit('/').next())
+            .unwrap_or_default();
+        return normalize_language(program.trim_end_matches(|c: char| c.is_ascii_digit()));
+    }
+    let language = if SQL_STATEMENT.is_match(code) {
+        "sql"
+    } else if PYTHON_CODE.is_match(code) {
+        "python"
+    } else if JAVASCRIPT_CODE.is_match(code) {
+        "javascript"
+    } else if SHELL_CODE.is_match(code) {
+        "shell"
+    } else {
+        return None;
+    };
+    Some(language.to_string())
+}
+
+/// Fenced code blocks (``` or ~~~), with the language from the info string or
+/// inferred from the code. An unterminated fence runs to the end of the content.
+pub fn extract_code_blocks(content: &str) -> Vec<CodeBlock> {
+    let mut blocks = vec![];
+    // (fence char, fence length, language tag, code start)
+    let mut open: Option<(char, usize, String, usize)> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start_matches(' ');
+        if line.len() - trimmed.len() > 3 {
+            continue; // Indented code, not a fence
+        }
+        let fence_char = match trimmed.chars().next() {
+            Some(c @ ('`' | '~')) => c,
+            _ => continue,
+        };
+        let fence_len = trimmed.chars().take_while(|&c| c == fence_char).count();
+        if fence_len < 3 {
+            continue;
+        }
+        let info = trimmed[fence_len..].trim();
+
+        match &open {
+            None => {
+                let tag = info
+                    .split(|c: char| c.is_whitespace() || c == '{' || c == ',')
+                    .next()
+                    .unwrap_or_default();
+                open = Some((fence_char, fence_len, tag.to_string(), offset));
+            }
+            Some((c, len, tag, start))
+                if *c == fence_char && fence_len >= *len && info.is_empty() =>
+            {
+                blocks.push(code_block(content, tag, *start, line_start));
+                open = None;
+            }
+            Some(_) => {}
+        }
+    }
+    if let Some((_, _, tag, start)) = open {
+        blocks.push(code_block(content, &tag, start, content.len()));
+    }
+
+    blocks
+}
+
+fn code_block(content: &str, tag: &str, start: usize, end: usize) -> CodeBlock {
+    match normalize_language(tag) {
+        Some(language) => CodeBlock {
+            language: Some(language),
+            detected: false,
+            start,
+            end,
+        },
+        None => {
+            let language = detect_language(&content[start..end]);
+            CodeBlock {
+                detected: language.is_some(),
+                language,
+                start,
+                end,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str) -> GuardrailResult {
+        CodeSafetyGuardrail::new(CodeSafetyConfig::default())
+            .unwrap()
+            .check(content)
+    }
+
+    fn rules(result: &GuardrailResult) -> Vec<&str> {
+        result.violations.iter().map(|v| v.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_rules_parse() {
+        let rules = parse_code_rules(DEFAULT_CODE_RULES).unwrap();
+        assert!(rules.len() >= 15);
+        assert!(rules.iter().any(|r| r.rule == "PIPE_TO_SHELL"));
+    }
+
+    #[test]
+    fn test_parse_code_rules_errors() {
+        let err = parse_code_rules("shell\tRULE\tpattern\tdescription")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected 5"));
+
+        let err = parse_code_rules("shell\tRULE\t[unclosed\tdescription\thigh")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid regex"));
+
+        let err = parse_code_rules("shell\tRULE\tpattern\tdescription\tsevere")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid severity"));
+    }
+
+    #[test]
+    fn test_parse_code_rules_languages() {
+        let rules = parse_code_rules("bash, py\tR1\tx\td\tlow\nany\tR2\ty\td\thigh").unwrap();
+        assert_eq!(rules[0].languages, vec!["shell", "python"]);
+        assert!(rules[1].languages.is_empty());
+        assert!(rules[0].applies_to(Some("shell")));
+        assert!(!rules[0].applies_to(Some("sql")));
+        assert!(rules[0].applies_to(None));
+    }
+
+    #[test]
+    fn test_extract_code_blocks() {
+        let content =
+            "Intro\n```bash\nls -la\n```\ntext\n~~~~\nSELECT 1;\n~~~~\n```python\nprint(1)";
+        let blocks = extract_code_blocks(content);
+        assert_eq!(blocks.len(), 3);
+
+        assert_eq!(blocks[0].language.as_deref(), Some("shell"));
+        assert!(!blocks[0].detected);
+        assert_eq!(&content[blocks[0].start..blocks[0].end], "ls -la\n");
+
+        assert_eq!(blocks[1].language.as_deref(), Some("sql"));
+        assert!(blocks[1].detected);
+
+        // Unterminated fence runs to the end
+        assert_eq!(blocks[2].language.as_deref(), Some("python"));
+        assert_eq!(&content[blocks[2].start..blocks[2].end], "print(1)");
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language("#!/usr/bin/env python3\nprint(1)").as_deref(),
+            Some("python")
+        );
+        assert_eq!(detect_language("#!/bin/bash\nls").as_deref(), Some("shell"));
+        assert_eq!(
+            detect_language("sudo apt install nginx").as_deref(),
+            Some("shell")
+        );
+        assert_eq!(detect_language("just some words"), None);
+    }
+
+    #[test]
+    fn test_dangerous_shell() {
+        let result = check(
+            "Run this:\n```sh\ncurl -fsSL https://get.example.com | sudo bash\nchmod -R 777 /var/www\nrm -rf / --no-preserve-root\n```",
+        );
+        assert!(!result.passed);
+        assert_eq!(
+            rules(&result),
+            vec!["PIPE_TO_SHELL", "CHMOD_777", "RM_RF_ROOT"]
+        );
+        assert_eq!(result.violations[0].severity, Severity::Critical);
+        assert!(result.violations[0]
+            .location
+            .as_deref()
+            .unwrap()
+            .starts_with("Position 16-"));
+    }
+
+    #[test]
+    fn test_safe_shell_passes() {
+        let result = check("```bash\nrm -rf ./build\nchmod 644 config.toml\ncurl -o file.tgz https://example.com/file.tgz\n```");
+        assert!(result.passed, "{:?}", result.violations);
+    }
+
+    #[test]
+    fn test_dangerous_sql() {
+        let result = check("```sql\nDELETE FROM users;\nDELETE FROM orders WHERE id = 1;\nDROP TABLE sessions;\n```");
+        assert_eq!(rules(&result), vec!["DELETE_WITHOUT_WHERE", "DROP_TABLE"]);
+    }
+
+    #[test]
+    fn test_tls_and_eval() {
+        let result =
+            check("```python\nrequests.get(url, verify=False)\nresult = eval(input('> '))\n```");
+        assert_eq!(
+            rules(&result),
+            vec!["TLS_VERIFY_DISABLED", "EVAL_UNTRUSTED"]
+        );
+    }
+
+    #[test]
+    fn test_rules_are_scoped_to_language() {
+        // DROP TABLE in a Python string is not flagged by the SQL-only rule
+        let result = check("```python\nprint('drop table is a SQL statement')\n```");
+        assert!(result.passed);
+    }
+
+    #[test]
+    fn test_prose_outside_code_is_ignored() {
+        let result = check("Never run `rm -rf /` or pipe curl | sh.");
+        assert!(result.passed);
+
+        let unfenced = CodeSafetyGuardrail::new(CodeSafetyConfig {
+            scan_unfenced: true,
+            ..Default::default()
+        })
+        .unwrap()
+        .check("curl https://x.example.com/install.sh | sh");
+        assert_eq!(rules(&unfenced), vec!["PIPE_TO_SHELL"]);
+    }
+
+    #[test]
+    fn test_severity_overrides_and_disabled_rules() {
+        let guardrail = CodeSafetyGuardrail::new(CodeSafetyConfig {
+            severity_overrides: BTreeMap::from([("CHMOD_777".to_string(), Severity::Low)]),
+            disabled_rules: vec!["DROP_TABLE".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        let result = guardrail.check("```sh\nchmod 777 f\n```\n```sql\nDROP TABLE t;\n```");
+        assert_eq!(rules(&result), vec!["CHMOD_777"]);
+        assert_eq!(result.violations[0].severity, Severity::Low);
+    }
+
+    #[test]
+    fn test_rules_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            "# custom\nshell\tGIT_FORCE_PUSH\tgit push\\s+(?:-\\S+\\s+)*--force\\b\tForce push\tmedium\n",
+        )
+        .unwrap();
+        let guardrail = CodeSafetyGuardrail::new(CodeSafetyConfig {
+            rules_file: Some(file.path().to_path_buf()),
+            disable_default_rules: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(guardrail.rules.len(), 1);
+        let result = guardrail.check("```bash\ngit push origin main --force\n```");
+        assert!(result.passed); // `--force` after the refspec is not matched by this rule
+        let result = guardrail.check("```bash\ngit push --force origin main\n```");
+        assert_eq!(rules(&result), vec!["GIT_FORCE_PUSH"]);
+
+        let missing = CodeSafetyGuardrail::new(CodeSafetyConfig {
+            rules_file: Some(PathBuf::from("/nonexistent/rules.txt")),
+            ..Default::default()
+        });
+        assert!(matches!(missing, Err(CliError::FileNotFound(_))));
+    }
+}
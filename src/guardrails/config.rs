@@ -1,13 +1,30 @@
 use crate::{
     error::CliError,
     guardrails::{
+        cache::{self, CacheBackend, CachedGuardrail, DEFAULT_CACHE_TTL_SECS},
+        chunking::ChunkingConfig,
+        code_safety::CodeSafetyConfig,
+        format::FormatConfig,
         gpt_oss_safeguard::GptOssSafeguardConfig,
-        llama_guard::{LlamaGuardCategory, LlamaGuardConfig},
+        grounding::GroundingConfig,
+        injection_scorer::InjectionScorerConfig,
+        language::LanguageConfig,
+        llama_guard::{LlamaGuardCategory, LlamaGuardConfig, LlamaGuardCustomCategory},
+        llama_prompt_guard::LlamaPromptGuardConfig,
         provider::Severity,
+        safeguard_policy::{compose_policy, PolicyFragment, ReasoningEffort},
+        secrets::SecretsConfig,
+        system_prompt_leak::SystemPromptLeakConfig,
+        topic::{TopicClassifier, TopicConfig},
+        url_policy::UrlPolicyConfig,
     },
 };
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 /// Regex guardrail configuration (unified for both input and output)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +65,11 @@ fn default_prompt_guard_threshold() -> f32 {
     0.5
 }
 
+/// Serde default for options that are enabled unless turned off
+pub(crate) fn default_true() -> bool {
+    true
+}
+
 /// Helper function to resolve API key from either direct value or environment variable
 /// Returns the resolved API key value, or None if neither is specified
 fn resolve_api_key(
@@ -96,7 +118,7 @@ pub enum ExecutionMode {
 }
 
 /// Aggregation mode for composite guardrails
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum AggregationMode {
     /// All providers must say "safe" for overall "safe" (conservative)
@@ -105,6 +127,66 @@ pub enum AggregationMode {
 
     /// Any provider can say "safe" for overall "safe" (permissive)
     AnyCanPass,
+
+    /// More than half of the providers must say "safe" (ties fail)
+    Majority,
+
+    /// At least `n` providers must say "safe"
+    AtLeast(usize),
+
+    /// The weighted share of "safe" votes must reach `threshold` (0.0-1.0)
+    /// Weights come from each provider's `weight` (default: 1.0)
+    Weighted { threshold: f32 },
+}
+
+/// What a composite guardrail does when a nested provider errors or times out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorPolicy {
+    /// Treat the error as a failed check (violation, votes "unsafe")
+    #[default]
+    FailClosed,
+
+    /// Treat the error as a passed check (warning, votes "safe")
+    FailOpen,
+
+    /// Skip the provider's vote and record a warning
+    Warn,
+}
+
+/// A provider nested in a composite guardrail, with its voting weight and error handling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeMemberConfig {
+    /// Voting weight (only used by `AggregationMode::Weighted`)
+    #[serde(default = "default_member_weight")]
+    pub weight: f32,
+
+    /// Behaviour when this provider errors or times out (default: fail_closed)
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+
+    /// Deadline for this provider in the composite, on top of its own `timeout_secs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_override_secs: Option<u64>,
+
+    /// The nested provider configuration
+    #[serde(flatten)]
+    pub provider: GuardrailProviderConfig,
+}
+
+fn default_member_weight() -> f32 {
+    1.0
+}
+
+impl From<GuardrailProviderConfig> for CompositeMemberConfig {
+    fn from(provider: GuardrailProviderConfig) -> Self {
+        Self {
+            weight: default_member_weight(),
+            on_error: ErrorPolicy::default(),
+            timeout_override_secs: None,
+            provider,
+        }
+    }
 }
 
 /// Top-level guardrail configuration
@@ -140,22 +222,44 @@ pub enum GuardrailProviderConfig {
         timeout_secs: u64,
         #[serde(default = "default_llama_guard_categories")]
         enabled_categories: Vec<LlamaGuardCategory>,
+        /// Custom categories rendered into the prompt
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        custom_categories: Vec<LlamaGuardCustomCategory>,
+        /// Severity overrides keyed by category code (default: Critical)
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        category_severities: BTreeMap<String, Severity>,
         #[serde(skip_serializing_if = "Option::is_none")]
         api_key: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         api_key_name: Option<String>,
+        /// Chunking of long content (default: provider-specific)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        chunking: Option<ChunkingConfig>,
     },
 
     /// GPT-OSS-Safeguard (policy-driven reasoning model)
     GptOssSafeguard {
         api_url: String,
         model: String,
+        /// Inline policy text (first fragment of the composed policy)
+        #[serde(default)]
         policy: String,
+        /// Additional policy fragments (files, built-ins, inline text), appended in order
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        policy_fragments: Vec<PolicyFragment>,
+        /// Values for `{{name}}` placeholders in the composed policy
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        policy_variables: BTreeMap<String, String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reasoning_effort: Option<ReasoningEffort>,
         timeout_secs: u64,
         #[serde(skip_serializing_if = "Option::is_none")]
         api_key: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         api_key_name: Option<String>,
+        /// Chunking of long content (default: provider-specific)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        chunking: Option<ChunkingConfig>,
     },
 
     /// Llama Prompt Guard 2 (prompt injection detection, input-only)
@@ -170,22 +274,72 @@ pub enum GuardrailProviderConfig {
         api_key: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         api_key_name: Option<String>,
+        /// Chunking of long content (default: provider-specific)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        chunking: Option<ChunkingConfig>,
     },
 
+    /// Offline heuristic prompt-injection scorer (Llama Prompt Guard-shaped result)
+    InjectionScorer(InjectionScorerConfig),
+
     /// JSON Schema guardrail (validates content against a JSON Schema file)
     JsonSchema {
         /// Path to the JSON Schema file (.json)
         schema_file: PathBuf,
+        /// Extract JSON from fenced or chatty content before validating (default: false)
+        #[serde(default)]
+        lenient: bool,
     },
 
+    /// Format guardrail (YAML, TOML, XML, CSV or Markdown structure)
+    Format(FormatConfig),
+
+    /// Grounding guardrail (checks output against the source document, output-only)
+    Grounding(GroundingConfig),
+
+    /// System prompt leakage guardrail (detects responses echoing the system prompt, output-only)
+    SystemPromptLeak(SystemPromptLeakConfig),
+
+    /// Topic restriction guardrail (allowed/denied topics, input or output)
+    Topic(TopicConfig),
+
+    /// URL and domain policy guardrail (allow/deny lists, exfiltration and spoofing checks)
+    UrlPolicy(UrlPolicyConfig),
+
+    /// Secret and credential scanning (known formats, entropy, allowlist)
+    Secrets(SecretsConfig),
+
+    /// Code and command safety guardrail (dangerous constructs in fenced code blocks)
+    CodeSafety(CodeSafetyConfig),
+
+    /// Offline language identification (allowed languages, mixed-script words)
+    Language(LanguageConfig),
+
     /// Composite guardrail (combines multiple providers)
     Composite {
-        providers: Vec<GuardrailProviderConfig>,
+        providers: Vec<CompositeMemberConfig>,
         execution: ExecutionMode,
         aggregation: AggregationMode,
+        /// Fail if any provider reports a Critical violation, regardless of votes
+        #[serde(default)]
+        fail_on_critical: bool,
+    },
+
+    /// Verdict cache wrapping another provider
+    Cached {
+        provider: Box<GuardrailProviderConfig>,
+        #[serde(default)]
+        backend: CacheBackend,
+        /// Verdict lifetime in seconds (0 = never expires)
+        #[serde(default = "default_cache_ttl_secs")]
+        ttl_secs: u64,
     },
 }
 
+fn default_cache_ttl_secs() -> u64 {
+    DEFAULT_CACHE_TTL_SECS
+}
+
 impl Default for GuardrailProviderConfig {
     fn default() -> Self {
         // Default: Simple regex guardrail with default settings
@@ -210,7 +364,10 @@ impl GuardrailProviderConfig {
                 model,
                 timeout_secs,
                 enabled_categories,
+                custom_categories,
+                category_severities,
                 api_key,
+                chunking,
                 ..
             } => Some(LlamaGuardConfig {
                 api_url: api_url.clone(),
@@ -218,33 +375,120 @@ impl GuardrailProviderConfig {
                 enabled_categories: enabled_categories.clone(),
                 timeout_secs: *timeout_secs,
                 api_key: api_key.clone(),
+                custom_categories: custom_categories.clone(),
+                category_severities: category_severities.clone(),
+                chunking: chunking.unwrap_or_else(LlamaGuardConfig::default_chunking),
             }),
             _ => None,
         }
     }
 
     /// Convert to GptOssSafeguardConfig (if this is a GptOssSafeguard config)
-    pub fn to_gpt_oss_safeguard_config(&self) -> Option<GptOssSafeguardConfig> {
+    ///
+    /// Composes the policy from `policy` and `policy_fragments`, which reads
+    /// policy files and fails on placeholders without a value.
+    pub fn to_gpt_oss_safeguard_config(&self) -> Result<Option<GptOssSafeguardConfig>, CliError> {
         match self {
             Self::GptOssSafeguard {
                 api_url,
                 model,
                 policy,
+                policy_fragments,
+                policy_variables,
+                reasoning_effort,
                 timeout_secs,
                 api_key,
+                chunking,
                 ..
-            } => Some(GptOssSafeguardConfig {
+            } => Ok(Some(GptOssSafeguardConfig {
                 api_url: api_url.clone(),
                 model: model.clone(),
-                policy: policy.clone(),
+                policy: compose_policy(policy, policy_fragments, policy_variables)?,
                 timeout_secs: *timeout_secs,
                 api_key: api_key.clone(),
-            }),
-            _ => None,
+                reasoning_effort: *reasoning_effort,
+                chunking: chunking.unwrap_or_else(GptOssSafeguardConfig::default_chunking),
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Files the provider reads when it is created (patterns, schemas, rules,
+    /// policies, examples, allowlists), including those of nested providers
+    pub fn referenced_files(&self) -> Vec<&Path> {
+        match self {
+            Self::Regex(config) => config.patterns_file.iter().map(PathBuf::as_path).collect(),
+            Self::GptOssSafeguard {
+                policy_fragments, ..
+            } => policy_fragments
+                .iter()
+                .filter_map(|fragment| match fragment {
+                    PolicyFragment::File { path } => Some(path.as_path()),
+                    _ => None,
+                })
+                .collect(),
+            Self::JsonSchema { schema_file, .. } => vec![schema_file.as_path()],
+            Self::Format(config) => config.schema_file.iter().map(PathBuf::as_path).collect(),
+            Self::Topic(config) => config
+                .allowed_topics
+                .iter()
+                .chain(&config.denied_topics)
+                .filter_map(|topic| topic.examples_file.as_deref())
+                .collect(),
+            Self::Secrets(config) => config.allowlist_file.iter().map(PathBuf::as_path).collect(),
+            Self::CodeSafety(config) => config.rules_file.iter().map(PathBuf::as_path).collect(),
+            Self::Composite { providers, .. } => providers
+                .iter()
+                .flat_map(|member| member.provider.referenced_files())
+                .collect(),
+            Self::Cached { provider, .. } => provider.referenced_files(),
+            Self::LlamaGuard { .. }
+            | Self::LlamaPromptGuard { .. }
+            | Self::InjectionScorer(_)
+            | Self::Grounding(_)
+            | Self::SystemPromptLeak(_)
+            | Self::UrlPolicy(_)
+            | Self::Language(_) => Vec::new(),
         }
     }
 }
 
+/// Reject composite settings that would make the vote meaningless or fail open
+fn validate_composite(
+    providers: &[CompositeMemberConfig],
+    aggregation: &AggregationMode,
+) -> Result<(), CliError> {
+    if let Some(member) = providers
+        .iter()
+        .find(|member| !member.weight.is_finite() || member.weight < 0.0)
+    {
+        return Err(CliError::InvalidArguments(format!(
+            "Composite guardrail: provider weight must be a finite, non-negative number (got {})",
+            member.weight
+        )));
+    }
+    match *aggregation {
+        AggregationMode::AtLeast(n) if n == 0 || n > providers.len() => {
+            Err(CliError::InvalidArguments(format!(
+                "Composite guardrail: 'at_least' must be between 1 and the number of providers ({}), got {n}",
+                providers.len()
+            )))
+        }
+        AggregationMode::Weighted { threshold } if !(0.0..=1.0).contains(&threshold) => {
+            Err(CliError::InvalidArguments(format!(
+                "Composite guardrail: weighted 'threshold' must be between 0.0 and 1.0, got {threshold}"
+            )))
+        }
+        AggregationMode::Weighted { .. } if providers.iter().all(|member| member.weight == 0.0) => {
+            Err(CliError::InvalidArguments(
+                "Composite guardrail: weighted aggregation needs at least one provider with a positive weight"
+                    .to_string(),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Factory function to create GuardrailProvider from configuration
 pub fn create_guardrail_provider(
     config: &GuardrailProviderConfig,
@@ -264,8 +508,11 @@ pub fn create_guardrail_provider(
             model,
             timeout_secs,
             enabled_categories,
+            custom_categories,
+            category_severities,
             api_key,
             api_key_name,
+            chunking,
         } => {
             let resolved_api_key = resolve_api_key(api_key, api_key_name, "LlamaGuard")?;
             let llama_config = LlamaGuardConfig {
@@ -274,6 +521,9 @@ pub fn create_guardrail_provider(
                 enabled_categories: enabled_categories.clone(),
                 timeout_secs: *timeout_secs,
                 api_key: resolved_api_key,
+                custom_categories: custom_categories.clone(),
+                category_severities: category_severities.clone(),
+                chunking: chunking.unwrap_or_else(LlamaGuardConfig::default_chunking),
             };
             Ok(Box::new(LlamaGuardProvider::new(llama_config)))
         }
@@ -282,17 +532,23 @@ pub fn create_guardrail_provider(
             api_url,
             model,
             policy,
+            policy_fragments,
+            policy_variables,
+            reasoning_effort,
             timeout_secs,
             api_key,
             api_key_name,
+            chunking,
         } => {
             let resolved_api_key = resolve_api_key(api_key, api_key_name, "GptOssSafeguard")?;
             let gpt_oss_config = GptOssSafeguardConfig {
                 api_url: api_url.clone(),
                 model: model.clone(),
-                policy: policy.clone(),
+                policy: compose_policy(policy, policy_fragments, policy_variables)?,
                 timeout_secs: *timeout_secs,
                 api_key: resolved_api_key,
+                reasoning_effort: *reasoning_effort,
+                chunking: chunking.unwrap_or_else(GptOssSafeguardConfig::default_chunking),
             };
             Ok(Box::new(GptOssSafeguardProvider::new(gpt_oss_config)))
         }
@@ -304,17 +560,18 @@ pub fn create_guardrail_provider(
             threshold,
             api_key,
             api_key_name,
+            chunking,
         } => {
             let resolved_api_key = resolve_api_key(api_key, api_key_name, "LlamaPromptGuard")?;
-            let prompt_guard_config =
-                crate::guardrails::llama_prompt_guard::LlamaPromptGuardConfig {
-                    api_url: api_url.clone(),
-                    model: model.clone(),
-                    timeout_secs: *timeout_secs,
-                    threshold: *threshold,
-                    api_key: resolved_api_key,
-                    api_key_name: None, // Already resolved to api_key
-                };
+            let prompt_guard_config = LlamaPromptGuardConfig {
+                api_url: api_url.clone(),
+                model: model.clone(),
+                timeout_secs: *timeout_secs,
+                threshold: *threshold,
+                api_key: resolved_api_key,
+                api_key_name: None, // Already resolved to api_key
+                chunking: chunking.unwrap_or_else(LlamaPromptGuardConfig::default_chunking),
+            };
             Ok(Box::new(
                 crate::guardrails::llama_prompt_guard::LlamaPromptGuardProvider::new(
                     prompt_guard_config,
@@ -322,24 +579,120 @@ pub fn create_guardrail_provider(
             ))
         }
 
-        GuardrailProviderConfig::JsonSchema { schema_file } => Ok(Box::new(
-            crate::guardrails::json_schema::JsonSchemaGuardrail::new(schema_file.clone())?,
+        GuardrailProviderConfig::InjectionScorer(scorer_config) => Ok(Box::new(
+            crate::guardrails::injection_scorer::InjectionScorerGuardrail::new(
+                scorer_config.clone(),
+            ),
+        )),
+
+        GuardrailProviderConfig::JsonSchema {
+            schema_file,
+            lenient,
+        } => Ok(Box::new(
+            crate::guardrails::json_schema::JsonSchemaGuardrail::new(schema_file.clone())?
+                .with_lenient(*lenient),
+        )),
+
+        GuardrailProviderConfig::Format(format_config) => Ok(Box::new(
+            crate::guardrails::format::FormatGuardrail::new(format_config.clone())?,
+        )),
+
+        GuardrailProviderConfig::Grounding(grounding_config) => {
+            let mut grounding_config = grounding_config.clone();
+            if let Some(entailment) = grounding_config.entailment.as_mut() {
+                entailment.api_key =
+                    resolve_api_key(&entailment.api_key, &entailment.api_key_name, "Grounding")?;
+                entailment.api_key_name = None; // Already resolved to api_key
+            }
+            Ok(Box::new(
+                crate::guardrails::grounding::GroundingGuardrail::new(grounding_config),
+            ))
+        }
+
+        GuardrailProviderConfig::SystemPromptLeak(leak_config) => Ok(Box::new(
+            crate::guardrails::system_prompt_leak::SystemPromptLeakGuardrail::new(
+                leak_config.clone(),
+            ),
+        )),
+
+        GuardrailProviderConfig::Topic(topic_config) => {
+            let mut topic_config = topic_config.clone();
+            if let TopicClassifier::Llm {
+                api_key,
+                api_key_name,
+                ..
+            } = &mut topic_config.classifier
+            {
+                *api_key = resolve_api_key(api_key, api_key_name, "Topic")?;
+                *api_key_name = None; // Already resolved to api_key
+            }
+            Ok(Box::new(crate::guardrails::topic::TopicGuardrail::new(
+                topic_config,
+            )?))
+        }
+
+        GuardrailProviderConfig::UrlPolicy(url_config) => Ok(Box::new(
+            crate::guardrails::url_policy::UrlPolicyGuardrail::new(url_config.clone()),
+        )),
+
+        GuardrailProviderConfig::Secrets(secrets_config) => Ok(Box::new(
+            crate::guardrails::secrets::SecretsGuardrail::new(secrets_config.clone())?,
+        )),
+
+        GuardrailProviderConfig::CodeSafety(code_config) => Ok(Box::new(
+            crate::guardrails::code_safety::CodeSafetyGuardrail::new(code_config.clone())?,
+        )),
+
+        GuardrailProviderConfig::Language(language_config) => Ok(Box::new(
+            crate::guardrails::language::LanguageGuardrail::new(language_config.clone()),
         )),
 
         GuardrailProviderConfig::Composite {
             providers,
             execution,
             aggregation,
+            fail_on_critical,
         } => {
+            validate_composite(providers, aggregation)?;
             // Recursively create all providers
-            let provider_instances: Result<Vec<_>, _> =
-                providers.iter().map(create_guardrail_provider).collect();
+            let provider_instances: Result<Vec<_>, _> = providers
+                .iter()
+                .map(|member| create_guardrail_provider(&member.provider))
+                .collect();
+            let weights = providers.iter().map(|member| member.weight).collect();
+            let error_policies = providers.iter().map(|member| member.on_error).collect();
+            let timeouts = providers
+                .iter()
+                .map(|member| member.timeout_override_secs.map(Duration::from_secs))
+                .collect();
 
-            Ok(Box::new(HybridGuardrail::new(
-                provider_instances?,
-                *execution,
-                *aggregation,
-            )))
+            Ok(Box::new(
+                HybridGuardrail::new(provider_instances?, *execution, *aggregation)
+                    .with_weights(weights)
+                    .with_error_policies(error_policies)
+                    .with_timeouts(timeouts)
+                    .with_fail_on_critical(*fail_on_critical),
+            ))
+        }
+
+        GuardrailProviderConfig::Cached {
+            provider,
+            backend,
+            ttl_secs,
+        } => {
+            // The serialised config and the contents of the files it references identify
+            // the provider, so any config or file change invalidates earlier verdicts
+            let config_json = serde_json::to_string(provider).map_err(|e| {
+                CliError::InvalidArguments(format!("Failed to fingerprint cached provider: {e}"))
+            })?;
+            let fingerprint =
+                cache::config_fingerprint(&config_json, &provider.referenced_files())?;
+            Ok(Box::new(CachedGuardrail::new(
+                create_guardrail_provider(provider)?,
+                fingerprint,
+                backend,
+                Duration::from_secs(*ttl_secs),
+            )?))
         }
     }
 }
@@ -347,6 +700,31 @@ pub fn create_guardrail_provider(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_referenced_files_include_nested_providers() {
+        let config: GuardrailProviderConfig = toml::from_str(
+            r#"
+            type = "cached"
+            [provider]
+            type = "composite"
+            execution = "sequential"
+            aggregation = "all_must_pass"
+            [[provider.providers]]
+            type = "json_schema"
+            schema_file = "schemas/out.json"
+            [[provider.providers]]
+            type = "regex"
+            max_length_bytes = 1024
+            patterns_file = "patterns/out.txt"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.referenced_files(),
+            vec![Path::new("schemas/out.json"), Path::new("patterns/out.txt")]
+        );
+    }
+
     #[test]
     fn test_execution_mode_default() {
         assert_eq!(ExecutionMode::default(), ExecutionMode::Parallel);
@@ -390,8 +768,11 @@ mod tests {
             model: "test-model".to_string(),
             timeout_secs: 60,
             enabled_categories: vec![LlamaGuardCategory::S1, LlamaGuardCategory::S9],
+            custom_categories: vec![],
+            category_severities: BTreeMap::new(),
             api_key: None,
             api_key_name: None,
+            chunking: None,
         };
 
         let llama_config = config.to_llama_guard_config().unwrap();
@@ -430,8 +811,11 @@ mod tests {
             model: "llama-guard3:8b".to_string(),
             timeout_secs: 30,
             enabled_categories: vec![LlamaGuardCategory::S1],
+            custom_categories: vec![],
+            category_severities: BTreeMap::new(),
             api_key: None,
             api_key_name: None,
+            chunking: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -447,18 +831,24 @@ mod tests {
                     max_length_bytes: 1024,
                     patterns_file: None,
                     severity_threshold: Severity::Medium,
-                }),
+                })
+                .into(),
                 GuardrailProviderConfig::LlamaGuard {
                     api_url: "http://localhost:11434".to_string(),
                     model: "llama-guard3:8b".to_string(),
                     timeout_secs: 30,
                     enabled_categories: vec![LlamaGuardCategory::S1],
+                    custom_categories: vec![],
+                    category_severities: BTreeMap::new(),
                     api_key: None,
                     api_key_name: None,
-                },
+                    chunking: None,
+                }
+                .into(),
             ],
             execution: ExecutionMode::Parallel,
             aggregation: AggregationMode::AllMustPass,
+            fail_on_critical: false,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -471,6 +861,7 @@ mod tests {
     fn test_serde_json_schema_config() {
         let config = GuardrailProviderConfig::JsonSchema {
             schema_file: PathBuf::from("schemas/test.json"),
+            lenient: false,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -479,13 +870,175 @@ mod tests {
 
         let deserialized: GuardrailProviderConfig = serde_json::from_str(&json).unwrap();
         match deserialized {
-            GuardrailProviderConfig::JsonSchema { schema_file } => {
+            GuardrailProviderConfig::JsonSchema { schema_file, .. } => {
                 assert_eq!(schema_file, PathBuf::from("schemas/test.json"));
             }
             _ => panic!("Should deserialize to JsonSchema"),
         }
     }
 
+    #[test]
+    fn test_serde_grounding_config_defaults() {
+        let json = r#"{"type": "grounding"}"#;
+        let config: GuardrailProviderConfig = serde_json::from_str(json).unwrap();
+        match config {
+            GuardrailProviderConfig::Grounding(grounding_config) => {
+                assert_eq!(grounding_config.max_unsupported_ratio, 0.2);
+                assert_eq!(grounding_config.min_quote_chars, 12);
+                assert!(grounding_config.entailment.is_none());
+            }
+            _ => panic!("Should deserialize to Grounding"),
+        }
+    }
+
+    #[test]
+    fn test_serde_weighted_composite_config() {
+        let toml_str = r#"
+type = "composite"
+execution = "parallel"
+aggregation = { weighted = { threshold = 0.6 } }
+fail_on_critical = true
+
+[[providers]]
+type = "regex"
+max_length_bytes = 1024
+weight = 2.5
+
+[[providers]]
+type = "grounding"
+"#;
+        let config: GuardrailProviderConfig = toml::from_str(toml_str).unwrap();
+        match config {
+            GuardrailProviderConfig::Composite {
+                providers,
+                aggregation,
+                fail_on_critical,
+                ..
+            } => {
+                assert_eq!(aggregation, AggregationMode::Weighted { threshold: 0.6 });
+                assert!(fail_on_critical);
+                assert_eq!(providers[0].weight, 2.5);
+                assert!(providers[0].provider.as_regex_config().is_some());
+                assert_eq!(providers[1].weight, 1.0); // default
+            }
+            _ => panic!("Should deserialize to Composite"),
+        }
+
+        let at_least: AggregationMode = toml::from_str::<toml::Table>("a = { at_least = 2 }")
+            .unwrap()["a"]
+            .clone()
+            .try_into()
+            .unwrap();
+        assert_eq!(at_least, AggregationMode::AtLeast(2));
+    }
+
+    #[test]
+    fn test_invalid_composite_configs_rejected() {
+        let composite = |aggregation: &str, weights: [&str; 2]| {
+            toml::from_str::<GuardrailProviderConfig>(&format!(
+                r#"
+type = "composite"
+execution = "parallel"
+aggregation = {aggregation}
+
+[[providers]]
+type = "regex"
+max_length_bytes = 1024
+weight = {}
+
+[[providers]]
+type = "regex"
+max_length_bytes = 2048
+weight = {}
+"#,
+                weights[0], weights[1]
+            ))
+            .unwrap()
+        };
+
+        for config in [
+            composite("{ at_least = 0 }", ["1.0", "1.0"]),
+            composite("{ at_least = 3 }", ["1.0", "1.0"]),
+            composite("{ weighted = { threshold = 1.5 } }", ["1.0", "1.0"]),
+            composite("{ weighted = { threshold = -0.1 } }", ["1.0", "1.0"]),
+            composite("{ weighted = { threshold = 0.5 } }", ["0.0", "0.0"]),
+            composite("\"all_must_pass\"", ["-1.0", "1.0"]),
+            composite("\"all_must_pass\"", ["nan", "1.0"]),
+            composite("\"all_must_pass\"", ["inf", "1.0"]),
+        ] {
+            let Err(err) = create_guardrail_provider(&config) else {
+                panic!("expected {config:?} to be rejected");
+            };
+            assert!(matches!(err, CliError::InvalidArguments(_)), "{err:?}");
+        }
+
+        assert!(create_guardrail_provider(&composite("{ at_least = 2 }", ["1.0", "1.0"])).is_ok());
+        assert!(create_guardrail_provider(&composite(
+            "{ weighted = { threshold = 1.0 } }",
+            ["0.0", "2.0"]
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_serde_cached_config() {
+        let toml_str = r#"
+type = "cached"
+ttl_secs = 600
+backend = { type = "disk", path = "/tmp/verdicts" }
+
+[provider]
+type = "regex"
+max_length_bytes = 1024
+"#;
+        let config: GuardrailProviderConfig = toml::from_str(toml_str).unwrap();
+        match config {
+            GuardrailProviderConfig::Cached {
+                provider,
+                backend,
+                ttl_secs,
+            } => {
+                assert!(provider.as_regex_config().is_some());
+                assert_eq!(
+                    backend,
+                    CacheBackend::Disk {
+                        path: PathBuf::from("/tmp/verdicts")
+                    }
+                );
+                assert_eq!(ttl_secs, 600);
+            }
+            _ => panic!("Should deserialize to Cached"),
+        }
+
+        // Defaults: in-memory backend, 24h TTL
+        let config: GuardrailProviderConfig =
+            toml::from_str("type = \"cached\"\n[provider]\ntype = \"grounding\"").unwrap();
+        match config {
+            GuardrailProviderConfig::Cached {
+                backend, ttl_secs, ..
+            } => {
+                assert_eq!(backend, CacheBackend::default());
+                assert_eq!(ttl_secs, DEFAULT_CACHE_TTL_SECS);
+            }
+            _ => panic!("Should deserialize to Cached"),
+        }
+    }
+
+    #[test]
+    fn test_serde_system_prompt_leak_config() {
+        let json = r#"{"type": "system_prompt_leak", "min_leak_words": 5, "severity": "Critical"}"#;
+        let config: GuardrailProviderConfig = serde_json::from_str(json).unwrap();
+        match config {
+            GuardrailProviderConfig::SystemPromptLeak(leak_config) => {
+                assert_eq!(leak_config.min_leak_words, 5);
+                assert_eq!(leak_config.severity, Severity::Critical);
+                assert_eq!(leak_config.ngram_size, 3); // default
+                assert_eq!(leak_config.max_ngram_overlap, 0.5); // default
+            }
+            _ => panic!("Should deserialize to SystemPromptLeak"),
+        }
+    }
+
     #[test]
     fn test_resolve_api_key_direct_value() {
         let result = resolve_api_key(&Some("test-key".to_string()), &None, "TestProvider");
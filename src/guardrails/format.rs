@@ -0,0 +1,775 @@
+//! Document format guardrail (YAML, TOML, XML, CSV, Markdown)
+//!
+//! Sibling of `JsonSchemaGuardrail` for responses in other formats. YAML and
+//! TOML documents are converted to JSON and validated against an optional JSON
+//! Schema; XML is checked for well-formedness and its root element; CSV for its
+//! columns and cell types; Markdown for unclosed fences, ragged tables and
+//! required headings. Parse errors are located by line and column.
+
+use crate::{
+    error::CliError,
+    guardrails::{
+        config::default_true,
+        json_schema::{load_schema_file, schema_violations, MAX_VIOLATIONS},
+        provider::{GuardrailProvider, GuardrailResult, Severity, Violation},
+    },
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Rule name for content that does not parse in the configured format
+pub const RULE_FORMAT_PARSE_ERROR: &str = "FORMAT_PARSE_ERROR";
+/// Rule name for an XML document with an unexpected root element
+pub const RULE_XML_ROOT_MISMATCH: &str = "XML_ROOT_MISMATCH";
+/// Rule name for a required CSV column missing from the header
+pub const RULE_CSV_MISSING_COLUMN: &str = "CSV_MISSING_COLUMN";
+/// Rule name for a CSV column not listed in the configuration
+pub const RULE_CSV_UNEXPECTED_COLUMN: &str = "CSV_UNEXPECTED_COLUMN";
+/// Rule name for a CSV cell that does not match its column type
+pub const RULE_CSV_TYPE_MISMATCH: &str = "CSV_TYPE_MISMATCH";
+/// Rule name for a fenced code block that is never closed
+pub const RULE_MARKDOWN_UNCLOSED_FENCE: &str = "MARKDOWN_UNCLOSED_FENCE";
+/// Rule name for a table row whose cell count differs from the header
+pub const RULE_MARKDOWN_TABLE_COLUMNS: &str = "MARKDOWN_TABLE_COLUMNS";
+/// Rule name for a required heading missing from the document
+pub const RULE_MARKDOWN_MISSING_HEADING: &str = "MARKDOWN_MISSING_HEADING";
+
+/// Document format checked by the format guardrail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentFormat {
+    Yaml,
+    Toml,
+    Xml,
+    Csv,
+    Markdown,
+}
+
+impl DocumentFormat {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Yaml => "YAML",
+            Self::Toml => "TOML",
+            Self::Xml => "XML",
+            Self::Csv => "CSV",
+            Self::Markdown => "Markdown",
+        }
+    }
+}
+
+/// Type of the values in a CSV column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvColumnType {
+    /// Any value
+    #[default]
+    String,
+    Integer,
+    /// Integer or decimal number
+    Number,
+    /// `true` or `false` (case-insensitive)
+    Boolean,
+    /// ISO 8601 date (`YYYY-MM-DD`)
+    Date,
+}
+
+impl CsvColumnType {
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            Self::String => true,
+            Self::Integer => value.parse::<i64>().is_ok(),
+            Self::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            Self::Boolean => {
+                value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false")
+            }
+            Self::Date => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        }
+    }
+}
+
+/// Expected CSV column
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumn {
+    /// Header name
+    pub name: String,
+
+    /// Value type (default: string)
+    #[serde(default, rename = "type")]
+    pub column_type: CsvColumnType,
+
+    /// Column must be present in the header
+    #[serde(default = "default_true")]
+    pub required: bool,
+
+    /// Empty cells are accepted regardless of type
+    #[serde(default)]
+    pub allow_empty: bool,
+}
+
+/// Configuration for the format guardrail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatConfig {
+    /// Expected document format
+    pub format: DocumentFormat,
+
+    /// JSON Schema the parsed document must match (YAML and TOML only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_file: Option<PathBuf>,
+
+    /// Expected name of the root element (XML only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_element: Option<String>,
+
+    /// Expected columns (CSV only); empty = any columns
+    #[serde(default)]
+    pub columns: Vec<CsvColumn>,
+
+    /// Accept columns not listed in `columns` (CSV only)
+    #[serde(default = "default_true")]
+    pub allow_extra_columns: bool,
+
+    /// Field delimiter (CSV only)
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+
+    /// Headings the document must contain, matched case-insensitively (Markdown only)
+    #[serde(default)]
+    pub required_headings: Vec<String>,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+impl FormatConfig {
+    /// Configuration for `format` with every check at its default
+    pub fn new(format: DocumentFormat) -> Self {
+        Self {
+            format,
+            schema_file: None,
+            root_element: None,
+            columns: vec![],
+            allow_extra_columns: true,
+            delimiter: default_delimiter(),
+            required_headings: vec![],
+        }
+    }
+}
+
+/// Format guardrail: parses content in the configured format and validates its structure
+#[derive(Debug)]
+pub struct FormatGuardrail {
+    config: FormatConfig,
+    validator: Option<jsonschema::Validator>,
+}
+
+impl FormatGuardrail {
+    /// Create a format guardrail, compiling the JSON Schema if one is configured
+    ///
+    /// Returns an error if a schema is configured for a format other than YAML
+    /// or TOML, the schema cannot be loaded, or the CSV delimiter is not ASCII.
+    pub fn new(config: FormatConfig) -> Result<Self, CliError> {
+        let validator = match &config.schema_file {
+            Some(schema_file) => {
+                if !matches!(config.format, DocumentFormat::Yaml | DocumentFormat::Toml) {
+                    return Err(CliError::InvalidArguments(format!(
+                        "Format guardrail: schema_file is only supported for YAML and TOML, not {}",
+                        config.format.label()
+                    )));
+                }
+                Some(load_schema_file(schema_file, "Format guardrail")?)
+            }
+            None => None,
+        };
+        if !config.delimiter.is_ascii() {
+            return Err(CliError::InvalidArguments(format!(
+                "Format guardrail: CSV delimiter must be an ASCII character, got '{}'",
+                config.delimiter
+            )));
+        }
+        Ok(Self { config, validator })
+    }
+
+    fn check(&self, content: &str) -> Vec<Violation> {
+        if content.trim().is_empty() {
+            return vec![violation(
+                RULE_FORMAT_PARSE_ERROR,
+                format!("Content is empty (expected {})", self.config.format.label()),
+                None,
+            )];
+        }
+        match self.config.format {
+            DocumentFormat::Yaml => self.check_structured(parse_yaml(content)),
+            DocumentFormat::Toml => self.check_structured(parse_toml(content)),
+            DocumentFormat::Xml => self.check_xml(content),
+            DocumentFormat::Csv => self.check_csv(content),
+            DocumentFormat::Markdown => self.check_markdown(content),
+        }
+    }
+
+    /// Validate a YAML or TOML document converted to JSON
+    fn check_structured(&self, parsed: Result<serde_json::Value, Violation>) -> Vec<Violation> {
+        match (parsed, &self.validator) {
+            (Err(parse_error), _) => vec![parse_error],
+            (Ok(value), Some(validator)) => schema_violations(validator.iter_errors(&value)),
+            (Ok(_), None) => vec![],
+        }
+    }
+
+    fn check_xml(&self, content: &str) -> Vec<Violation> {
+        let document = match roxmltree::Document::parse(content) {
+            Ok(document) => document,
+            Err(e) => {
+                let pos = e.pos();
+                return vec![violation(
+                    RULE_FORMAT_PARSE_ERROR,
+                    format!("Content is not valid XML: {e}"),
+                    Some(line_column(pos.row as usize, pos.col as usize)),
+                )];
+            }
+        };
+        let root = document.root_element();
+        match &self.config.root_element {
+            Some(expected) if root.tag_name().name() != expected => {
+                let pos = document.text_pos_at(root.range().start);
+                vec![violation(
+                    RULE_XML_ROOT_MISMATCH,
+                    format!(
+                        "Root element is <{}>, expected <{expected}>",
+                        root.tag_name().name()
+                    ),
+                    Some(line_column(pos.row as usize, pos.col as usize)),
+                )]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn check_csv(&self, content: &str) -> Vec<Violation> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.config.delimiter as u8)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+
+        let headers = match reader.headers() {
+            Ok(headers) => headers.clone(),
+            Err(e) => return vec![csv_parse_error(&e)],
+        };
+        let header_names: Vec<&str> = headers.iter().map(str::trim).collect();
+
+        let mut violations = vec![];
+        for column in &self.config.columns {
+            if column.required && !header_names.contains(&column.name.as_str()) {
+                violations.push(violation(
+                    RULE_CSV_MISSING_COLUMN,
+                    format!("Required column '{}' is missing", column.name),
+                    Some("Line 1".to_string()),
+                ));
+            }
+        }
+        if !self.config.allow_extra_columns && !self.config.columns.is_empty() {
+            for (idx, name) in header_names.iter().enumerate() {
+                if !self.config.columns.iter().any(|c| c.name == *name) {
+                    violations.push(violation(
+                        RULE_CSV_UNEXPECTED_COLUMN,
+                        format!("Unexpected column '{name}'"),
+                        Some(format!("Line 1, field {}", idx + 1)),
+                    ));
+                }
+            }
+        }
+
+        // Column type of each header position
+        let typed: Vec<Option<&CsvColumn>> = header_names
+            .iter()
+            .map(|name| self.config.columns.iter().find(|c| c.name == *name))
+            .collect();
+        let mut mismatches = vec![];
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    violations.push(csv_parse_error(&e));
+                    return violations;
+                }
+            };
+            let line = record.position().map_or(0, |p| p.line());
+            if record.len() != headers.len() {
+                violations.push(violation(
+                    RULE_FORMAT_PARSE_ERROR,
+                    format!(
+                        "Row has {} fields, header has {}",
+                        record.len(),
+                        headers.len()
+                    ),
+                    Some(format!("Line {line}")),
+                ));
+                continue;
+            }
+            for (idx, value) in record.iter().enumerate() {
+                let Some(column) = typed[idx] else {
+                    continue;
+                };
+                let value = value.trim();
+                if value.is_empty() && column.allow_empty {
+                    continue;
+                }
+                if !column.column_type.accepts(value) {
+                    mismatches.push(violation(
+                        RULE_CSV_TYPE_MISMATCH,
+                        format!(
+                            "Value '{value}' in column '{}' is not of type {:?}",
+                            column.name, column.column_type
+                        ),
+                        Some(format!("Line {line}, field {}", idx + 1)),
+                    ));
+                }
+            }
+        }
+
+        let total = mismatches.len();
+        violations.extend(mismatches.into_iter().take(MAX_VIOLATIONS));
+        if total > MAX_VIOLATIONS {
+            violations.push(Violation {
+                severity: Severity::Medium,
+                ..violation(
+                    RULE_CSV_TYPE_MISMATCH,
+                    format!(
+                        "... and {} more type mismatches (showing first {MAX_VIOLATIONS} of {total})",
+                        total - MAX_VIOLATIONS
+                    ),
+                    None,
+                )
+            });
+        }
+        violations
+    }
+
+    fn check_markdown(&self, content: &str) -> Vec<Violation> {
+        let mut violations = vec![];
+        let mut headings: Vec<String> = vec![];
+        // Opening fence: line number, fence character and length
+        let mut fence: Option<(usize, char, usize)> = None;
+        // Cell count of the current table's header
+        let mut table_columns: Option<usize> = None;
+        let lines: Vec<&str> = content.lines().collect();
+
+        for (idx, line) in lines.iter().enumerate() {
+            let line_number = idx + 1;
+            let trimmed = line.trim_start();
+
+            if let Some((marker, length)) = fence_marker(trimmed) {
+                match fence {
+                    None => fence = Some((line_number, marker, length)),
+                    Some((_, open_marker, open_length))
+                        if marker == open_marker
+                            && length >= open_length
+                            && trimmed.trim_start_matches(marker).trim().is_empty() =>
+                    {
+                        fence = None
+                    }
+                    Some(_) => {}
+                }
+                table_columns = None;
+                continue;
+            }
+            if fence.is_some() {
+                continue;
+            }
+
+            if let Some(heading) = heading_text(trimmed) {
+                headings.push(heading.to_lowercase());
+            }
+
+            if !trimmed.starts_with('|') {
+                table_columns = None;
+                continue;
+            }
+            let cells = table_cells(trimmed);
+            match table_columns {
+                Some(expected) if cells != expected => violations.push(violation(
+                    RULE_MARKDOWN_TABLE_COLUMNS,
+                    format!("Table row has {cells} cells, header has {expected}"),
+                    Some(format!("Line {line_number}")),
+                )),
+                Some(_) => {}
+                // A header row is followed by a delimiter row (| --- | :---: |)
+                None if lines
+                    .get(idx + 1)
+                    .is_some_and(|next| is_table_delimiter(next.trim())) =>
+                {
+                    table_columns = Some(cells)
+                }
+                None => {}
+            }
+        }
+
+        if let Some((line_number, marker, length)) = fence {
+            violations.push(violation(
+                RULE_MARKDOWN_UNCLOSED_FENCE,
+                format!(
+                    "Code block opened with {} is never closed",
+                    marker.to_string().repeat(length)
+                ),
+                Some(format!("Line {line_number}")),
+            ));
+        }
+        for required in &self.config.required_headings {
+            if !headings.contains(&required.trim().to_lowercase()) {
+                violations.push(violation(
+                    RULE_MARKDOWN_MISSING_HEADING,
+                    format!("Required heading '{required}' is missing"),
+                    None,
+                ));
+            }
+        }
+        violations
+    }
+}
+
+#[async_trait]
+impl GuardrailProvider for FormatGuardrail {
+    async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        let violations = self.check(content);
+        Ok(GuardrailResult::without_quality_score(
+            violations.is_empty(),
+            violations,
+            vec![],
+        ))
+    }
+
+    fn name(&self) -> &str {
+        "FormatGuardrail"
+    }
+}
+
+fn violation(rule: &str, message: String, location: Option<String>) -> Violation {
+    Violation {
+        rule: rule.to_string(),
+        severity: Severity::High,
+        message,
+        location,
+        provider: None,
+    }
+}
+
+fn line_column(line: usize, column: usize) -> String {
+    format!("Line {line}, column {column}")
+}
+
+/// 1-based line and column (in characters) of a byte offset
+fn offset_line_column(content: &str, offset: usize) -> String {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    line_column(line, before[line_start..].chars().count() + 1)
+}
+
+fn parse_yaml(content: &str) -> Result<serde_json::Value, Violation> {
+    serde_norway::from_str(content).map_err(|e| {
+        violation(
+            RULE_FORMAT_PARSE_ERROR,
+            format!("Content is not valid YAML: {e}"),
+            e.location().map(|l| line_column(l.line(), l.column())),
+        )
+    })
+}
+
+fn parse_toml(content: &str) -> Result<serde_json::Value, Violation> {
+    let table: toml::Table = toml::from_str(content).map_err(|e| {
+        violation(
+            RULE_FORMAT_PARSE_ERROR,
+            format!("Content is not valid TOML: {}", e.message()),
+            e.span().map(|span| offset_line_column(content, span.start)),
+        )
+    })?;
+    Ok(toml_to_json(toml::Value::Table(table)))
+}
+
+/// TOML to JSON (dates and times become RFC 3339 strings)
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(s) => serde_json::Value::String(s),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => f.into(),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(d) => serde_json::Value::String(d.to_string()),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => serde_json::Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn csv_parse_error(e: &csv::Error) -> Violation {
+    violation(
+        RULE_FORMAT_PARSE_ERROR,
+        format!("Content is not valid CSV: {e}"),
+        e.position().map(|p| format!("Line {}", p.line())),
+    )
+}
+
+/// Fence character and length of a code fence line (``` or ~~~, at least three)
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let marker = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let length = line.chars().take_while(|c| *c == marker).count();
+    (length >= 3).then_some((marker, length))
+}
+
+/// Text of an ATX heading (`# Title` to `###### Title`)
+fn heading_text(line: &str) -> Option<String> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim().to_string())
+}
+
+/// Number of cells in a table row (escaped pipes do not separate cells)
+fn table_cells(row: &str) -> usize {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = if row.ends_with('|') && !row.ends_with("\\|") {
+        &row[..row.len() - 1]
+    } else {
+        row
+    };
+    let mut cells = 1;
+    let mut escaped = false;
+    for c in row.chars() {
+        match c {
+            '\\' => escaped = !escaped,
+            '|' if !escaped => cells += 1,
+            _ => escaped = false,
+        }
+    }
+    cells
+}
+
+fn is_table_delimiter(line: &str) -> bool {
+    line.contains('-')
+        && line.contains('|')
+        && line
+            .chars()
+            .all(|c| matches!(c, '|' | '-' | ':' | ' ' | '\t'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn check(config: FormatConfig, content: &str) -> Vec<Violation> {
+        FormatGuardrail::new(config).unwrap().check(content)
+    }
+
+    fn schema_file(schema: &str) -> tempfile::NamedTempFile {
+        let mut temp = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        temp.write_all(schema.as_bytes()).unwrap();
+        temp.flush().unwrap();
+        temp
+    }
+
+    const SERVICE_SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["name", "replicas"],
+        "properties": {
+            "name": { "type": "string" },
+            "replicas": { "type": "integer", "minimum": 1 }
+        }
+    }"#;
+
+    #[test]
+    fn test_yaml_against_schema() {
+        let schema = schema_file(SERVICE_SCHEMA);
+        let config = FormatConfig {
+            schema_file: Some(schema.path().to_path_buf()),
+            ..FormatConfig::new(DocumentFormat::Yaml)
+        };
+
+        assert!(check(config.clone(), "name: api\nreplicas: 3\n").is_empty());
+
+        let violations = check(config, "name: api\nreplicas: 0\n");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, "JSON_SCHEMA_VIOLATION");
+        assert_eq!(violations[0].location.as_deref(), Some("/replicas"));
+    }
+
+    #[test]
+    fn test_yaml_parse_error_location() {
+        let violations = check(
+            FormatConfig::new(DocumentFormat::Yaml),
+            "name: api\nports:\n  - 80\n bad: [\n",
+        );
+        assert_eq!(violations[0].rule, RULE_FORMAT_PARSE_ERROR);
+        assert!(violations[0]
+            .location
+            .as_deref()
+            .unwrap()
+            .starts_with("Line 4, column"));
+    }
+
+    #[test]
+    fn test_toml_against_schema() {
+        let schema = schema_file(SERVICE_SCHEMA);
+        let config = FormatConfig {
+            schema_file: Some(schema.path().to_path_buf()),
+            ..FormatConfig::new(DocumentFormat::Toml)
+        };
+
+        assert!(check(config.clone(), "name = \"api\"\nreplicas = 2\n").is_empty());
+
+        let violations = check(config.clone(), "name = \"api\"\nreplicas = \"two\"\n");
+        assert_eq!(violations[0].rule, "JSON_SCHEMA_VIOLATION");
+
+        let violations = check(config, "name = \"api\"\nreplicas = \n");
+        assert_eq!(violations[0].rule, RULE_FORMAT_PARSE_ERROR);
+        assert_eq!(violations[0].location.as_deref(), Some("Line 2, column 12"));
+    }
+
+    #[test]
+    fn test_toml_datetime_becomes_string() {
+        let value = parse_toml("released = 2024-05-01T10:00:00Z").unwrap();
+        assert_eq!(value["released"], "2024-05-01T10:00:00Z");
+    }
+
+    #[test]
+    fn test_xml() {
+        let config = FormatConfig {
+            root_element: Some("invoice".to_string()),
+            ..FormatConfig::new(DocumentFormat::Xml)
+        };
+        assert!(check(config.clone(), "<invoice><total>10</total></invoice>").is_empty());
+
+        let violations = check(config.clone(), "<invoice>\n  <total>10</totl>\n</invoice>");
+        assert_eq!(violations[0].rule, RULE_FORMAT_PARSE_ERROR);
+        assert!(violations[0]
+            .location
+            .as_deref()
+            .unwrap()
+            .starts_with("Line 2, column"));
+
+        let violations = check(config, "<?xml version=\"1.0\"?>\n<order/>");
+        assert_eq!(violations[0].rule, RULE_XML_ROOT_MISMATCH);
+        assert_eq!(violations[0].location.as_deref(), Some("Line 2, column 1"));
+    }
+
+    #[test]
+    fn test_csv_columns_and_types() {
+        let config = FormatConfig {
+            columns: vec![
+                CsvColumn {
+                    name: "id".to_string(),
+                    column_type: CsvColumnType::Integer,
+                    required: true,
+                    allow_empty: false,
+                },
+                CsvColumn {
+                    name: "price".to_string(),
+                    column_type: CsvColumnType::Number,
+                    required: true,
+                    allow_empty: true,
+                },
+                CsvColumn {
+                    name: "released".to_string(),
+                    column_type: CsvColumnType::Date,
+                    required: true,
+                    allow_empty: false,
+                },
+            ],
+            allow_extra_columns: false,
+            ..FormatConfig::new(DocumentFormat::Csv)
+        };
+
+        let valid = "id,price,released\n1,9.99,2024-01-31\n2,,2024-02-29\n";
+        assert!(check(config.clone(), valid).is_empty());
+
+        let invalid = "id,price,released,notes\n1,cheap,2024-13-01,x\n2,5\n";
+        let violations = check(config, invalid);
+        let rules: Vec<(&str, Option<&str>)> = violations
+            .iter()
+            .map(|v| (v.rule.as_str(), v.location.as_deref()))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (RULE_CSV_UNEXPECTED_COLUMN, Some("Line 1, field 4")),
+                (RULE_FORMAT_PARSE_ERROR, Some("Line 3")),
+                (RULE_CSV_TYPE_MISMATCH, Some("Line 2, field 2")),
+                (RULE_CSV_TYPE_MISMATCH, Some("Line 2, field 3")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_missing_column_and_row_length() {
+        let config = FormatConfig {
+            columns: vec![CsvColumn {
+                name: "email".to_string(),
+                column_type: CsvColumnType::String,
+                required: true,
+                allow_empty: false,
+            }],
+            delimiter: ';',
+            ..FormatConfig::new(DocumentFormat::Csv)
+        };
+        let violations = check(config, "name;age\nAda;36\nAlan\n");
+        let rules: Vec<&str> = violations.iter().map(|v| v.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            vec![RULE_CSV_MISSING_COLUMN, RULE_FORMAT_PARSE_ERROR]
+        );
+        assert_eq!(violations[1].location.as_deref(), Some("Line 3"));
+    }
+
+    #[test]
+    fn test_markdown() {
+        let config = FormatConfig {
+            required_headings: vec!["Summary".to_string(), "Next steps".to_string()],
+            ..FormatConfig::new(DocumentFormat::Markdown)
+        };
+        let valid = "# Summary\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n## Next Steps\n\n```sh\necho | cat\n```\n";
+        assert!(check(config.clone(), valid).is_empty());
+
+        let invalid = "# Summary\n\n| a | b |\n|---|---|\n| 1 | 2 | 3 |\n\n```python\nprint('x')\n";
+        let violations = check(config, invalid);
+        let rules: Vec<(&str, Option<&str>)> = violations
+            .iter()
+            .map(|v| (v.rule.as_str(), v.location.as_deref()))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (RULE_MARKDOWN_TABLE_COLUMNS, Some("Line 5")),
+                (RULE_MARKDOWN_UNCLOSED_FENCE, Some("Line 7")),
+                (RULE_MARKDOWN_MISSING_HEADING, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_content() {
+        let violations = check(FormatConfig::new(DocumentFormat::Yaml), "  \n");
+        assert_eq!(violations[0].rule, RULE_FORMAT_PARSE_ERROR);
+        assert!(violations[0].message.contains("expected YAML"));
+    }
+
+    #[test]
+    fn test_schema_only_for_yaml_and_toml() {
+        let schema = schema_file(SERVICE_SCHEMA);
+        let config = FormatConfig {
+            schema_file: Some(schema.path().to_path_buf()),
+            ..FormatConfig::new(DocumentFormat::Csv)
+        };
+        let err = FormatGuardrail::new(config).unwrap_err().to_string();
+        assert!(err.contains("only supported for YAML and TOML"));
+    }
+}
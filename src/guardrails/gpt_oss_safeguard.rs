@@ -1,8 +1,12 @@
 use crate::{
     client::LlmClient,
     error::CliError,
-    guardrails::provider::{
-        GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity, Violation,
+    guardrails::{
+        chunking::{validate_in_chunks, ChunkingConfig},
+        provider::{
+            GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity, Violation,
+        },
+        safeguard_policy::{policy_hash, BuiltinPolicy, ReasoningEffort},
     },
     provider::InvokeParams,
 };
@@ -27,6 +31,12 @@ pub struct GptOssSafeguardConfig {
     pub policy: String, // Full policy text as system prompt
     pub timeout_secs: u64,
     pub api_key: Option<String>,
+    /// Reasoning effort requested from the model (default: model's own)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Chunking of long content (default: 16384-token chunks, 512-token overlap)
+    #[serde(default = "GptOssSafeguardConfig::default_chunking")]
+    pub chunking: ChunkingConfig,
 }
 
 impl Default for GptOssSafeguardConfig {
@@ -37,18 +47,24 @@ impl Default for GptOssSafeguardConfig {
             policy: Self::mlcommons_taxonomy_policy(),
             timeout_secs: 30,
             api_key: None,
+            reasoning_effort: None,
+            chunking: Self::default_chunking(),
         }
     }
 }
 
 impl GptOssSafeguardConfig {
+    /// Leaves room for the policy and the reasoning output
+    pub fn default_chunking() -> ChunkingConfig {
+        ChunkingConfig::new(16384, 512)
+    }
+
     /// MLCommons taxonomy policy (S1-S13) - mirrors Llama Guard 3 categories
     /// This makes GPT-OSS-Safeguard behave like Llama Guard 3
     ///
     /// Policy is loaded from external file at compile time for easier maintenance.
     pub fn mlcommons_taxonomy_policy() -> String {
-        const POLICY: &str = include_str!("policies/mlcommons_taxonomy_policy.txt");
-        POLICY.to_string()
+        BuiltinPolicy::MlcommonsTaxonomy.text()
     }
 }
 
@@ -56,12 +72,47 @@ impl GptOssSafeguardConfig {
 pub struct GptOssSafeguardProvider {
     client: LlmClient,
     config: GptOssSafeguardConfig,
+    system_prompt: String,
+    policy_hash: String,
 }
 
 impl GptOssSafeguardProvider {
     pub fn new(config: GptOssSafeguardConfig) -> Self {
         let client = LlmClient::new(config.api_url.clone(), None);
-        Self { client, config }
+        // gpt-oss reads the reasoning effort from the system prompt
+        let system_prompt = match config.reasoning_effort {
+            Some(effort) => format!("Reasoning: {effort}\n\n{}", config.policy),
+            None => config.policy.clone(),
+        };
+        let policy_hash = policy_hash(&config.policy);
+        Self {
+            client,
+            config,
+            system_prompt,
+            policy_hash,
+        }
+    }
+
+    /// Classify a single chunk against the policy
+    async fn classify(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        // GPT-OSS-Safeguard requires policy as system prompt and content as user prompt
+        let response = self
+            .client
+            .invoke(InvokeParams {
+                model: &self.config.model,
+                system_prompt: &self.system_prompt, // Policy in system prompt
+                user_prompt: content,               // Content to evaluate in user prompt
+                temperature: 0.0, // Temperature 0 for deterministic classification
+                max_tokens: Some(300), // Longer than Llama Guard (JSON output needs more tokens)
+                seed: None,       // No seed needed for guardrails
+                api_key: self.config.api_key.as_deref(),
+                timeout_secs: self.config.timeout_secs,
+                response_format: None, // No response_format needed for guardrails
+                history: &[],
+            })
+            .await?;
+
+        self.parse_json_response(&response)
     }
 
     /// Parse GPT-OSS-Safeguard JSON response
@@ -88,6 +139,7 @@ impl GptOssSafeguardProvider {
                     .clone()
                     .unwrap_or_else(|| "Policy violation detected".to_string()),
                 location: None,
+                provider: None,
             }]
         } else {
             vec![]
@@ -99,6 +151,7 @@ impl GptOssSafeguardProvider {
             category: json.category.clone(),
             rationale: json.rationale.clone(),
             raw_response: response.to_string(),
+            policy_hash: Some(self.policy_hash.clone()),
         };
 
         Ok(GuardrailResult {
@@ -107,6 +160,8 @@ impl GptOssSafeguardProvider {
             warnings: vec![],
             quality_score: None, // GPT-OSS-Safeguard is binary (no confidence scores)
             provider_specific: Some(ProviderSpecificResult::GptOssSafeguard(gpt_oss_result)),
+            children: vec![],
+            cache_hit: false,
         })
     }
 }
@@ -114,23 +169,7 @@ impl GptOssSafeguardProvider {
 #[async_trait]
 impl GuardrailProvider for GptOssSafeguardProvider {
     async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
-        // GPT-OSS-Safeguard requires policy as system prompt and content as user prompt
-        let response = self
-            .client
-            .invoke(InvokeParams {
-                model: &self.config.model,
-                system_prompt: &self.config.policy, // Policy in system prompt
-                user_prompt: content,               // Content to evaluate in user prompt
-                temperature: 0.0, // Temperature 0 for deterministic classification
-                max_tokens: Some(300), // Longer than Llama Guard (JSON output needs more tokens)
-                seed: None,       // No seed needed for guardrails
-                api_key: self.config.api_key.as_deref(),
-                timeout_secs: self.config.timeout_secs,
-                response_format: None, // No response_format needed for guardrails
-            })
-            .await?;
-
-        self.parse_json_response(&response)
+        validate_in_chunks(content, &self.config.chunking, |chunk| self.classify(chunk)).await
     }
 
     fn name(&self) -> &str {
@@ -207,6 +246,36 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_result_records_policy_hash() {
+        let config = GptOssSafeguardConfig {
+            policy: "Custom policy".to_string(),
+            ..Default::default()
+        };
+        let provider = GptOssSafeguardProvider::new(config);
+
+        let result = provider.parse_json_response(r#"{"violation": 0}"#).unwrap();
+        match result.provider_specific {
+            Some(ProviderSpecificResult::GptOssSafeguard(r)) => {
+                assert_eq!(r.policy_hash, Some(policy_hash("Custom policy")));
+            }
+            other => panic!("Expected GptOssSafeguard result, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_reasoning_effort_in_system_prompt() {
+        let config = GptOssSafeguardConfig {
+            policy: "Custom policy".to_string(),
+            reasoning_effort: Some(ReasoningEffort::High),
+            ..Default::default()
+        };
+        let provider = GptOssSafeguardProvider::new(config);
+        assert_eq!(provider.system_prompt, "Reasoning: high\n\nCustom policy");
+        // The hash covers the policy only
+        assert_eq!(provider.policy_hash, policy_hash("Custom policy"));
+    }
+
     #[test]
     fn test_mlcommons_policy_contains_all_categories() {
         let policy = GptOssSafeguardConfig::mlcommons_taxonomy_policy();
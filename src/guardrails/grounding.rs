@@ -0,0 +1,673 @@
+use crate::{
+    client::LlmClient,
+    error::CliError,
+    guardrails::{
+        chunking::ChunkingConfig,
+        hybrid::RULE_PROVIDER_ERROR,
+        provider::{
+            GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity,
+            ValidationContext, Violation,
+        },
+        text::{is_content_word, tokenize},
+    },
+    provider::InvokeParams,
+};
+use async_trait::async_trait;
+use futures::stream::{FuturesOrdered, StreamExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Rule name when the share of unsupported statements exceeds the threshold
+pub const RULE_UNGROUNDED_CONTENT: &str = "UNGROUNDED_CONTENT";
+/// Rule name for an individual statement not supported by the source
+pub const RULE_UNSUPPORTED_STATEMENT: &str = "UNSUPPORTED_STATEMENT";
+/// Rule name for a quoted span that does not appear verbatim in the source
+pub const RULE_UNVERIFIED_QUOTE: &str = "UNVERIFIED_QUOTE";
+/// Rule name when no source document is available to check against
+pub const RULE_NO_SOURCE_CONTEXT: &str = "NO_SOURCE_CONTEXT";
+
+/// Minimum number of content words for a sentence to count as a checkable statement.
+/// Shorter fragments ("Sure!", "In summary:") carry no factual claim.
+const MIN_STATEMENT_WORDS: usize = 3;
+
+/// Words that frame a claim about the source rather than carry it, ignored
+/// when measuring lexical overlap (whitespace-separated)
+const EXTRA_STOPWORDS: &str = "document according states stated mentions mentioned says";
+
+/// Quoted spans: straight double quotes or typographic quotes
+static QUOTE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#""([^"\n]+)"|“([^”\n]+)”"#).expect("valid quote regex"));
+
+/// Entailment prompt for the optional LLM check
+const ENTAILMENT_SYSTEM_PROMPT: &str = "You are a fact-checking assistant. \
+Determine whether the CLAIM is fully supported by the SOURCE document. \
+Use only the SOURCE; do not rely on outside knowledge. \
+Answer with exactly one word: SUPPORTED or UNSUPPORTED.";
+
+/// Optional LLM-based entailment check for statements the lexical check cannot confirm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundingEntailmentConfig {
+    /// API endpoint (Ollama or OpenAI-compatible)
+    pub api_url: String,
+
+    /// Model used to judge entailment
+    pub model: String,
+
+    /// Request timeout in seconds
+    #[serde(default = "default_entailment_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Optional API key for authenticated endpoints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+
+    /// Optional API key environment variable name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_name: Option<String>,
+
+    /// Maximum number of statements judged concurrently
+    #[serde(default = "default_entailment_max_parallel")]
+    pub max_parallel: usize,
+
+    /// Size of the source excerpt sent with each statement in (estimated) tokens;
+    /// 0 sends the whole source
+    #[serde(default = "default_entailment_max_source_tokens")]
+    pub max_source_tokens: usize,
+}
+
+fn default_entailment_timeout_secs() -> u64 {
+    30
+}
+
+fn default_entailment_max_parallel() -> usize {
+    4
+}
+
+fn default_entailment_max_source_tokens() -> usize {
+    4096
+}
+
+/// Configuration for the grounding guardrail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundingConfig {
+    /// Maximum fraction of unsupported statements (0.0-1.0) before validation fails
+    #[serde(default = "default_max_unsupported_ratio")]
+    pub max_unsupported_ratio: f32,
+
+    /// Minimum fraction of a statement's content words found in the source
+    /// for the statement to count as supported (0.0-1.0)
+    #[serde(default = "default_min_token_overlap")]
+    pub min_token_overlap: f32,
+
+    /// Minimum length (characters) of a quoted span to be verified verbatim
+    #[serde(default = "default_min_quote_chars")]
+    pub min_quote_chars: usize,
+
+    /// Optional LLM entailment check (only consulted for statements the lexical check rejects)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entailment: Option<GroundingEntailmentConfig>,
+}
+
+fn default_max_unsupported_ratio() -> f32 {
+    0.2
+}
+
+fn default_min_token_overlap() -> f32 {
+    0.6
+}
+
+fn default_min_quote_chars() -> usize {
+    12
+}
+
+impl Default for GroundingConfig {
+    fn default() -> Self {
+        Self {
+            max_unsupported_ratio: default_max_unsupported_ratio(),
+            min_token_overlap: default_min_token_overlap(),
+            min_quote_chars: default_min_quote_chars(),
+            entailment: None,
+        }
+    }
+}
+
+/// Grounding specific result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundingResult {
+    /// Number of checkable statements found in the content
+    pub total_statements: usize,
+    /// Number of statements not supported by the source
+    pub unsupported_statements: usize,
+    /// unsupported_statements / total_statements (0.0 when there are no statements)
+    pub unsupported_ratio: f32,
+    /// Number of quoted spans found verbatim in the source
+    pub verified_quotes: usize,
+    /// Number of quoted spans not found in the source
+    pub unverified_quotes: usize,
+    /// Whether the LLM entailment check was consulted
+    pub entailment_checked: bool,
+}
+
+/// A sentence-level statement extracted from the content
+#[derive(Debug, Clone)]
+struct Statement<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+/// Grounding guardrail: verifies that a response is supported by its source document
+///
+/// Output-only. Requires `ValidationContext::source_text`; without it the
+/// guardrail passes with a warning since there is nothing to check against.
+pub struct GroundingGuardrail {
+    config: GroundingConfig,
+    client: Option<LlmClient>,
+}
+
+impl GroundingGuardrail {
+    pub fn new(config: GroundingConfig) -> Self {
+        let client = config
+            .entailment
+            .as_ref()
+            .map(|e| LlmClient::new(e.api_url.clone(), None));
+        Self { config, client }
+    }
+
+    /// Check content against the source document
+    async fn check(&self, content: &str, source: &str) -> Result<GuardrailResult, CliError> {
+        let source_normalized = normalize(source);
+        let source_tokens: HashSet<String> = tokenize(source).collect();
+
+        let mut violations = Vec::new();
+        let mut warnings = Vec::new();
+
+        // 1. Exact quote verification (always local)
+        let mut verified_quotes = 0;
+        let mut unverified_quotes = 0;
+        for caps in QUOTE_REGEX.captures_iter(content) {
+            let Some(quote) = caps.get(1).or_else(|| caps.get(2)) else {
+                continue;
+            };
+            if quote.as_str().trim().chars().count() < self.config.min_quote_chars {
+                continue;
+            }
+            if source_normalized.contains(&normalize(quote.as_str())) {
+                verified_quotes += 1;
+            } else {
+                unverified_quotes += 1;
+                violations.push(Violation {
+                    rule: RULE_UNVERIFIED_QUOTE.to_string(),
+                    severity: Severity::High,
+                    message: format!(
+                        "Quoted text not found in source document: \"{}\"",
+                        truncate_for_message(quote.as_str())
+                    ),
+                    location: Some(format!("Position {}", quote.start())),
+                    provider: None,
+                });
+            }
+        }
+
+        // 2. Statement support (lexical overlap, optionally confirmed by LLM entailment)
+        let statements = extract_statements(content);
+        let mut unsupported: Vec<&Statement> = statements
+            .iter()
+            .filter(|s| !self.is_lexically_supported(s.text, &source_tokens))
+            .collect();
+
+        let entailment_checked = self.client.is_some() && !unsupported.is_empty();
+        if let (true, Some(entailment)) = (entailment_checked, &self.config.entailment) {
+            // Each statement is judged against the source excerpt sharing the most words with it
+            let excerpts: Vec<(&str, HashSet<String>)> = ChunkingConfig::new(
+                entailment.max_source_tokens,
+                entailment.max_source_tokens / 8,
+            )
+            .split(source)
+            .into_iter()
+            .map(|(_, excerpt)| (excerpt, tokenize(excerpt).collect()))
+            .collect();
+            // Keep at most `max_parallel` judgements in flight, in statement order
+            let max_parallel = entailment.max_parallel.max(1);
+            let mut pending = unsupported.iter();
+            let mut in_flight = FuturesOrdered::new();
+            let mut verdicts = Vec::with_capacity(unsupported.len());
+            loop {
+                while in_flight.len() < max_parallel {
+                    let Some(statement) = pending.next() else {
+                        break;
+                    };
+                    let excerpt = best_excerpt(statement.text, &excerpts);
+                    in_flight.push_back(self.check_entailment(statement.text, excerpt));
+                }
+                match in_flight.next().await {
+                    Some(verdict) => verdicts.push(verdict),
+                    None => break,
+                }
+            }
+
+            // A failed judgement leaves the lexical verdict standing
+            let mut still_unsupported = Vec::new();
+            for (statement, verdict) in unsupported.into_iter().zip(verdicts) {
+                match verdict {
+                    Ok(true) => {}
+                    Ok(false) => still_unsupported.push(statement),
+                    Err(e) => {
+                        log::warn!("Grounding entailment check failed: {e}");
+                        warnings.push(Violation {
+                            rule: RULE_PROVIDER_ERROR.to_string(),
+                            severity: Severity::Low,
+                            message: format!(
+                                "Entailment check failed, lexical verdict kept for \"{}\": {e}",
+                                truncate_for_message(statement.text)
+                            ),
+                            location: Some(format!("Position {}", statement.offset)),
+                            provider: Some(self.name().to_string()),
+                        });
+                        still_unsupported.push(statement);
+                    }
+                }
+            }
+            unsupported = still_unsupported;
+        }
+
+        let total_statements = statements.len();
+        let unsupported_ratio = if total_statements == 0 {
+            0.0
+        } else {
+            unsupported.len() as f32 / total_statements as f32
+        };
+        let ratio_exceeded = unsupported_ratio > self.config.max_unsupported_ratio;
+
+        for statement in &unsupported {
+            let violation = Violation {
+                rule: RULE_UNSUPPORTED_STATEMENT.to_string(),
+                severity: Severity::Medium,
+                message: format!(
+                    "Statement not supported by source document: \"{}\"",
+                    truncate_for_message(statement.text)
+                ),
+                location: Some(format!("Position {}", statement.offset)),
+                provider: None,
+            };
+            // Individual statements only block when the overall threshold is exceeded
+            if ratio_exceeded {
+                violations.push(violation);
+            } else {
+                warnings.push(violation);
+            }
+        }
+
+        if ratio_exceeded {
+            violations.insert(
+                0,
+                Violation {
+                    rule: RULE_UNGROUNDED_CONTENT.to_string(),
+                    severity: Severity::High,
+                    message: format!(
+                        "{} of {} statements ({:.0}%) are not supported by the source document (max allowed: {:.0}%)",
+                        unsupported.len(),
+                        total_statements,
+                        unsupported_ratio * 100.0,
+                        self.config.max_unsupported_ratio * 100.0
+                    ),
+                    location: None,
+                    provider: None,
+                },
+            );
+        }
+
+        let grounding_result = GroundingResult {
+            total_statements,
+            unsupported_statements: unsupported.len(),
+            unsupported_ratio,
+            verified_quotes,
+            unverified_quotes,
+            entailment_checked,
+        };
+
+        Ok(GuardrailResult {
+            passed: violations.is_empty(),
+            violations,
+            warnings,
+            quality_score: None,
+            provider_specific: Some(ProviderSpecificResult::Grounding(grounding_result)),
+            children: vec![],
+            cache_hit: false,
+        })
+    }
+
+    /// Lexical support check: enough content words present and every number present
+    fn is_lexically_supported(&self, statement: &str, source_tokens: &HashSet<String>) -> bool {
+        let words: Vec<String> = content_words(statement).collect();
+        if words.is_empty() {
+            return true;
+        }
+
+        // Numbers are the most common hallucination; any unknown number is unsupported
+        let numbers_supported = words
+            .iter()
+            .filter(|w| w.chars().all(|c| c.is_ascii_digit()))
+            .all(|n| source_tokens.contains(n));
+        if !numbers_supported {
+            return false;
+        }
+
+        let found = words.iter().filter(|w| source_tokens.contains(*w)).count();
+        found as f32 / words.len() as f32 >= self.config.min_token_overlap
+    }
+
+    /// Ask the entailment model whether the source supports the statement
+    async fn check_entailment(&self, statement: &str, source: &str) -> Result<bool, CliError> {
+        let (Some(client), Some(entailment)) = (&self.client, &self.config.entailment) else {
+            return Ok(false);
+        };
+
+        let user_prompt = format!("SOURCE:\n{source}\n\nCLAIM:\n{statement}");
+        let response = client
+            .invoke(InvokeParams {
+                model: &entailment.model,
+                system_prompt: ENTAILMENT_SYSTEM_PROMPT,
+                user_prompt: &user_prompt,
+                temperature: 0.0,     // Deterministic judgement
+                max_tokens: Some(10), // Single word answer
+                seed: None,
+                api_key: entailment.api_key.as_deref(),
+                timeout_secs: entailment.timeout_secs,
+                response_format: None,
+                history: &[],
+            })
+            .await?;
+
+        parse_entailment_response(&response)
+    }
+}
+
+/// Source excerpt sharing the most content words with the statement
+fn best_excerpt<'a>(statement: &str, excerpts: &[(&'a str, HashSet<String>)]) -> &'a str {
+    let words: HashSet<String> = content_words(statement).collect();
+    excerpts
+        .iter()
+        .rev() // max_by_key keeps the last maximum; prefer the earliest excerpt
+        .max_by_key(|(_, tokens)| words.iter().filter(|w| tokens.contains(*w)).count())
+        .map_or("", |(excerpt, _)| excerpt)
+}
+
+/// Parse "SUPPORTED" / "UNSUPPORTED" verdict
+fn parse_entailment_response(response: &str) -> Result<bool, CliError> {
+    let normalized = response.trim().to_uppercase();
+    if normalized.contains("UNSUPPORTED") || normalized.contains("NOT SUPPORTED") {
+        Ok(false)
+    } else if normalized.contains("SUPPORTED") {
+        Ok(true)
+    } else {
+        Err(CliError::InvalidResponse(format!(
+            "Unexpected grounding entailment response: {response}"
+        )))
+    }
+}
+
+/// Split content into sentence-level statements with byte offsets
+fn extract_statements(content: &str) -> Vec<Statement<'_>> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        let is_boundary = match c {
+            '\n' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if is_boundary {
+            let end = idx + c.len_utf8();
+            push_statement(&mut statements, content, start, end);
+            start = end;
+        }
+    }
+    push_statement(&mut statements, content, start, content.len());
+
+    statements
+}
+
+fn push_statement<'a>(
+    statements: &mut Vec<Statement<'a>>,
+    content: &'a str,
+    start: usize,
+    end: usize,
+) {
+    let raw = &content[start..end];
+    let trimmed = raw.trim_start();
+    let offset = start + (raw.len() - trimmed.len());
+    let text = trimmed.trim_end();
+    if content_words(text).count() >= MIN_STATEMENT_WORDS {
+        statements.push(Statement { text, offset });
+    }
+}
+
+/// Tokens that carry meaning: numbers, and words of 3+ characters that are not stopwords
+fn content_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    tokenize(text)
+        .filter(|t| t.chars().all(|c| c.is_ascii_digit()) || is_content_word(t, EXTRA_STOPWORDS))
+}
+
+/// Lowercase and collapse whitespace for verbatim comparison
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Keep violation messages readable for long statements
+fn truncate_for_message(text: &str) -> String {
+    const MAX_CHARS: usize = 120;
+    if text.chars().count() <= MAX_CHARS {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(MAX_CHARS).collect();
+        format!("{truncated}...")
+    }
+}
+
+#[async_trait]
+impl GuardrailProvider for GroundingGuardrail {
+    async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        self.validate_with_context(content, &ValidationContext::default())
+            .await
+    }
+
+    async fn validate_with_context(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
+        match context.source_text {
+            Some(source) => self.check(content, source).await,
+            None => {
+                log::warn!("Grounding guardrail has no source document to check against; skipping");
+                Ok(GuardrailResult::without_quality_score(
+                    true,
+                    vec![],
+                    vec![Violation {
+                        rule: RULE_NO_SOURCE_CONTEXT.to_string(),
+                        severity: Severity::Low,
+                        message: "No source document available; grounding was not checked"
+                            .to_string(),
+                        location: None,
+                        provider: None,
+                    }],
+                ))
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "GroundingGuardrail"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "The Acme Widget 3000 was released in 2021. \
+        It weighs 450 grams and ships with a rechargeable lithium battery. \
+        The manufacturer offers a two year warranty on all widgets.";
+
+    fn context(source: &str) -> ValidationContext<'_> {
+        ValidationContext {
+            source_text: Some(source),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_extract_statements() {
+        let statements =
+            extract_statements("Sure! The widget weighs 450 grams. It ships with a spare battery.");
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].text, "The widget weighs 450 grams.");
+        assert_eq!(statements[0].offset, 6);
+        assert_eq!(statements[1].text, "It ships with a spare battery.");
+    }
+
+    #[test]
+    fn test_extract_statements_keeps_decimals() {
+        let statements = extract_statements("The ratio reached 3.5 percent overall.");
+        assert_eq!(statements.len(), 1);
+    }
+
+    #[test]
+    fn test_best_excerpt() {
+        let source = format!(
+            "{}The warehouse in Lyon stores 400 pallets. {}",
+            "Revenue and staffing figures are discussed here. ".repeat(40),
+            "Closing remarks follow. ".repeat(40)
+        );
+        let excerpts: Vec<(&str, HashSet<String>)> = ChunkingConfig::new(100, 10)
+            .split(&source)
+            .into_iter()
+            .map(|(_, excerpt)| (excerpt, tokenize(excerpt).collect()))
+            .collect();
+        assert!(excerpts.len() > 2);
+
+        let excerpt = best_excerpt("Lyon's warehouse holds 400 pallets.", &excerpts);
+        assert!(excerpt.contains("The warehouse in Lyon stores 400 pallets."));
+        assert_eq!(best_excerpt("Nothing in common", &excerpts), excerpts[0].0);
+    }
+
+    #[test]
+    fn test_parse_entailment_response() {
+        assert!(parse_entailment_response("SUPPORTED").unwrap());
+        assert!(parse_entailment_response(" supported\n").unwrap());
+        assert!(!parse_entailment_response("UNSUPPORTED").unwrap());
+        assert!(!parse_entailment_response("Not supported").unwrap());
+        assert!(parse_entailment_response("maybe").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_grounded_response_passes() {
+        let guardrail = GroundingGuardrail::new(GroundingConfig::default());
+        let response = "The Acme Widget 3000 was released in 2021. It weighs 450 grams.";
+
+        let result = guardrail
+            .validate_with_context(response, &context(SOURCE))
+            .await
+            .unwrap();
+        assert!(result.passed);
+        assert!(result.violations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hallucinated_number_fails() {
+        let guardrail = GroundingGuardrail::new(GroundingConfig::default());
+        let response = "The Acme Widget 3000 was released in 2019. It weighs 900 grams.";
+
+        let result = guardrail
+            .validate_with_context(response, &context(SOURCE))
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, RULE_UNGROUNDED_CONTENT);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.rule == RULE_UNSUPPORTED_STATEMENT && v.location.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_below_threshold_is_warning() {
+        let guardrail = GroundingGuardrail::new(GroundingConfig {
+            max_unsupported_ratio: 0.5,
+            ..Default::default()
+        });
+        let response = "The Acme Widget 3000 was released in 2021. \
+            It weighs 450 grams. \
+            Customers love the bright purple paint finish.";
+
+        let result = guardrail
+            .validate_with_context(response, &context(SOURCE))
+            .await
+            .unwrap();
+        assert!(result.passed);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].rule, RULE_UNSUPPORTED_STATEMENT);
+    }
+
+    #[tokio::test]
+    async fn test_unverified_quote_fails() {
+        let guardrail = GroundingGuardrail::new(GroundingConfig::default());
+        let response = "The manual says \"the warranty covers accidental damage\".";
+
+        let result = guardrail
+            .validate_with_context(response, &context(SOURCE))
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.rule == RULE_UNVERIFIED_QUOTE));
+    }
+
+    #[tokio::test]
+    async fn test_verified_quote_ignores_case_and_whitespace() {
+        let guardrail = GroundingGuardrail::new(GroundingConfig::default());
+        let response = "The source states “a two year   warranty on ALL widgets”.";
+
+        let result = guardrail
+            .validate_with_context(response, &context(SOURCE))
+            .await
+            .unwrap();
+        assert!(result.passed);
+
+        match result.provider_specific {
+            Some(ProviderSpecificResult::Grounding(grounding)) => {
+                assert_eq!(grounding.verified_quotes, 1);
+                assert_eq!(grounding.unverified_quotes, 0);
+                assert!(!grounding.entailment_checked);
+            }
+            _ => panic!("Expected Grounding provider-specific result"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_without_source_context_passes_with_warning() {
+        let guardrail = GroundingGuardrail::new(GroundingConfig::default());
+
+        let result = guardrail
+            .validate("Anything at all goes here.")
+            .await
+            .unwrap();
+        assert!(result.passed);
+        assert_eq!(result.warnings[0].rule, RULE_NO_SOURCE_CONTEXT);
+    }
+
+    #[test]
+    fn test_name() {
+        let guardrail = GroundingGuardrail::new(GroundingConfig::default());
+        assert_eq!(guardrail.name(), "GroundingGuardrail");
+    }
+}
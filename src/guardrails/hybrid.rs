@@ -1,17 +1,42 @@
 use crate::{
     error::CliError,
     guardrails::{
-        config::{AggregationMode, ExecutionMode},
-        provider::{GuardrailProvider, GuardrailResult},
+        config::{AggregationMode, ErrorPolicy, ExecutionMode},
+        provider::{
+            GuardrailProvider, GuardrailProviderReport, GuardrailResult, Severity,
+            ValidationContext, Violation,
+        },
     },
 };
 use async_trait::async_trait;
+use std::time::{Duration, Instant};
+
+/// Rule name for a nested provider that errored or timed out
+///
+/// The violation/warning `provider` holds the provider name.
+pub const RULE_PROVIDER_ERROR: &str = "PROVIDER_ERROR";
 
 /// Composite guardrail combining multiple providers
 pub struct HybridGuardrail {
     providers: Vec<Box<dyn GuardrailProvider>>,
     execution: ExecutionMode,
     aggregation: AggregationMode,
+    /// Voting weight per provider (same order as `providers`, default: 1.0)
+    weights: Vec<f32>,
+    /// Error policy per provider (same order as `providers`, default: fail closed)
+    error_policies: Vec<ErrorPolicy>,
+    /// Optional deadline per provider (same order as `providers`)
+    timeouts: Vec<Option<Duration>>,
+    /// Fail on any Critical violation regardless of votes
+    fail_on_critical: bool,
+}
+
+/// Outcome of running one nested provider under its error policy
+enum ProviderOutcome {
+    /// The provider votes with this result
+    Vote(GuardrailResult),
+    /// The provider errored under `ErrorPolicy::Warn` and does not vote
+    Abstain(Violation),
 }
 
 impl HybridGuardrail {
@@ -20,143 +45,381 @@ impl HybridGuardrail {
         execution: ExecutionMode,
         aggregation: AggregationMode,
     ) -> Self {
+        let weights = vec![1.0; providers.len()];
+        let error_policies = vec![ErrorPolicy::default(); providers.len()];
+        let timeouts = vec![None; providers.len()];
         Self {
             providers,
             execution,
             aggregation,
+            weights,
+            error_policies,
+            timeouts,
+            fail_on_critical: false,
+        }
+    }
+
+    /// Set per-provider voting weights (used by `AggregationMode::Weighted`)
+    ///
+    /// Missing weights default to 1.0.
+    pub fn with_weights(mut self, weights: Vec<f32>) -> Self {
+        self.weights = (0..self.providers.len())
+            .map(|idx| weights.get(idx).copied().unwrap_or(1.0))
+            .collect();
+        self
+    }
+
+    /// Set per-provider error policies
+    ///
+    /// Missing policies default to `ErrorPolicy::FailClosed`.
+    pub fn with_error_policies(mut self, error_policies: Vec<ErrorPolicy>) -> Self {
+        self.error_policies = (0..self.providers.len())
+            .map(|idx| error_policies.get(idx).copied().unwrap_or_default())
+            .collect();
+        self
+    }
+
+    /// Set per-provider deadlines (`None` keeps the provider's own timeout only)
+    pub fn with_timeouts(mut self, timeouts: Vec<Option<Duration>>) -> Self {
+        self.timeouts = (0..self.providers.len())
+            .map(|idx| timeouts.get(idx).copied().flatten())
+            .collect();
+        self
+    }
+
+    /// Fail whenever any provider reports a Critical violation, regardless of votes
+    pub fn with_fail_on_critical(mut self, fail_on_critical: bool) -> Self {
+        self.fail_on_critical = fail_on_critical;
+        self
+    }
+
+    /// Whether a set of (provider index, passed) votes passes the aggregation mode
+    fn votes_pass(&self, votes: &[(usize, bool)]) -> bool {
+        let passing = votes.iter().filter(|(_, passed)| *passed).count();
+        match self.aggregation {
+            AggregationMode::AllMustPass => passing == votes.len(),
+            AggregationMode::AnyCanPass => passing > 0,
+            AggregationMode::Majority => passing * 2 > votes.len(),
+            AggregationMode::AtLeast(n) => passing >= n,
+            AggregationMode::Weighted { threshold } => {
+                let total: f32 = votes.iter().map(|(idx, _)| self.weights[*idx]).sum();
+                // No weighted votes (zero weights, or every provider abstained): fail closed
+                if total <= 0.0 {
+                    return false;
+                }
+                let passing_weight: f32 = votes
+                    .iter()
+                    .filter(|(_, passed)| *passed)
+                    .map(|(idx, _)| self.weights[*idx])
+                    .sum();
+                passing_weight / total >= threshold
+            }
         }
     }
 
+    /// Whether any result reports a Critical violation (severity-aware mode)
+    fn has_critical(results: &[(usize, GuardrailResult)]) -> bool {
+        results
+            .iter()
+            .flat_map(|(_, r)| &r.violations)
+            .any(|v| v.severity == Severity::Critical)
+    }
+
+    /// Whether the outcome is already decided by the results so far
+    ///
+    /// The outcome is decided when it is the same whether all remaining
+    /// providers (from index `processed` on) pass or fail.
+    fn is_decided(&self, results: &[(usize, GuardrailResult)], processed: usize) -> bool {
+        if self.fail_on_critical && Self::has_critical(results) {
+            return true;
+        }
+
+        let votes: Vec<(usize, bool)> = results.iter().map(|(idx, r)| (*idx, r.passed)).collect();
+        let remaining = processed..self.providers.len();
+        let with_remaining = |passed: bool| -> Vec<(usize, bool)> {
+            votes
+                .iter()
+                .copied()
+                .chain(remaining.clone().map(|idx| (idx, passed)))
+                .collect()
+        };
+
+        let best = self.votes_pass(&with_remaining(true));
+        // A remaining provider could still report a Critical violation
+        let critical_pending = self.fail_on_critical && !remaining.is_empty();
+        let worst = !critical_pending && self.votes_pass(&with_remaining(false));
+        best == worst
+    }
+
     /// Aggregate results from multiple providers based on aggregation mode
+    ///
+    /// Results are assumed to be in provider order.
     fn aggregate_results(&self, results: Vec<GuardrailResult>) -> GuardrailResult {
+        self.aggregate_indexed(results.into_iter().enumerate().collect(), &[])
+    }
+
+    /// Aggregate (provider index, result) pairs based on aggregation mode
+    ///
+    /// Each result is kept as a child report keyed by provider name, and its
+    /// violations and warnings are attributed to that provider when merged.
+    fn aggregate_indexed(
+        &self,
+        results: Vec<(usize, GuardrailResult)>,
+        latencies: &[u64],
+    ) -> GuardrailResult {
         if results.is_empty() {
-            return GuardrailResult {
-                passed: true,
-                violations: vec![],
-                warnings: vec![],
-                quality_score: None,
-                provider_specific: None,
-            };
+            return GuardrailResult::without_quality_score(true, vec![], vec![]);
         }
 
-        let passed = match self.aggregation {
-            AggregationMode::AllMustPass => {
-                // All must say "safe" for overall "safe" (conservative)
-                results.iter().all(|r| r.passed)
-            }
-            AggregationMode::AnyCanPass => {
-                // Any can say "safe" for overall "safe" (permissive)
-                results.iter().any(|r| r.passed)
-            }
-        };
+        let votes: Vec<(usize, bool)> = results.iter().map(|(idx, r)| (*idx, r.passed)).collect();
+        let mut passed = self.votes_pass(&votes);
 
-        // Merge all violations and warnings
+        // Severity-aware: any Critical violation fails regardless of votes
+        if self.fail_on_critical && Self::has_critical(&results) {
+            passed = false;
+        }
+
+        // Use first available quality_score (every score is kept in the children)
+        let quality_score = results.iter().find_map(|(_, r)| r.quality_score);
+
+        // Use first available provider_specific data (likewise kept in the children)
+        let provider_specific = results
+            .iter()
+            .find_map(|(_, r)| r.provider_specific.clone());
+
+        // Merge all violations and warnings, attributed to their provider
         let mut violations = vec![];
         let mut warnings = vec![];
+        let mut children = vec![];
 
-        for result in &results {
+        for (idx, mut result) in results {
+            let provider = self.providers.get(idx).map_or("unknown", |p| p.name());
+            result.attribute_to(provider);
             violations.extend(result.violations.clone());
             warnings.extend(result.warnings.clone());
+            children.push(GuardrailProviderReport {
+                provider: provider.to_string(),
+                latency_ms: latencies.get(idx).copied().unwrap_or_default(),
+                result,
+            });
         }
 
-        // Use first available quality_score
-        let quality_score = results.iter().find_map(|r| r.quality_score);
-
-        // Use first available provider_specific data
-        let provider_specific = results.iter().find_map(|r| r.provider_specific.clone());
-
         GuardrailResult {
             passed,
             violations,
             warnings,
             quality_score,
             provider_specific,
+            children,
+            cache_hit: false,
         }
     }
 
     /// Validate content using the configured execution and aggregation strategy
-    async fn validate_with_strategy(&self, content: &str) -> Result<GuardrailResult, CliError> {
+    async fn validate_with_strategy(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
         match self.execution {
-            ExecutionMode::Sequential => self.validate_sequential(content).await,
-            ExecutionMode::Parallel => self.validate_parallel(content).await,
+            ExecutionMode::Sequential => self.validate_sequential(content, context).await,
+            ExecutionMode::Parallel => self.validate_parallel(content, context).await,
         }
     }
 
-    /// Sequential execution (can short-circuit based on aggregation mode)
-    async fn validate_sequential(&self, content: &str) -> Result<GuardrailResult, CliError> {
-        let mut results = Vec::new();
+    /// Run one provider, applying its deadline and error policy
+    ///
+    /// Returns the outcome and the time spent in milliseconds.
+    async fn run_provider(
+        &self,
+        idx: usize,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> (ProviderOutcome, u64) {
+        let started = Instant::now();
+        let outcome = self.run_provider_inner(idx, content, context).await;
+        (outcome, started.elapsed().as_millis() as u64)
+    }
+
+    async fn run_provider_inner(
+        &self,
+        idx: usize,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> ProviderOutcome {
+        let provider = &self.providers[idx];
+        let validation = provider.validate_with_context(content, context);
+        let result = match self.timeouts[idx] {
+            Some(deadline) => tokio::time::timeout(deadline, validation)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(CliError::InvalidResponse(format!(
+                        "timed out after {}s",
+                        deadline.as_secs_f32()
+                    )))
+                }),
+            None => validation.await,
+        };
+
+        let error = match result {
+            Ok(result) => return ProviderOutcome::Vote(result),
+            Err(e) => e,
+        };
 
-        for provider in &self.providers {
-            let result = provider.validate(content).await?;
+        let name = provider.name().to_string();
+        let policy = self.error_policies[idx];
+        log::warn!("Provider {name} failed (on_error={policy:?}): {error}");
+        let violation = |severity| Violation {
+            rule: RULE_PROVIDER_ERROR.to_string(),
+            severity,
+            message: format!("Provider {name} failed: {error}"),
+            location: None,
+            provider: Some(name.clone()),
+        };
 
-            let can_short_circuit = match self.aggregation {
-                // AllMustPass: short-circuit on first failure
-                AggregationMode::AllMustPass => !result.passed,
-                // AnyCanPass: short-circuit on first success
-                AggregationMode::AnyCanPass => result.passed,
-            };
+        match policy {
+            ErrorPolicy::FailClosed => {
+                ProviderOutcome::Vote(GuardrailResult::without_quality_score(
+                    false,
+                    vec![violation(Severity::High)],
+                    vec![],
+                ))
+            }
+            ErrorPolicy::FailOpen => ProviderOutcome::Vote(GuardrailResult::without_quality_score(
+                true,
+                vec![],
+                vec![violation(Severity::Medium)],
+            )),
+            ErrorPolicy::Warn => ProviderOutcome::Abstain(violation(Severity::Medium)),
+        }
+    }
 
-            results.push(result);
+    /// Aggregate the votes and attach warnings for abstaining (errored) providers
+    fn finish(
+        &self,
+        votes: Vec<(usize, GuardrailResult)>,
+        latencies: &[u64],
+        abstained: Vec<Violation>,
+    ) -> Result<GuardrailResult, CliError> {
+        if votes.is_empty() && !abstained.is_empty() {
+            return Err(CliError::InvalidResponse(format!(
+                "All guardrail providers failed: {}",
+                abstained
+                    .iter()
+                    .map(|v| v.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )));
+        }
+
+        let mut result = self.aggregate_indexed(votes, latencies);
+        result.warnings.extend(abstained);
+        Ok(result)
+    }
 
-            if can_short_circuit {
+    /// Sequential execution (can short-circuit based on aggregation mode)
+    async fn validate_sequential(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
+        let mut results = Vec::new();
+        let mut abstained = Vec::new();
+        let mut latencies = vec![0; self.providers.len()];
+
+        for (idx, latency) in latencies.iter_mut().enumerate() {
+            let (outcome, latency_ms) = self.run_provider(idx, content, context).await;
+            *latency = latency_ms;
+            match outcome {
+                ProviderOutcome::Vote(result) => results.push((idx, result)),
+                ProviderOutcome::Abstain(warning) => abstained.push(warning),
+            }
+
+            // Short-circuit once the remaining providers cannot change the outcome
+            // (e.g. first failure for AllMustPass, first success for AnyCanPass)
+            let processed = idx + 1;
+            if processed < self.providers.len()
+                && !results.is_empty()
+                && self.is_decided(&results, processed)
+            {
                 log::debug!(
                     "Short-circuiting sequential validation after {} providers (aggregation={:?})",
-                    results.len(),
+                    processed,
                     self.aggregation
                 );
                 break;
             }
         }
 
-        Ok(self.aggregate_results(results))
+        self.finish(results, &latencies, abstained)
     }
 
     /// Parallel execution (all providers run simultaneously)
-    async fn validate_parallel(&self, content: &str) -> Result<GuardrailResult, CliError> {
+    async fn validate_parallel(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
         // Handle empty providers gracefully
         if self.providers.is_empty() {
             return Ok(self.aggregate_results(vec![]));
         }
 
-        // Execute all providers in parallel
-        let futures: Vec<_> = self
-            .providers
-            .iter()
-            .map(|provider| provider.validate(content))
+        // Execute all providers in parallel and wait for all to complete
+        let futures: Vec<_> = (0..self.providers.len())
+            .map(|idx| self.run_provider(idx, content, context))
             .collect();
+        let outcomes = futures::future::join_all(futures).await;
 
-        // Wait for all to complete
-        let results = futures::future::join_all(futures).await;
-
-        // Collect successes, log failures
-        let mut successes = Vec::new();
-        for (idx, result) in results.into_iter().enumerate() {
-            match result {
-                Ok(r) => successes.push(r),
-                Err(e) => {
-                    log::warn!(
-                        "Provider {} failed during parallel execution: {}",
-                        self.providers[idx].name(),
-                        e
-                    );
-                }
+        let mut results = Vec::new();
+        let mut abstained = Vec::new();
+        let mut latencies = vec![0; self.providers.len()];
+        for (idx, (outcome, latency_ms)) in outcomes.into_iter().enumerate() {
+            latencies[idx] = latency_ms;
+            match outcome {
+                ProviderOutcome::Vote(result) => results.push((idx, result)),
+                ProviderOutcome::Abstain(warning) => abstained.push(warning),
             }
         }
 
-        if successes.is_empty() {
-            return Err(CliError::InvalidResponse(
-                "All providers failed during parallel execution".to_string(),
-            ));
-        }
+        self.finish(results, &latencies, abstained)
+    }
+}
 
-        Ok(self.aggregate_results(successes))
+/// Names of the providers that errored in a (possibly nested) composite result
+///
+/// Each name is listed once, in first-seen order, even when a provider
+/// reported several errors (e.g. one per checked statement).
+pub fn errored_providers(result: &GuardrailResult) -> Vec<String> {
+    let mut providers: Vec<String> = Vec::new();
+    for provider in result
+        .violations
+        .iter()
+        .chain(&result.warnings)
+        .filter(|v| v.rule == RULE_PROVIDER_ERROR)
+        .filter_map(|v| v.provider.as_ref())
+    {
+        if !providers.contains(provider) {
+            providers.push(provider.clone());
+        }
     }
+    providers
 }
 
 #[async_trait]
 impl GuardrailProvider for HybridGuardrail {
     async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
-        self.validate_with_strategy(content).await
+        self.validate_with_strategy(content, &ValidationContext::default())
+            .await
+    }
+
+    async fn validate_with_context(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
+        // Forward context so nested context-aware providers receive it
+        self.validate_with_strategy(content, context).await
     }
 
     fn name(&self) -> &str {
@@ -285,6 +548,8 @@ mod tests {
                 warnings: vec![],
                 quality_score: None,
                 provider_specific: None,
+                children: vec![],
+                cache_hit: false,
             },
             GuardrailResult {
                 passed: true,
@@ -292,6 +557,8 @@ mod tests {
                 warnings: vec![],
                 quality_score: None,
                 provider_specific: None,
+                children: vec![],
+                cache_hit: false,
             },
         ];
 
@@ -318,6 +585,8 @@ mod tests {
                 warnings: vec![],
                 quality_score: None,
                 provider_specific: None,
+                children: vec![],
+                cache_hit: false,
             },
             GuardrailResult {
                 passed: false,
@@ -326,10 +595,13 @@ mod tests {
                     severity: Severity::Critical,
                     message: "Test violation".to_string(),
                     location: None,
+                    provider: None,
                 }],
                 warnings: vec![],
                 quality_score: None,
                 provider_specific: None,
+                children: vec![],
+                cache_hit: false,
             },
         ];
 
@@ -358,10 +630,13 @@ mod tests {
                     severity: Severity::Critical,
                     message: "Test violation".to_string(),
                     location: None,
+                    provider: None,
                 }],
                 warnings: vec![],
                 quality_score: None,
                 provider_specific: None,
+                children: vec![],
+                cache_hit: false,
             },
             GuardrailResult {
                 passed: true,
@@ -369,6 +644,8 @@ mod tests {
                 warnings: vec![],
                 quality_score: None,
                 provider_specific: None,
+                children: vec![],
+                cache_hit: false,
             },
         ];
 
@@ -410,4 +687,394 @@ mod tests {
         let result = composite.validate("Any input").await.unwrap();
         assert!(result.passed);
     }
+
+    fn vote(passed: bool, severity: Severity) -> GuardrailResult {
+        GuardrailResult {
+            passed,
+            violations: if passed {
+                vec![]
+            } else {
+                vec![Violation {
+                    rule: "TEST".to_string(),
+                    severity,
+                    message: "Test violation".to_string(),
+                    location: None,
+                    provider: None,
+                }]
+            },
+            warnings: vec![],
+            quality_score: None,
+            provider_specific: None,
+            children: vec![],
+            cache_hit: false,
+        }
+    }
+
+    fn regex_providers(count: usize) -> Vec<Box<dyn GuardrailProvider>> {
+        (0..count)
+            .map(|_| {
+                Box::new(RegexGuardrail::new(RegexGuardrailConfig::default()))
+                    as Box<dyn GuardrailProvider>
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate_majority() {
+        let composite = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Parallel,
+            AggregationMode::Majority,
+        );
+
+        let two_of_three = vec![
+            vote(true, Severity::High),
+            vote(false, Severity::High),
+            vote(true, Severity::High),
+        ];
+        assert!(composite.aggregate_results(two_of_three).passed);
+
+        let one_of_three = vec![
+            vote(true, Severity::High),
+            vote(false, Severity::High),
+            vote(false, Severity::High),
+        ];
+        assert!(!composite.aggregate_results(one_of_three).passed);
+
+        // Ties fail
+        let tie = vec![vote(true, Severity::High), vote(false, Severity::High)];
+        assert!(!composite.aggregate_results(tie).passed);
+    }
+
+    #[test]
+    fn test_aggregate_at_least() {
+        let composite = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Parallel,
+            AggregationMode::AtLeast(1),
+        );
+        let results = vec![
+            vote(false, Severity::High),
+            vote(false, Severity::High),
+            vote(true, Severity::High),
+        ];
+        assert!(composite.aggregate_results(results).passed);
+
+        let composite = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Parallel,
+            AggregationMode::AtLeast(3),
+        );
+        let results = vec![
+            vote(true, Severity::High),
+            vote(true, Severity::High),
+            vote(false, Severity::Low),
+        ];
+        assert!(!composite.aggregate_results(results).passed);
+    }
+
+    #[test]
+    fn test_aggregate_weighted() {
+        let composite = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Parallel,
+            AggregationMode::Weighted { threshold: 0.5 },
+        )
+        .with_weights(vec![3.0, 1.0, 1.0]);
+
+        // Heavy provider says safe: 3/5 >= 0.5
+        let results = vec![
+            vote(true, Severity::High),
+            vote(false, Severity::High),
+            vote(false, Severity::High),
+        ];
+        assert!(composite.aggregate_results(results).passed);
+
+        // Heavy provider says unsafe: 2/5 < 0.5
+        let results = vec![
+            vote(false, Severity::High),
+            vote(true, Severity::High),
+            vote(true, Severity::High),
+        ];
+        assert!(!composite.aggregate_results(results).passed);
+    }
+
+    #[test]
+    fn test_aggregate_weighted_without_weight_fails_closed() {
+        let composite = HybridGuardrail::new(
+            regex_providers(2),
+            ExecutionMode::Parallel,
+            AggregationMode::Weighted { threshold: 0.5 },
+        )
+        .with_weights(vec![0.0, 0.0]);
+
+        let results = vec![vote(true, Severity::High), vote(true, Severity::High)];
+        assert!(!composite.aggregate_results(results).passed);
+    }
+
+    #[test]
+    fn test_with_weights_defaults_missing_to_one() {
+        let composite = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Parallel,
+            AggregationMode::Weighted { threshold: 0.5 },
+        )
+        .with_weights(vec![2.0]);
+        assert_eq!(composite.weights, vec![2.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_fail_on_critical_overrides_votes() {
+        let composite = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Parallel,
+            AggregationMode::Majority,
+        )
+        .with_fail_on_critical(true);
+
+        let results = vec![
+            vote(true, Severity::High),
+            vote(true, Severity::High),
+            vote(false, Severity::Critical),
+        ];
+        assert!(!composite.aggregate_results(results).passed);
+
+        // Non-critical dissent is still outvoted
+        let results = vec![
+            vote(true, Severity::High),
+            vote(true, Severity::High),
+            vote(false, Severity::High),
+        ];
+        assert!(composite.aggregate_results(results).passed);
+    }
+
+    #[test]
+    fn test_is_decided() {
+        let majority = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Sequential,
+            AggregationMode::Majority,
+        );
+        // One vote each way: third provider decides
+        assert!(!majority.is_decided(&[(0, vote(true, Severity::High))], 1));
+        assert!(!majority.is_decided(
+            &[
+                (0, vote(true, Severity::High)),
+                (1, vote(false, Severity::High))
+            ],
+            2
+        ));
+        // Two passes out of three: decided
+        assert!(majority.is_decided(
+            &[
+                (0, vote(true, Severity::High)),
+                (1, vote(true, Severity::High))
+            ],
+            2
+        ));
+
+        // Severity-aware: a pass never decides early, a Critical always does
+        let any_critical = HybridGuardrail::new(
+            regex_providers(3),
+            ExecutionMode::Sequential,
+            AggregationMode::AnyCanPass,
+        )
+        .with_fail_on_critical(true);
+        assert!(!any_critical.is_decided(&[(0, vote(true, Severity::High))], 1));
+        assert!(any_critical.is_decided(&[(0, vote(false, Severity::Critical))], 1));
+    }
+
+    /// Provider that always errors (or hangs, when `hang` is set)
+    struct BrokenProvider {
+        hang: bool,
+    }
+
+    #[async_trait]
+    impl GuardrailProvider for BrokenProvider {
+        async fn validate(&self, _content: &str) -> Result<GuardrailResult, CliError> {
+            if self.hang {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            Err(CliError::InvalidResponse("server down".to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "BrokenProvider"
+        }
+    }
+
+    fn with_broken(execution: ExecutionMode, policy: ErrorPolicy) -> HybridGuardrail {
+        let providers: Vec<Box<dyn GuardrailProvider>> = vec![
+            Box::new(RegexGuardrail::new(RegexGuardrailConfig::default())),
+            Box::new(BrokenProvider { hang: false }),
+        ];
+        HybridGuardrail::new(providers, execution, AggregationMode::AllMustPass)
+            .with_error_policies(vec![ErrorPolicy::FailClosed, policy])
+    }
+
+    #[tokio::test]
+    async fn test_error_policy_fail_closed() {
+        for execution in [ExecutionMode::Sequential, ExecutionMode::Parallel] {
+            let composite = with_broken(execution, ErrorPolicy::FailClosed);
+            let result = composite.validate("Clean input").await.unwrap();
+            assert!(!result.passed);
+            assert_eq!(result.violations[0].rule, RULE_PROVIDER_ERROR);
+            assert_eq!(errored_providers(&result), vec!["BrokenProvider"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_policy_fail_open() {
+        for execution in [ExecutionMode::Sequential, ExecutionMode::Parallel] {
+            let composite = with_broken(execution, ErrorPolicy::FailOpen);
+            let result = composite.validate("Clean input").await.unwrap();
+            assert!(result.passed);
+            assert_eq!(result.warnings[0].rule, RULE_PROVIDER_ERROR);
+            assert_eq!(errored_providers(&result), vec!["BrokenProvider"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_policy_warn_abstains() {
+        // Under AtLeast(2), an abstaining provider cannot make up the second vote
+        let providers: Vec<Box<dyn GuardrailProvider>> = vec![
+            Box::new(RegexGuardrail::new(RegexGuardrailConfig::default())),
+            Box::new(BrokenProvider { hang: false }),
+        ];
+        let composite = HybridGuardrail::new(
+            providers,
+            ExecutionMode::Parallel,
+            AggregationMode::AtLeast(2),
+        )
+        .with_error_policies(vec![ErrorPolicy::FailClosed, ErrorPolicy::Warn]);
+        let result = composite.validate("Clean input").await.unwrap();
+        assert!(!result.passed);
+        assert!(result.violations.is_empty());
+        assert_eq!(errored_providers(&result), vec!["BrokenProvider"]);
+
+        // AllMustPass only counts the providers that answered
+        let composite = with_broken(ExecutionMode::Sequential, ErrorPolicy::Warn);
+        let result = composite.validate("Clean input").await.unwrap();
+        assert!(result.passed);
+        assert_eq!(result.warnings[0].rule, RULE_PROVIDER_ERROR);
+    }
+
+    #[test]
+    fn test_errored_providers_deduplicated_in_order() {
+        let error = |provider: &str| Violation {
+            rule: RULE_PROVIDER_ERROR.to_string(),
+            severity: Severity::Medium,
+            message: "Provider error".to_string(),
+            location: None,
+            provider: Some(provider.to_string()),
+        };
+        let mut result = vote(true, Severity::Low);
+        result.warnings = vec![
+            error("GroundingGuardrail"),
+            error("BrokenProvider"),
+            error("GroundingGuardrail"),
+        ];
+        assert_eq!(
+            errored_providers(&result),
+            vec!["GroundingGuardrail", "BrokenProvider"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_policy_warn_all_failed_is_error() {
+        let providers: Vec<Box<dyn GuardrailProvider>> =
+            vec![Box::new(BrokenProvider { hang: false })];
+        let composite = HybridGuardrail::new(
+            providers,
+            ExecutionMode::Parallel,
+            AggregationMode::AllMustPass,
+        )
+        .with_error_policies(vec![ErrorPolicy::Warn]);
+        assert!(composite.validate("Clean input").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timeout_override() {
+        let providers: Vec<Box<dyn GuardrailProvider>> = vec![
+            Box::new(RegexGuardrail::new(RegexGuardrailConfig::default())),
+            Box::new(BrokenProvider { hang: true }),
+        ];
+        let composite = HybridGuardrail::new(
+            providers,
+            ExecutionMode::Parallel,
+            AggregationMode::AllMustPass,
+        )
+        .with_error_policies(vec![ErrorPolicy::FailClosed, ErrorPolicy::FailOpen])
+        .with_timeouts(vec![None, Some(Duration::from_millis(50))]);
+
+        let result = composite.validate("Clean input").await.unwrap();
+        assert!(result.passed);
+        assert!(result.warnings[0].message.contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_nested_composite_keeps_child_results() {
+        let inner: Vec<Box<dyn GuardrailProvider>> = vec![
+            Box::new(RegexGuardrail::new(RegexGuardrailConfig::default())),
+            Box::new(BrokenProvider { hang: false }),
+        ];
+        let inner =
+            HybridGuardrail::new(inner, ExecutionMode::Parallel, AggregationMode::AllMustPass)
+                .with_error_policies(vec![ErrorPolicy::FailClosed, ErrorPolicy::FailClosed]);
+        let outer: Vec<Box<dyn GuardrailProvider>> = vec![
+            Box::new(RegexGuardrail::new(RegexGuardrailConfig {
+                max_length_bytes: 5,
+                patterns_file: None,
+                severity_threshold: Severity::Medium,
+            })),
+            Box::new(inner),
+        ];
+        let composite =
+            HybridGuardrail::new(outer, ExecutionMode::Parallel, AggregationMode::AllMustPass);
+
+        let result = composite.validate("Clean input").await.unwrap();
+        assert!(!result.passed);
+
+        // One child per provider, in provider order
+        let names: Vec<_> = result
+            .children
+            .iter()
+            .map(|c| c.provider.as_str())
+            .collect();
+        assert_eq!(names, vec!["RegexGuardrail", "CompositeGuardrail"]);
+        assert!(!result.children[0].result.passed);
+
+        // The nested composite keeps its own children
+        let nested = &result.children[1].result;
+        assert_eq!(nested.children.len(), 2);
+        assert_eq!(nested.children[1].provider, "BrokenProvider");
+
+        // Merged violations are attributed to the leaf provider that raised them
+        let providers: Vec<_> = result
+            .violations
+            .iter()
+            .map(|v| v.provider.as_deref().unwrap())
+            .collect();
+        assert!(providers.contains(&"RegexGuardrail"));
+        assert!(providers.contains(&"BrokenProvider"));
+        assert!(!providers.contains(&"CompositeGuardrail"));
+    }
+
+    #[test]
+    fn test_aggregate_keeps_every_provider_specific_result() {
+        let composite = HybridGuardrail::new(
+            regex_providers(2),
+            ExecutionMode::Parallel,
+            AggregationMode::AllMustPass,
+        );
+        let scored = |score| GuardrailResult::with_quality_score(true, vec![], vec![], score);
+
+        let result = composite.aggregate_results(vec![scored(3.0), scored(8.0)]);
+        let scores: Vec<_> = result
+            .children
+            .iter()
+            .map(|c| c.result.quality_score)
+            .collect();
+        assert_eq!(scores, vec![Some(3.0), Some(8.0)]);
+    }
 }
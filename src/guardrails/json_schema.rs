@@ -1,29 +1,34 @@
 use crate::{
     error::CliError,
     guardrails::provider::{GuardrailProvider, GuardrailResult, Severity, Violation},
-    schema_validator,
+    json_extraction, schema_validator,
 };
 use async_trait::async_trait;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Maximum number of schema validation errors to report individually.
 /// When exceeded, remaining errors are summarized in one additional entry.
-const MAX_VIOLATIONS: usize = 25;
+pub(crate) const MAX_VIOLATIONS: usize = 25;
 
 /// Rule name for JSON parse failures (content is not valid JSON).
 pub const RULE_JSON_PARSE_ERROR: &str = "JSON_PARSE_ERROR";
 /// Rule name for JSON Schema validation failures (content doesn't match schema).
 pub const RULE_JSON_SCHEMA_VIOLATION: &str = "JSON_SCHEMA_VIOLATION";
+/// Rule name for the warning emitted when lenient extraction had to repair the content.
+pub const RULE_JSON_REPAIRED: &str = "JSON_REPAIRED";
 
 /// JSON Schema guardrail that validates content against a compiled JSON Schema.
 ///
 /// The schema is compiled once at construction time and reused for all validations.
-/// Supports JSON Schema Draft 7 via the `jsonschema` crate.
+/// Supports JSON Schema drafts 4 through 2020-12 (detected from `$schema`) via the
+/// `jsonschema` crate.
 #[derive(Debug)]
 pub struct JsonSchemaGuardrail {
     validator: jsonschema::Validator,
-    /// Retained for diagnostic logging only; not used for re-validation.
-    schema_path: PathBuf,
+    /// Schema file path or name, retained for diagnostic logging only.
+    schema_source: String,
+    /// Extract JSON from fenced or chatty content before validating
+    lenient: bool,
 }
 
 impl JsonSchemaGuardrail {
@@ -33,29 +38,7 @@ impl JsonSchemaGuardrail {
     /// Returns an error if the file doesn't exist, isn't valid JSON,
     /// or isn't a valid JSON Schema.
     pub fn new(schema_file: PathBuf) -> Result<Self, CliError> {
-        let schema_content = std::fs::read_to_string(&schema_file).map_err(|e| {
-            CliError::FileNotFound(format!(
-                "JSON Schema guardrail: Failed to read schema file '{}': {}",
-                schema_file.display(),
-                e
-            ))
-        })?;
-
-        let schema_value: serde_json::Value =
-            serde_json::from_str(&schema_content).map_err(|e| {
-                CliError::InvalidArguments(format!(
-                    "JSON Schema guardrail: Schema file '{}' is not valid JSON: {}",
-                    schema_file.display(),
-                    e
-                ))
-            })?;
-
-        let validator = schema_validator::compile_json_schema(&schema_value).map_err(|e| {
-            CliError::InvalidArguments(format!(
-                "JSON Schema guardrail: Schema file '{}' is not a valid JSON Schema: {e}",
-                schema_file.display(),
-            ))
-        })?;
+        let validator = load_schema_file(&schema_file, "JSON Schema guardrail")?;
 
         log::info!(
             "JSON Schema guardrail initialized with schema from '{}'",
@@ -64,9 +47,110 @@ impl JsonSchemaGuardrail {
 
         Ok(Self {
             validator,
-            schema_path: schema_file,
+            schema_source: schema_file.display().to_string(),
+            lenient: false,
         })
     }
+
+    /// Create a JSON Schema guardrail from an in-memory schema.
+    ///
+    /// `name` identifies the schema in log messages (e.g. the response format schema name).
+    /// Returns an error if the value isn't a valid JSON Schema.
+    pub fn from_schema(name: &str, schema: &serde_json::Value) -> Result<Self, CliError> {
+        let validator = schema_validator::compile_json_schema(schema)?;
+        Ok(Self {
+            validator,
+            schema_source: name.to_string(),
+            lenient: false,
+        })
+    }
+
+    /// Extract the first JSON value from fenced or chatty content before validating.
+    ///
+    /// Repairs are reported as a `JSON_REPAIRED` warning.
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+}
+
+/// Read and compile a JSON Schema file
+///
+/// Relative `$ref`s to sibling schema files are resolved against the file's directory.
+/// `label` names the guardrail in error messages.
+pub(crate) fn load_schema_file(
+    schema_file: &Path,
+    label: &str,
+) -> Result<jsonschema::Validator, CliError> {
+    let schema_content = std::fs::read_to_string(schema_file).map_err(|e| {
+        CliError::FileNotFound(format!(
+            "{label}: Failed to read schema file '{}': {}",
+            schema_file.display(),
+            e
+        ))
+    })?;
+
+    let schema_value: serde_json::Value = serde_json::from_str(&schema_content).map_err(|e| {
+        CliError::InvalidArguments(format!(
+            "{label}: Schema file '{}' is not valid JSON: {}",
+            schema_file.display(),
+            e
+        ))
+    })?;
+
+    let schema_value = schema_validator::bundle_file_refs(&schema_value, schema_file)
+        .map_err(|e| CliError::InvalidArguments(format!("{label}: {e}")))?;
+
+    schema_validator::compile_json_schema(&schema_value).map_err(|e| {
+        CliError::InvalidArguments(format!(
+            "{label}: Schema file '{}' is not a valid JSON Schema: {e}",
+            schema_file.display(),
+        ))
+    })
+}
+
+/// Convert schema validation errors into violations located at their instance path
+///
+/// At most `MAX_VIOLATIONS` errors are reported individually; the rest are summarized.
+pub(crate) fn schema_violations<'a>(
+    errors: impl IntoIterator<Item = jsonschema::ValidationError<'a>>,
+) -> Vec<Violation> {
+    let errors: Vec<_> = errors.into_iter().collect();
+    let total_errors = errors.len();
+    let mut violations: Vec<Violation> = errors
+        .into_iter()
+        .take(MAX_VIOLATIONS)
+        .map(|error| {
+            let instance_path = error.instance_path().to_string();
+            Violation {
+                rule: RULE_JSON_SCHEMA_VIOLATION.to_string(),
+                severity: Severity::High,
+                message: error.to_string(),
+                location: if instance_path.is_empty() {
+                    Some("/".to_string())
+                } else {
+                    Some(instance_path)
+                },
+                provider: None,
+            }
+        })
+        .collect();
+
+    if total_errors > MAX_VIOLATIONS {
+        violations.push(Violation {
+            rule: RULE_JSON_SCHEMA_VIOLATION.to_string(),
+            severity: Severity::Medium,
+            message: format!(
+                "... and {} more validation errors (showing first {} of {})",
+                total_errors - MAX_VIOLATIONS,
+                MAX_VIOLATIONS,
+                total_errors
+            ),
+            location: None,
+            provider: None,
+        });
+    }
+    violations
 }
 
 #[async_trait]
@@ -80,12 +164,39 @@ impl GuardrailProvider for JsonSchemaGuardrail {
                     severity: Severity::High,
                     message: "Content is empty (expected valid JSON)".to_string(),
                     location: None,
+                    provider: None,
                 }],
                 vec![],
             ));
         }
 
-        let json_value: serde_json::Value = match serde_json::from_str(content) {
+        let mut warnings = Vec::new();
+        let extracted = if self.lenient {
+            json_extraction::extract_json(content)
+        } else {
+            None
+        };
+        let parsed = match extracted {
+            Some(extracted) => {
+                if extracted.is_repaired() {
+                    let repairs: Vec<_> = extracted.repairs.iter().map(|r| r.to_string()).collect();
+                    warnings.push(Violation {
+                        rule: RULE_JSON_REPAIRED.to_string(),
+                        severity: Severity::Low,
+                        message: format!(
+                            "Content was repaired before validation: {}",
+                            repairs.join(", ")
+                        ),
+                        location: None,
+                        provider: None,
+                    });
+                }
+                Ok(extracted.value)
+            }
+            None => serde_json::from_str(content),
+        };
+
+        let json_value: serde_json::Value = match parsed {
             Ok(v) => v,
             Err(e) => {
                 return Ok(GuardrailResult::without_quality_score(
@@ -95,6 +206,7 @@ impl GuardrailProvider for JsonSchemaGuardrail {
                         severity: Severity::High,
                         message: format!("Content is not valid JSON: {e}"),
                         location: None,
+                        provider: None,
                     }],
                     vec![],
                 ));
@@ -106,54 +218,26 @@ impl GuardrailProvider for JsonSchemaGuardrail {
         if errors.is_empty() {
             log::debug!(
                 "JSON Schema validation passed (schema: '{}')",
-                self.schema_path.display()
+                self.schema_source
             );
-            return Ok(GuardrailResult::without_quality_score(true, vec![], vec![]));
+            return Ok(GuardrailResult::without_quality_score(
+                true,
+                vec![],
+                warnings,
+            ));
         }
 
         let total_errors = errors.len();
-        let mut violations: Vec<Violation> = errors
-            .into_iter()
-            .take(MAX_VIOLATIONS)
-            .map(|error| {
-                let instance_path = error.instance_path().to_string();
-                Violation {
-                    rule: RULE_JSON_SCHEMA_VIOLATION.to_string(),
-                    severity: Severity::High,
-                    message: error.to_string(),
-                    location: if instance_path.is_empty() {
-                        Some("/".to_string())
-                    } else {
-                        Some(instance_path)
-                    },
-                }
-            })
-            .collect();
-
-        if total_errors > MAX_VIOLATIONS {
-            violations.push(Violation {
-                rule: RULE_JSON_SCHEMA_VIOLATION.to_string(),
-                severity: Severity::Medium,
-                message: format!(
-                    "... and {} more validation errors (showing first {} of {})",
-                    total_errors - MAX_VIOLATIONS,
-                    MAX_VIOLATIONS,
-                    total_errors
-                ),
-                location: None,
-            });
-        }
+        let violations = schema_violations(errors);
 
         log::warn!(
             "JSON Schema validation failed with {} errors (schema: '{}')",
             total_errors,
-            self.schema_path.display()
+            self.schema_source
         );
 
         Ok(GuardrailResult::without_quality_score(
-            false,
-            violations,
-            vec![],
+            false, violations, warnings,
         ))
     }
 
@@ -337,4 +421,74 @@ mod tests {
         let result = guardrail.validate(invalid2).await.unwrap();
         assert!(!result.passed);
     }
+
+    #[tokio::test]
+    async fn test_fenced_json_fails_without_lenient() {
+        let (_tmp, guardrail) = create_guardrail(SIMPLE_SCHEMA);
+        let result = guardrail
+            .validate("```json\n{\"name\": \"Alice\"}\n```")
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, RULE_JSON_PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_lenient_extracts_and_reports_repairs() {
+        let (_tmp, guardrail) = create_guardrail(SIMPLE_SCHEMA);
+        let guardrail = guardrail.with_lenient(true);
+        let result = guardrail
+            .validate("Here you go:\n```json\n{\"name\": \"Alice\", \"age\": 30,}\n```")
+            .await
+            .unwrap();
+        assert!(result.passed);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].rule, RULE_JSON_REPAIRED);
+        assert!(result.warnings[0]
+            .message
+            .contains("stripped_code_fence, removed_trailing_commas"));
+    }
+
+    #[tokio::test]
+    async fn test_lenient_still_validates_schema() {
+        let (_tmp, guardrail) = create_guardrail(SIMPLE_SCHEMA);
+        let guardrail = guardrail.with_lenient(true);
+        let result = guardrail
+            .validate("The result is {\"age\": 30}.")
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, RULE_JSON_SCHEMA_VIOLATION);
+        assert_eq!(result.warnings[0].rule, RULE_JSON_REPAIRED);
+
+        let result = guardrail.validate("No JSON here").await.unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, RULE_JSON_PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_lenient_valid_json_has_no_warning() {
+        let (_tmp, guardrail) = create_guardrail(SIMPLE_SCHEMA);
+        let guardrail = guardrail.with_lenient(true);
+        let result = guardrail.validate(r#"{"name": "Alice"}"#).await.unwrap();
+        assert!(result.passed);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_from_schema() {
+        let schema: serde_json::Value = serde_json::from_str(SIMPLE_SCHEMA).unwrap();
+        let guardrail = JsonSchemaGuardrail::from_schema("person", &schema).unwrap();
+        assert!(
+            guardrail
+                .validate(r#"{"name": "Alice"}"#)
+                .await
+                .unwrap()
+                .passed
+        );
+        assert!(!guardrail.validate(r#"{"age": 1}"#).await.unwrap().passed);
+
+        let invalid = serde_json::json!({"type": "not-a-type"});
+        assert!(JsonSchemaGuardrail::from_schema("bad", &invalid).is_err());
+    }
 }
@@ -1,13 +1,18 @@
 use crate::{
     client::LlmClient,
     error::CliError,
-    guardrails::provider::{
-        GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity, Violation,
+    guardrails::{
+        chunking::{validate_in_chunks, ChunkingConfig},
+        provider::{
+            GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity,
+            ValidationContext, ValidationStage, Violation,
+        },
     },
-    provider::InvokeParams,
+    provider::{ConversationTurn, InvokeParams},
 };
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// MLCommons AI Risk and Reliability Benchmark v1.0 categories
 /// Plus Meta additions (S13, S14)
@@ -114,6 +119,28 @@ impl LlamaGuardCategory {
     }
 }
 
+/// Custom Llama Guard category, rendered into the prompt alongside the enabled built-in ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlamaGuardCustomCategory {
+    /// Code the model answers with (e.g., "S15", "C1"); overrides a built-in with the same code
+    pub code: String,
+
+    /// Short category name (e.g., "Competitor Mentions")
+    pub name: String,
+
+    /// What the category covers, rendered on the lines below the name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Severity of violations (default: Critical)
+    #[serde(default = "default_category_severity")]
+    pub severity: Severity,
+}
+
+fn default_category_severity() -> Severity {
+    Severity::Critical
+}
+
 /// Configuration for Llama Guard 3
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlamaGuardConfig {
@@ -122,6 +149,22 @@ pub struct LlamaGuardConfig {
     pub enabled_categories: Vec<LlamaGuardCategory>,
     pub timeout_secs: u64,
     pub api_key: Option<String>,
+    /// Custom categories (switches classification to an explicitly rendered prompt)
+    #[serde(default)]
+    pub custom_categories: Vec<LlamaGuardCustomCategory>,
+    /// Severity overrides keyed by category code (default: Critical)
+    #[serde(default)]
+    pub category_severities: BTreeMap<String, Severity>,
+    /// Chunking of long content (default: 4096-token chunks, 256-token overlap)
+    #[serde(default = "LlamaGuardConfig::default_chunking")]
+    pub chunking: ChunkingConfig,
+}
+
+impl LlamaGuardConfig {
+    /// Leaves room for the category list and the user turn in the prompt
+    pub fn default_chunking() -> ChunkingConfig {
+        ChunkingConfig::new(4096, 256)
+    }
 }
 
 impl Default for LlamaGuardConfig {
@@ -132,6 +175,9 @@ impl Default for LlamaGuardConfig {
             enabled_categories: LlamaGuardCategory::all(),
             timeout_secs: 30,
             api_key: None,
+            custom_categories: vec![],
+            category_severities: BTreeMap::new(),
+            chunking: Self::default_chunking(),
         }
     }
 }
@@ -148,6 +194,150 @@ impl LlamaGuardProvider {
         Self { client, config }
     }
 
+    /// Send a conversation to Llama Guard and parse its verdict
+    ///
+    /// The server renders the conversation with the model's chat template, which
+    /// asks for a verdict on the last message. An empty `user_prompt` makes the last
+    /// assistant reply in `history` that message.
+    async fn classify(
+        &self,
+        history: &[ConversationTurn],
+        user_prompt: &str,
+    ) -> Result<GuardrailResult, CliError> {
+        let response = self
+            .client
+            .invoke(InvokeParams {
+                model: &self.config.model,
+                system_prompt: "", // The chat template has no system role
+                user_prompt,
+                temperature: 0.0, // Temperature 0 for deterministic safety checks
+                max_tokens: Some(100), // Short response: "safe" or "unsafe\nS1,S3"
+                seed: None,       // No seed needed for guardrails
+                api_key: self.config.api_key.as_deref(),
+                timeout_secs: self.config.timeout_secs,
+                response_format: None, // No response_format needed for guardrails
+                history,
+            })
+            .await?;
+
+        self.parse_response(&response)
+    }
+
+    /// Build the Llama Guard 3 prompt for classifying the agent turn of a
+    /// user/agent conversation
+    ///
+    /// Follows the official Llama Guard 3 prompt format, restricted to the
+    /// enabled categories. Only used with custom categories, which the model's
+    /// chat template can't carry.
+    fn build_conversation_prompt(&self, user_prompt: &str, response: &str) -> String {
+        self.build_prompt(
+            "Agent",
+            &format!("User: {user_prompt}\n\nAgent: {response}"),
+        )
+    }
+
+    /// Build the Llama Guard 3 prompt for classifying a single user turn
+    fn build_user_prompt(&self, content: &str) -> String {
+        self.build_prompt("User", &format!("User: {content}"))
+    }
+
+    /// Render the official prompt template for the last `role` message of `conversation`
+    fn build_prompt(&self, role: &str, conversation: &str) -> String {
+        let categories = self.render_categories();
+
+        format!(
+            "Task: Check if there is unsafe content in '{role}' messages in conversations \
+            according to our safety policy with the below categories.\n\n\
+            <BEGIN UNSAFE CONTENT CATEGORIES>\n{categories}\n<END UNSAFE CONTENT CATEGORIES>\n\n\
+            <BEGIN CONVERSATION>\n\n\
+            {conversation}\n\n\
+            <END CONVERSATION>\n\n\
+            Provide your safety assessment for ONLY THE LAST {role} message in the above conversation:\n\
+            - First line must read 'safe' or 'unsafe'.\n\
+            - If unsafe, a second line must include a comma-separated list of violated categories."
+        )
+    }
+
+    /// Enabled built-in categories followed by the custom ones
+    ///
+    /// A custom category replaces a built-in category with the same code.
+    fn render_categories(&self) -> String {
+        let built_in = self
+            .config
+            .enabled_categories
+            .iter()
+            .filter(|cat| self.custom_category(cat.as_str()).is_none())
+            .map(|cat| format!("{}: {}.", cat.as_str(), cat.description()));
+        let custom = self
+            .config
+            .custom_categories
+            .iter()
+            .map(|cat| match &cat.description {
+                Some(description) => format!("{}: {}.\n{}", cat.code, cat.name, description.trim()),
+                None => format!("{}: {}.", cat.code, cat.name),
+            });
+        built_in.chain(custom).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Classify content as a user turn
+    ///
+    /// Without custom categories the raw content is sent and the model applies
+    /// its built-in policy; custom categories require the explicit prompt.
+    async fn classify_user_turn(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        if self.config.custom_categories.is_empty() {
+            self.classify(&[], content).await
+        } else {
+            self.classify(&[], &self.build_user_prompt(content)).await
+        }
+    }
+
+    /// Classify a response as the agent turn following `user_prompt`
+    async fn classify_agent_turn(
+        &self,
+        user_prompt: &str,
+        response: &str,
+    ) -> Result<GuardrailResult, CliError> {
+        if self.config.custom_categories.is_empty() {
+            let turn = ConversationTurn {
+                user: user_prompt.to_string(),
+                assistant: response.to_string(),
+            };
+            self.classify(&[turn], "").await
+        } else {
+            let prompt = self.build_conversation_prompt(user_prompt, response);
+            self.classify(&[], &prompt).await
+        }
+    }
+
+    fn custom_category(&self, code: &str) -> Option<&LlamaGuardCustomCategory> {
+        self.config
+            .custom_categories
+            .iter()
+            .find(|cat| cat.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Name and severity of a violated category code, or None if the category is disabled
+    fn resolve_category(&self, code: &str) -> Option<(String, Severity)> {
+        let (name, default_severity) = match self.custom_category(code) {
+            Some(custom) => (custom.name.clone(), custom.severity),
+            None => match LlamaGuardCategory::parse(code) {
+                Ok(cat) if self.config.enabled_categories.contains(&cat) => {
+                    (cat.description().to_string(), Severity::Critical)
+                }
+                Ok(_) => return None,
+                // Codes outside the taxonomy are kept: the model judged the content unsafe
+                Err(_) => (format!("unrecognized category {code}"), Severity::Critical),
+            },
+        };
+        let severity = self
+            .config
+            .category_severities
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(code))
+            .map_or(default_severity, |(_, severity)| *severity);
+        Some((name, severity))
+    }
+
     /// Parse Llama Guard 3 response
     /// Format: "safe" or "unsafe\nS1,S3,S7"
     fn parse_response(&self, response: &str) -> Result<GuardrailResult, CliError> {
@@ -163,25 +353,29 @@ impl LlamaGuardProvider {
         let safe = lines[0].trim().eq_ignore_ascii_case("safe");
 
         let violated_categories = if !safe && lines.len() > 1 {
-            self.parse_categories(lines[1])?
+            self.parse_categories(lines[1])
         } else {
             vec![]
         };
 
-        // Filter violations to only enabled categories
-        let filtered_violations: Vec<LlamaGuardCategory> = violated_categories
+        // Keep only enabled built-in and custom categories
+        let filtered_violations: Vec<(String, String, Severity)> = violated_categories
             .into_iter()
-            .filter(|cat| self.config.enabled_categories.contains(cat))
+            .filter_map(|code| {
+                self.resolve_category(&code)
+                    .map(|(name, severity)| (code, name, severity))
+            })
             .collect();
 
         // Convert to generic GuardrailResult
         let violations = filtered_violations
             .iter()
-            .map(|cat| Violation {
-                rule: cat.as_str().to_string(),
-                severity: Severity::Critical,
-                message: format!("Llama Guard violation: {}", cat.description()),
+            .map(|(code, name, severity)| Violation {
+                rule: code.clone(),
+                severity: *severity,
+                message: format!("Llama Guard violation: {name}"),
                 location: None,
+                provider: None,
             })
             .collect();
 
@@ -190,7 +384,7 @@ impl LlamaGuardProvider {
             safe: safe || filtered_violations.is_empty(), // Safe if no enabled categories violated
             violated_categories: filtered_violations
                 .iter()
-                .map(|c| c.as_str().to_string())
+                .map(|(code, _, _)| code.clone())
                 .collect(),
             raw_response: response.to_string(),
         };
@@ -201,15 +395,16 @@ impl LlamaGuardProvider {
             warnings: vec![],
             quality_score: None, // Llama Guard is binary (no confidence scores)
             provider_specific: Some(ProviderSpecificResult::LlamaGuard(llama_result)),
+            children: vec![],
+            cache_hit: false,
         })
     }
 
-    /// Parse comma-separated categories from response line
-    fn parse_categories(&self, line: &str) -> Result<Vec<LlamaGuardCategory>, CliError> {
+    /// Parse comma-separated category codes from response line
+    fn parse_categories(&self, line: &str) -> Vec<String> {
         line.split(',')
-            .map(|s| s.trim())
+            .map(|s| s.trim().to_uppercase())
             .filter(|s| !s.is_empty())
-            .map(LlamaGuardCategory::parse)
             .collect()
     }
 }
@@ -217,24 +412,32 @@ impl LlamaGuardProvider {
 #[async_trait]
 impl GuardrailProvider for LlamaGuardProvider {
     async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
-        // Llama Guard 3 is fine-tuned for safety classification and doesn't need
-        // elaborate prompting. Just pass the raw content with an empty system prompt.
-        let response = self
-            .client
-            .invoke(InvokeParams {
-                model: &self.config.model,
-                system_prompt: "", // Empty system prompt - model has built-in safety policy
-                user_prompt: content, // Raw content to evaluate
-                temperature: 0.0,  // Temperature 0 for deterministic safety checks
-                max_tokens: Some(100), // Short response: "safe" or "unsafe\nS1,S3"
-                seed: None,        // No seed needed for guardrails
-                api_key: self.config.api_key.as_deref(),
-                timeout_secs: self.config.timeout_secs,
-                response_format: None, // No response_format needed for guardrails
-            })
-            .await?;
+        validate_in_chunks(content, &self.config.chunking, |chunk| {
+            self.classify_user_turn(chunk)
+        })
+        .await
+    }
 
-        self.parse_response(&response)
+    async fn validate_with_context(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
+        // Llama Guard classifies the agent turn in the context of the user turn;
+        // without the user prompt fall back to classifying the raw content.
+        // Long responses are chunked; each chunk is judged as the agent turn.
+        match (context.stage, context.user_prompt) {
+            (ValidationStage::Output, Some(user_prompt)) => {
+                // The user turn goes with every chunk, so it counts against the chunk
+                // budget (a whole PDF as user prompt would overflow the guard model)
+                let (user_prompt, chunking) = self.config.chunking.reserve_context(user_prompt);
+                validate_in_chunks(content, &chunking, |chunk| {
+                    self.classify_agent_turn(user_prompt, chunk)
+                })
+                .await
+            }
+            _ => self.validate(content).await,
+        }
     }
 
     fn name(&self) -> &str {
@@ -372,10 +575,11 @@ mod tests {
         let config = LlamaGuardConfig::default();
         let provider = LlamaGuardProvider::new(config);
 
-        let categories = provider.parse_categories("S1,S9").unwrap();
-        assert_eq!(categories.len(), 2);
-        assert_eq!(categories[0], LlamaGuardCategory::S1);
-        assert_eq!(categories[1], LlamaGuardCategory::S9);
+        let categories = provider.parse_categories("S1,S9");
+        assert_eq!(categories, vec!["S1", "S9"]);
+
+        // Arbitrary codes are kept (custom taxonomies)
+        assert_eq!(provider.parse_categories("c1, s15"), vec!["C1", "S15"]);
     }
 
     #[test]
@@ -410,6 +614,99 @@ mod tests {
         assert_eq!(result.violations.len(), 0);
     }
 
+    #[test]
+    fn test_build_conversation_prompt() {
+        let config = LlamaGuardConfig {
+            enabled_categories: vec![LlamaGuardCategory::S1, LlamaGuardCategory::S11],
+            ..Default::default()
+        };
+        let provider = LlamaGuardProvider::new(config);
+
+        let prompt = provider.build_conversation_prompt("How do I cook rice?", "Boil it.");
+        assert!(prompt.contains("S1: Violent Crimes."));
+        assert!(prompt.contains("S11: Suicide & Self-Harm."));
+        assert!(!prompt.contains("S2:"));
+        assert!(prompt.contains("User: How do I cook rice?\n\nAgent: Boil it."));
+        assert!(prompt.contains("ONLY THE LAST Agent message"));
+    }
+
+    fn custom_provider() -> LlamaGuardProvider {
+        LlamaGuardProvider::new(LlamaGuardConfig {
+            enabled_categories: vec![LlamaGuardCategory::S1, LlamaGuardCategory::S6],
+            custom_categories: vec![
+                LlamaGuardCustomCategory {
+                    code: "S15".to_string(),
+                    name: "Competitor Mentions".to_string(),
+                    description: Some(
+                        "Responses should not recommend competitor products.".to_string(),
+                    ),
+                    severity: Severity::Medium,
+                },
+                LlamaGuardCustomCategory {
+                    code: "S6".to_string(),
+                    name: "Medical Advice".to_string(),
+                    description: None,
+                    severity: Severity::High,
+                },
+            ],
+            category_severities: BTreeMap::from([("S1".to_string(), Severity::High)]),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_custom_categories_rendered() {
+        let provider = custom_provider();
+
+        let prompt = provider.build_user_prompt("Which laptop should I buy?");
+        assert!(prompt.contains("S1: Violent Crimes.\n"));
+        assert!(prompt.contains(
+            "S15: Competitor Mentions.\nResponses should not recommend competitor products."
+        ));
+        // Custom S6 replaces the built-in description
+        assert!(prompt.contains("S6: Medical Advice."));
+        assert!(!prompt.contains("Specialized Advice"));
+        assert!(prompt.contains("unsafe content in 'User' messages"));
+        assert!(prompt.contains("User: Which laptop should I buy?\n\n<END CONVERSATION>"));
+        assert!(prompt.contains("ONLY THE LAST User message"));
+    }
+
+    #[test]
+    fn test_custom_category_severities() {
+        let provider = custom_provider();
+
+        let result = provider.parse_response("unsafe\nS1,S6,s15").unwrap();
+        assert!(!result.passed);
+        let severities: Vec<_> = result
+            .violations
+            .iter()
+            .map(|v| (v.rule.as_str(), v.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![
+                ("S1", Severity::High),
+                ("S6", Severity::High),
+                ("S15", Severity::Medium)
+            ]
+        );
+        assert_eq!(
+            result.violations[2].message,
+            "Llama Guard violation: Competitor Mentions"
+        );
+    }
+
+    #[test]
+    fn test_unrecognized_category_is_kept() {
+        let provider = LlamaGuardProvider::new(LlamaGuardConfig::default());
+
+        let result = provider.parse_response("unsafe\nX9").unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, "X9");
+        assert_eq!(result.violations[0].severity, Severity::Critical);
+        assert!(result.violations[0].message.contains("unrecognized"));
+    }
+
     #[test]
     fn test_llama_guard_category_s14_parse() {
         assert_eq!(
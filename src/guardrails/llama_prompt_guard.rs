@@ -1,7 +1,10 @@
 use crate::{
     client::LlmClient,
     error::CliError,
-    guardrails::provider::{GuardrailProvider, GuardrailResult, Severity, Violation},
+    guardrails::{
+        chunking::{validate_in_chunks, ChunkingConfig},
+        provider::{GuardrailProvider, GuardrailResult, Severity, Violation},
+    },
     provider::InvokeParams,
 };
 use async_trait::async_trait;
@@ -36,6 +39,17 @@ pub struct LlamaPromptGuardConfig {
     /// Optional API key environment variable name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key_name: Option<String>,
+
+    /// Chunking of long content (default: 512-token chunks, 64-token overlap)
+    #[serde(default = "LlamaPromptGuardConfig::default_chunking")]
+    pub chunking: ChunkingConfig,
+}
+
+impl LlamaPromptGuardConfig {
+    /// Prompt Guard 2 has a 512-token context window
+    pub fn default_chunking() -> ChunkingConfig {
+        ChunkingConfig::new(512, 64)
+    }
 }
 
 fn default_threshold() -> f32 {
@@ -51,6 +65,7 @@ impl Default for LlamaPromptGuardConfig {
             threshold: 0.5,
             api_key: None,
             api_key_name: None,
+            chunking: Self::default_chunking(),
         }
     }
 }
@@ -80,6 +95,27 @@ impl LlamaPromptGuardProvider {
         Self { client, config }
     }
 
+    /// Classify a single chunk
+    async fn classify(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        let response = self
+            .client
+            .invoke(InvokeParams {
+                model: &self.config.model,
+                system_prompt: "", // Empty - classifier doesn't need system prompt
+                user_prompt: content,
+                temperature: 0.0,     // Deterministic classification
+                max_tokens: Some(50), // Short response
+                seed: None,
+                api_key: self.config.api_key.as_deref(),
+                timeout_secs: self.config.timeout_secs,
+                response_format: None,
+                history: &[],
+            })
+            .await?;
+
+        self.parse_response(&response)
+    }
+
     /// Parse Prompt Guard response (handles multiple formats)
     fn parse_response(&self, response: &str) -> Result<GuardrailResult, CliError> {
         let normalized = response.trim().to_uppercase();
@@ -114,6 +150,7 @@ impl LlamaPromptGuardProvider {
                     self.config.threshold
                 ),
                 location: None,
+                provider: None,
             }]
         } else {
             vec![]
@@ -137,6 +174,8 @@ impl LlamaPromptGuardProvider {
                     prompt_guard_result,
                 ),
             ),
+            children: vec![],
+            cache_hit: false,
         })
     }
 
@@ -172,30 +211,8 @@ impl LlamaPromptGuardProvider {
 #[async_trait]
 impl GuardrailProvider for LlamaPromptGuardProvider {
     async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
-        // Truncate if exceeds 512 tokens (~2048 chars)
-        let truncated = if content.len() > 2048 {
-            log::warn!("Content exceeds 512 tokens, truncating for Prompt Guard validation");
-            &content[..2048]
-        } else {
-            content
-        };
-
-        let response = self
-            .client
-            .invoke(InvokeParams {
-                model: &self.config.model,
-                system_prompt: "", // Empty - classifier doesn't need system prompt
-                user_prompt: truncated,
-                temperature: 0.0,     // Deterministic classification
-                max_tokens: Some(50), // Short response
-                seed: None,
-                api_key: self.config.api_key.as_deref(),
-                timeout_secs: self.config.timeout_secs,
-                response_format: None,
-            })
-            .await?;
-
-        self.parse_response(&response)
+        // Long content is classified in overlapping chunks that fit the context window
+        validate_in_chunks(content, &self.config.chunking, |chunk| self.classify(chunk)).await
     }
 
     fn name(&self) -> &str {
@@ -1,32 +1,75 @@
+pub mod cache;
+pub mod canary;
+pub mod chunking;
+pub mod code_safety;
 pub mod config;
+pub mod format;
 pub mod gpt_oss_safeguard;
+pub mod grounding;
 pub mod hybrid;
+pub mod injection_scorer;
 pub mod json_schema;
+pub mod language;
 pub mod llama_guard;
 pub mod llama_prompt_guard;
 pub mod patterns;
 pub mod provider;
 pub mod regex;
+pub mod safeguard_policy;
+pub mod secrets;
+pub mod spotlighting;
+pub mod system_prompt_leak;
+mod text;
+pub mod topic;
+pub mod url_policy;
 
 // Re-export core trait types
 pub use provider::{
-    GptOssSafeguardResult, GuardrailProvider, GuardrailResult, LlamaGuardResult,
-    ProviderSpecificResult, Severity, Violation,
+    GptOssSafeguardResult, GuardrailProvider, GuardrailProviderReport, GuardrailResult,
+    LlamaGuardResult, ProviderSpecificResult, Severity, ValidationContext, ValidationStage,
+    Violation,
 };
 
 // Re-export concrete implementations
+pub use cache::{CacheBackend, CachedGuardrail};
+pub use canary::{CanaryGuardrail, CanaryPlacement, CanaryToken};
+pub use chunking::{ChunkAggregation, ChunkingConfig};
+pub use code_safety::{
+    CodeBlock, CodeRule, CodeSafetyConfig, CodeSafetyGuardrail, CodeSafetyResult,
+};
 pub use config::{
-    create_guardrail_provider, AggregationMode, ExecutionMode, GuardrailConfig,
-    GuardrailProviderConfig, RegexGuardrailConfig,
+    create_guardrail_provider, AggregationMode, CompositeMemberConfig, ErrorPolicy, ExecutionMode,
+    GuardrailConfig, GuardrailProviderConfig, RegexGuardrailConfig,
 };
+pub use format::{CsvColumn, CsvColumnType, DocumentFormat, FormatConfig, FormatGuardrail};
 pub use gpt_oss_safeguard::{GptOssSafeguardConfig, GptOssSafeguardProvider};
-pub use hybrid::HybridGuardrail;
+pub use grounding::{
+    GroundingConfig, GroundingEntailmentConfig, GroundingGuardrail, GroundingResult,
+};
+pub use hybrid::{errored_providers, HybridGuardrail, RULE_PROVIDER_ERROR};
+pub use injection_scorer::{
+    InjectionHeuristic, InjectionScorerConfig, InjectionScorerGuardrail, InjectionScorerResult,
+    InjectionSignal,
+};
 pub use json_schema::JsonSchemaGuardrail;
-pub use llama_guard::{LlamaGuardCategory, LlamaGuardConfig, LlamaGuardProvider};
+pub use language::{
+    LanguageConfig, LanguageGuardrail, LanguageResult, LanguageShare, Script, ScriptShare,
+};
+pub use llama_guard::{
+    LlamaGuardCategory, LlamaGuardConfig, LlamaGuardCustomCategory, LlamaGuardProvider,
+};
 pub use llama_prompt_guard::{
     LlamaPromptGuardConfig, LlamaPromptGuardProvider, LlamaPromptGuardResult,
 };
 pub use regex::RegexGuardrail;
+pub use safeguard_policy::{BuiltinPolicy, PolicyFragment, ReasoningEffort};
+pub use secrets::{SecretFinding, SecretsConfig, SecretsGuardrail, SecretsResult};
+pub use spotlighting::SpotlightingConfig;
+pub use system_prompt_leak::{
+    LeakedSpan, SystemPromptLeakConfig, SystemPromptLeakGuardrail, SystemPromptLeakResult,
+};
+pub use topic::{TopicClassifier, TopicConfig, TopicDefinition, TopicGuardrail, TopicResult};
+pub use url_policy::{ExtractedUrl, UrlKind, UrlPolicyConfig, UrlPolicyGuardrail, UrlPolicyResult};
 
 // Type aliases
 /// Type alias for RegexGuardrail used for input validation
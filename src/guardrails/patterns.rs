@@ -123,7 +123,7 @@ fn parse_scope(s: &str) -> Result<PatternScope, String> {
 }
 
 /// Parse severity from string
-fn parse_severity(s: &str) -> Result<Severity, String> {
+pub(crate) fn parse_severity(s: &str) -> Result<Severity, String> {
     match s.to_lowercase().as_str() {
         "low" => Ok(Severity::Low),
         "medium" => Ok(Severity::Medium),
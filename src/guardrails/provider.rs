@@ -1,6 +1,7 @@
 use crate::error::CliError;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Generic guardrail provider trait for extensibility
 #[async_trait]
@@ -8,12 +9,105 @@ pub trait GuardrailProvider: Send + Sync {
     /// Validate content (works for both input and output)
     async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError>;
 
+    /// Validate content together with the context it was produced in
+    ///
+    /// Default implementation ignores the context and delegates to `validate()`.
+    /// Providers that need more than the content itself (e.g. grounding checks
+    /// against a source document) override this method.
+    async fn validate_with_context(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
+        let _ = context;
+        self.validate(content).await
+    }
+
     /// Provider name for logging and debugging
     fn name(&self) -> &str;
 }
 
+/// Pipeline stage a guardrail is invoked at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationStage {
+    /// Validating user-provided content before the LLM call
+    #[default]
+    Input,
+    /// Validating the LLM response
+    Output,
+}
+
+/// Additional context available to guardrails during validation
+///
+/// Carries the full conversation a piece of content belongs to, so providers
+/// can classify a response in light of what was asked (e.g. Llama Guard's
+/// user/agent conversation format) or compare it against the system prompt.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationContext<'a> {
+    /// Stage the content is being validated at
+    pub stage: ValidationStage,
+    /// System prompt sent to the LLM (trusted, developer-controlled)
+    pub system_prompt: Option<&'a str>,
+    /// User prompt sent to the LLM (after PDF extraction)
+    pub user_prompt: Option<&'a str>,
+    /// LLM response (output stage only)
+    pub response: Option<&'a str>,
+    /// Trusted source document the content is expected to be grounded in
+    /// (e.g. text extracted from `pdf_input`)
+    pub source_text: Option<&'a str>,
+    /// Per-request canary token embedded in the prompt (canary mode only)
+    pub canary: Option<&'a crate::guardrails::canary::CanaryToken>,
+    /// Free-form request metadata (e.g. model, provider)
+    pub metadata: HashMap<String, String>,
+}
+
+impl<'a> ValidationContext<'a> {
+    /// Context for validating user input before the LLM call
+    pub fn input(system_prompt: &'a str, user_prompt: &'a str) -> Self {
+        Self {
+            stage: ValidationStage::Input,
+            system_prompt: Some(system_prompt),
+            user_prompt: Some(user_prompt),
+            ..Default::default()
+        }
+    }
+
+    /// Context for validating an LLM response
+    pub fn output(system_prompt: &'a str, user_prompt: &'a str, response: &'a str) -> Self {
+        Self {
+            stage: ValidationStage::Output,
+            system_prompt: Some(system_prompt),
+            user_prompt: Some(user_prompt),
+            response: Some(response),
+            ..Default::default()
+        }
+    }
+
+    /// Set the trusted source document
+    pub fn with_source_text(mut self, source_text: Option<&'a str>) -> Self {
+        self.source_text = source_text;
+        self
+    }
+
+    /// Set the canary token to check the content for
+    pub fn with_canary(
+        mut self,
+        canary: Option<&'a crate::guardrails::canary::CanaryToken>,
+    ) -> Self {
+        self.canary = canary;
+        self
+    }
+
+    /// Add a metadata entry
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
 /// Generic validation result (unified for all providers)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuardrailResult {
     pub passed: bool,
     pub violations: Vec<Violation>,
@@ -22,10 +116,20 @@ pub struct GuardrailResult {
     /// Quality score (0.0-10.0) if provider supports it
     /// - Input/regex providers: None
     /// - Output guardrails: Some(score)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub quality_score: Option<f32>,
 
     /// Provider-specific metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub provider_specific: Option<ProviderSpecificResult>,
+
+    /// Results of nested providers (composite guardrails only), in provider order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<GuardrailProviderReport>,
+
+    /// Whether the verdict was served from a verdict cache
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cache_hit: bool,
 }
 
 impl GuardrailResult {
@@ -41,6 +145,8 @@ impl GuardrailResult {
             warnings,
             quality_score: None,
             provider_specific: None,
+            children: vec![],
+            cache_hit: false,
         }
     }
 
@@ -57,6 +163,8 @@ impl GuardrailResult {
             warnings,
             quality_score: Some(quality_score),
             provider_specific: None,
+            children: vec![],
+            cache_hit: false,
         }
     }
 
@@ -73,16 +181,49 @@ impl GuardrailResult {
             warnings,
             quality_score: None,
             provider_specific: Some(provider_specific),
+            children: vec![],
+            cache_hit: false,
+        }
+    }
+
+    /// Attribute violations and warnings without a provider to `provider`
+    pub fn attribute_to(&mut self, provider: &str) {
+        for violation in self.violations.iter_mut().chain(&mut self.warnings) {
+            if violation.provider.is_none() {
+                violation.provider = Some(provider.to_string());
+            }
         }
     }
 }
 
-/// Provider-specific result data
-#[derive(Debug, Clone)]
+/// Result of a single named guardrail provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardrailProviderReport {
+    /// Provider name
+    pub provider: String,
+    /// Time spent validating
+    pub latency_ms: u64,
+    /// Verdict, violations (with severities and locations), warnings,
+    /// quality score, raw classifier output and nested results
+    #[serde(flatten)]
+    pub result: GuardrailResult,
+}
+
+/// Provider-specific result data (raw classifier output)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ProviderSpecificResult {
     LlamaGuard(LlamaGuardResult),
     GptOssSafeguard(GptOssSafeguardResult),
     LlamaPromptGuard(crate::guardrails::llama_prompt_guard::LlamaPromptGuardResult),
+    InjectionScorer(crate::guardrails::injection_scorer::InjectionScorerResult),
+    Grounding(crate::guardrails::grounding::GroundingResult),
+    SystemPromptLeak(crate::guardrails::system_prompt_leak::SystemPromptLeakResult),
+    Topic(crate::guardrails::topic::TopicResult),
+    UrlPolicy(crate::guardrails::url_policy::UrlPolicyResult),
+    Secrets(crate::guardrails::secrets::SecretsResult),
+    CodeSafety(crate::guardrails::code_safety::CodeSafetyResult),
+    Language(crate::guardrails::language::LanguageResult),
     // Future: OpenAI(OpenAIModerationResult),
     // Future: Azure(AzureContentSafetyResult),
 }
@@ -102,6 +243,9 @@ pub struct GptOssSafeguardResult {
     pub category: Option<String>,
     pub rationale: Option<String>,
     pub raw_response: String,
+    /// SHA-256 of the policy the content was evaluated against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_hash: Option<String>,
 }
 
 /// Violation details
@@ -111,6 +255,9 @@ pub struct Violation {
     pub severity: Severity,
     pub message: String,
     pub location: Option<String>,
+    /// Name of the provider that raised it (set when results are aggregated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
 }
 
 /// Violation severity levels
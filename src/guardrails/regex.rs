@@ -53,6 +53,7 @@ impl RegexGuardrail {
                     self.config.max_length_bytes
                 ),
                 location: None,
+                provider: None,
             });
         }
 
@@ -64,6 +65,7 @@ impl RegexGuardrail {
                     severity: pattern_def.severity,
                     message: format!("Matched: {}", pattern_def.description),
                     location: Some(format!("Position {}", mat.start())),
+                    provider: None,
                 };
 
                 // Respect user-configured severity threshold
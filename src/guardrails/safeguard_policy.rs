@@ -0,0 +1,413 @@
+//! Policy library for GPT-OSS-Safeguard
+//!
+//! A policy is composed from fragments (inline text, files, or named built-in
+//! policies) joined in order, with `{{variable}}` placeholders substituted.
+//! Fragments contribute rules only: the `## Output Format` section is
+//! generated once, at the end, from the category codes of all fragments.
+//! The SHA-256 of the composed policy is recorded in results for audit.
+
+use crate::error::CliError;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+/// Named policies shipped with the crate (see `policies/README.md`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinPolicy {
+    /// MLCommons taxonomy (S1-S13), mirrors Llama Guard 3
+    MlcommonsTaxonomy,
+    /// Personal data disclosure and doxxing
+    Pii,
+    /// Suicide and self-harm
+    SelfHarm,
+    /// Personalised financial advice and market manipulation
+    FinancialAdvice,
+    /// Malware, exploits and insecure code
+    CodeSecurity,
+}
+
+impl BuiltinPolicy {
+    /// Standalone policy: rules followed by the output format for its categories
+    pub fn text(&self) -> String {
+        format!(
+            "{}\n\n{}",
+            self.rules().trim(),
+            output_format(self.categories())
+        )
+    }
+
+    /// Policy definitions, classification rules and examples (embedded at compile time)
+    pub fn rules(&self) -> &'static str {
+        match self {
+            Self::MlcommonsTaxonomy => include_str!("policies/mlcommons_taxonomy_policy.txt"),
+            Self::Pii => include_str!("policies/pii_policy.txt"),
+            Self::SelfHarm => include_str!("policies/self_harm_policy.txt"),
+            Self::FinancialAdvice => include_str!("policies/financial_advice_policy.txt"),
+            Self::CodeSecurity => include_str!("policies/code_security_policy.txt"),
+        }
+    }
+
+    /// Category codes the model reports for violations of this policy
+    pub fn categories(&self) -> &'static [&'static str] {
+        match self {
+            Self::MlcommonsTaxonomy => &[
+                "S1", "S2", "S3", "S4", "S5", "S6", "S7", "S8", "S9", "S10", "S11", "S12", "S13",
+            ],
+            Self::Pii => &["PII"],
+            Self::SelfHarm => &["SELF_HARM"],
+            Self::FinancialAdvice => &["FINANCIAL_ADVICE"],
+            Self::CodeSecurity => &["CODE_SECURITY"],
+        }
+    }
+}
+
+/// One part of a composed policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyFragment {
+    /// Policy text given directly in the configuration
+    Inline { text: String },
+
+    /// Policy text read from a file when the provider is created
+    File { path: PathBuf },
+
+    /// Named built-in policy
+    Builtin { name: BuiltinPolicy },
+}
+
+impl PolicyFragment {
+    fn load(&self) -> Result<String, CliError> {
+        match self {
+            Self::Inline { text } => Ok(text.clone()),
+            Self::File { path } => std::fs::read_to_string(path).map_err(|e| {
+                CliError::FileNotFound(format!(
+                    "GptOssSafeguard: Failed to read policy file '{}': {}",
+                    path.display(),
+                    e
+                ))
+            }),
+            Self::Builtin { name } => Ok(name.rules().to_string()),
+        }
+    }
+}
+
+/// Reasoning effort requested from the model (`Reasoning: <effort>` in the system prompt)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for ReasoningEffort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        })
+    }
+}
+
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+
+static OUTPUT_FORMAT_HEADING: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^##\s+output\s+format\s*$").unwrap());
+
+/// `"category": "A", "B" or null` in an output format section
+static CATEGORY_FIELD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#""category"\s*:\s*([^{}]*?)\s*or\s+null"#).unwrap());
+
+static QUOTED_CODE: Lazy<Regex> = Lazy::new(|| Regex::new(r#""([A-Za-z0-9_-]+)""#).unwrap());
+
+/// Generated `## Output Format` section listing the given category codes
+fn output_format(categories: &[&str]) -> String {
+    let category = match categories {
+        [] => "\"CATEGORY\"".to_string(),
+        [code] => format!("\"{code}\""),
+        codes => {
+            let quoted: Vec<String> = codes.iter().map(|code| format!("\"{code}\"")).collect();
+            format!("one of {}", quoted.join(", "))
+        }
+    };
+    format!(
+        "## Output Format\n\nReturn JSON only: {{\"violation\": 0 or 1, \"category\": {category} or null, \"rationale\": \"brief explanation\"}}"
+    )
+}
+
+/// Remove `## Output Format` sections, returning the remaining text and the
+/// category codes those sections declared
+fn split_output_format(text: &str) -> (String, Vec<String>) {
+    let mut rules = vec![];
+    let mut format = vec![];
+    let mut in_format = false;
+    for line in text.lines() {
+        if OUTPUT_FORMAT_HEADING.is_match(line) {
+            in_format = true;
+            continue;
+        }
+        if in_format && line.starts_with("## ") {
+            in_format = false;
+        }
+        if in_format {
+            format.push(line);
+        } else {
+            rules.push(line);
+        }
+    }
+
+    let format = format.join("\n");
+    let codes = CATEGORY_FIELD
+        .captures_iter(&format)
+        .flat_map(|caps| {
+            QUOTED_CODE
+                .captures_iter(caps.get(1).unwrap().as_str())
+                .map(|code| code[1].to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    (rules.join("\n").trim().to_string(), codes)
+}
+
+/// Compose a policy from an inline policy and fragments, substituting variables
+///
+/// The inline policy (if not empty) comes first; fragments follow in order,
+/// separated by blank lines. A lone inline or file policy is used as written.
+/// Otherwise the output format sections of the parts are dropped and a single
+/// `## Output Format` section listing every category code in use is appended.
+/// Every `{{name}}` placeholder must have a value in `variables`.
+pub fn compose_policy(
+    inline: &str,
+    fragments: &[PolicyFragment],
+    variables: &BTreeMap<String, String>,
+) -> Result<String, CliError> {
+    let mut parts = vec![];
+    if !inline.trim().is_empty() {
+        parts.push((inline.trim().to_string(), None));
+    }
+    for fragment in fragments {
+        let builtin = match fragment {
+            PolicyFragment::Builtin { name } => Some(*name),
+            _ => None,
+        };
+        parts.push((fragment.load()?.trim().to_string(), builtin));
+    }
+
+    let policy = match parts.as_slice() {
+        [] => {
+            return Err(CliError::InvalidArguments(
+                "GptOssSafeguard: A policy is required ('policy' or 'policy_fragments')"
+                    .to_string(),
+            ))
+        }
+        [(text, None)] => text.clone(),
+        _ => {
+            let mut rules = vec![];
+            let mut categories: Vec<String> = vec![];
+            for (text, builtin) in parts {
+                let (text, codes) = match builtin {
+                    Some(name) => (
+                        text,
+                        name.categories().iter().map(|c| c.to_string()).collect(),
+                    ),
+                    None => split_output_format(&text),
+                };
+                for code in codes {
+                    if !categories.contains(&code) {
+                        categories.push(code);
+                    }
+                }
+                if !text.is_empty() {
+                    rules.push(text);
+                }
+            }
+            let categories: Vec<&str> = categories.iter().map(String::as_str).collect();
+            rules.push(output_format(&categories));
+            rules.join("\n\n")
+        }
+    };
+
+    let missing: Vec<&str> = PLACEHOLDER
+        .captures_iter(&policy)
+        .map(|caps| caps.get(1).unwrap().as_str())
+        .filter(|name| !variables.contains_key(*name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(CliError::InvalidArguments(format!(
+            "GptOssSafeguard: Policy variables without a value: {}",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(PLACEHOLDER
+        .replace_all(&policy, |caps: &regex::Captures| {
+            variables[&caps[1]].clone()
+        })
+        .into_owned())
+}
+
+/// Hex-encoded SHA-256 of the policy text
+pub fn policy_hash(policy: &str) -> String {
+    format!("{:x}", Sha256::digest(policy.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin_policies_split_rules_and_output_format() {
+        for policy in [
+            BuiltinPolicy::MlcommonsTaxonomy,
+            BuiltinPolicy::Pii,
+            BuiltinPolicy::SelfHarm,
+            BuiltinPolicy::FinancialAdvice,
+            BuiltinPolicy::CodeSecurity,
+        ] {
+            let rules = policy.rules();
+            assert!(!rules.contains("## Output Format"), "{policy:?}");
+            assert!(!PLACEHOLDER.is_match(rules), "{policy:?}");
+            for code in policy.categories() {
+                assert!(rules.contains(code), "{policy:?}: {code}");
+            }
+
+            let text = policy.text();
+            assert!(text.starts_with(rules.trim()), "{policy:?}");
+            assert!(
+                text.ends_with(&output_format(policy.categories())),
+                "{policy:?}"
+            );
+        }
+        assert!(BuiltinPolicy::Pii.text().ends_with(
+            r#"Return JSON only: {"violation": 0 or 1, "category": "PII" or null, "rationale": "brief explanation"}"#
+        ));
+    }
+
+    #[test]
+    fn test_compose_fragments_in_order() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "Company rules for {{{{ company }}}}.").unwrap();
+
+        let policy = compose_policy(
+            "Base policy.",
+            &[
+                PolicyFragment::File {
+                    path: file.path().to_path_buf(),
+                },
+                PolicyFragment::Builtin {
+                    name: BuiltinPolicy::Pii,
+                },
+            ],
+            &vars(&[("company", "ACME")]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            policy,
+            format!(
+                "Base policy.\n\nCompany rules for ACME.\n\n{}\n\n{}",
+                BuiltinPolicy::Pii.rules().trim(),
+                output_format(&["PII"])
+            )
+        );
+    }
+
+    #[test]
+    fn test_compose_emits_single_output_format() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "Company rules.\n\n## Output Format\n\n\
+             Return JSON only: {{\"violation\": 0 or 1, \"category\": \"ACME\" or null}}\n\n\
+             ## Examples\n\nExample text."
+        )
+        .unwrap();
+
+        let policy = compose_policy(
+            "",
+            &[
+                PolicyFragment::Builtin {
+                    name: BuiltinPolicy::Pii,
+                },
+                PolicyFragment::File {
+                    path: file.path().to_path_buf(),
+                },
+                PolicyFragment::Builtin {
+                    name: BuiltinPolicy::SelfHarm,
+                },
+            ],
+            &vars(&[]),
+        )
+        .unwrap();
+
+        assert_eq!(policy.matches("## Output Format").count(), 1);
+        assert!(policy.contains("Company rules.\n\n## Examples\n\nExample text."));
+        assert!(policy.ends_with(&output_format(&["PII", "ACME", "SELF_HARM"])));
+        assert!(policy.contains(r#""category": one of "PII", "ACME", "SELF_HARM" or null"#));
+    }
+
+    #[test]
+    fn test_compose_lone_policy_used_as_written() {
+        let text = "Rules.\n\n## Output Format\n\nReturn JSON only: {\"violation\": 0 or 1}";
+        assert_eq!(compose_policy(text, &[], &vars(&[])).unwrap(), text);
+    }
+
+    #[test]
+    fn test_compose_reports_missing_variables() {
+        let err = compose_policy("Rules for {{company}} in {{region}}.", &[], &vars(&[]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("company, region"));
+    }
+
+    #[test]
+    fn test_compose_requires_a_policy() {
+        assert!(compose_policy("  ", &[], &vars(&[])).is_err());
+    }
+
+    #[test]
+    fn test_compose_missing_file() {
+        let result = compose_policy(
+            "",
+            &[PolicyFragment::File {
+                path: PathBuf::from("/nonexistent/policy.txt"),
+            }],
+            &vars(&[]),
+        );
+        assert!(matches!(result, Err(CliError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_policy_hash_is_stable() {
+        assert_eq!(policy_hash("abc"), policy_hash("abc"));
+        assert_ne!(policy_hash("abc"), policy_hash("abd"));
+        assert_eq!(policy_hash("abc").len(), 64);
+    }
+
+    #[test]
+    fn test_fragment_serde() {
+        let fragments: Vec<PolicyFragment> = serde_json::from_str(
+            r#"[{"type": "builtin", "name": "self_harm"}, {"type": "file", "path": "p.txt"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            fragments[0],
+            PolicyFragment::Builtin {
+                name: BuiltinPolicy::SelfHarm
+            }
+        );
+    }
+}
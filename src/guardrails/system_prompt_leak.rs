@@ -0,0 +1,469 @@
+use crate::{
+    error::CliError,
+    guardrails::provider::{
+        GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity, ValidationContext,
+        Violation,
+    },
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Rule name for a verbatim or near-verbatim system prompt fragment in the content
+pub const RULE_SYSTEM_PROMPT_LEAK: &str = "SYSTEM_PROMPT_LEAK";
+/// Rule name when the share of system prompt n-grams found in the content exceeds the threshold
+pub const RULE_SYSTEM_PROMPT_OVERLAP: &str = "SYSTEM_PROMPT_OVERLAP";
+/// Rule name when no system prompt is available to compare against
+pub const RULE_NO_SYSTEM_PROMPT: &str = "NO_SYSTEM_PROMPT";
+
+/// Configuration for the system prompt leakage guardrail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemPromptLeakConfig {
+    /// Minimum length (in words) of a fragment shared with the system prompt
+    /// for it to count as leaked
+    #[serde(default = "default_min_leak_words")]
+    pub min_leak_words: usize,
+
+    /// Word n-gram size used for the overall overlap measure
+    #[serde(default = "default_ngram_size")]
+    pub ngram_size: usize,
+
+    /// Maximum fraction of system prompt n-grams (0.0-1.0) that may appear in the content
+    #[serde(default = "default_max_ngram_overlap")]
+    pub max_ngram_overlap: f32,
+
+    /// Severity of reported leaks
+    #[serde(default = "default_leak_severity")]
+    pub severity: Severity,
+}
+
+fn default_min_leak_words() -> usize {
+    8
+}
+
+fn default_ngram_size() -> usize {
+    3
+}
+
+fn default_max_ngram_overlap() -> f32 {
+    0.5
+}
+
+fn default_leak_severity() -> Severity {
+    Severity::High
+}
+
+impl Default for SystemPromptLeakConfig {
+    fn default() -> Self {
+        Self {
+            min_leak_words: default_min_leak_words(),
+            ngram_size: default_ngram_size(),
+            max_ngram_overlap: default_max_ngram_overlap(),
+            severity: default_leak_severity(),
+        }
+    }
+}
+
+/// A fragment of the content copied from the system prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeakedSpan {
+    /// Byte offset where the fragment starts in the content
+    pub start: usize,
+    /// Byte offset where the fragment ends in the content (exclusive)
+    pub end: usize,
+    /// Fragment length in words
+    pub words: usize,
+}
+
+/// System prompt leakage specific result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemPromptLeakResult {
+    /// Fragments shared with the system prompt (at least `min_leak_words` long)
+    pub leaked_spans: Vec<LeakedSpan>,
+    /// Length in words of the longest fragment shared with the system prompt
+    pub longest_common_words: usize,
+    /// Fraction of system prompt n-grams found in the content (0.0-1.0)
+    pub ngram_overlap: f32,
+}
+
+/// A normalized word with its byte span in the original text
+#[derive(Debug, Clone)]
+struct Word {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// System prompt leakage guardrail: detects responses that echo the system prompt
+///
+/// Output-only. Compares against `ValidationContext::system_prompt`. Matching is
+/// done on lowercase words, so fragments that only differ in case, punctuation
+/// or whitespace count as leaked.
+pub struct SystemPromptLeakGuardrail {
+    config: SystemPromptLeakConfig,
+}
+
+impl SystemPromptLeakGuardrail {
+    pub fn new(config: SystemPromptLeakConfig) -> Self {
+        Self { config }
+    }
+
+    /// Compare content against the system prompt
+    fn check(&self, content: &str, system_prompt: &str) -> GuardrailResult {
+        let content_words = words(content);
+        let prompt_words = words(system_prompt);
+
+        let (spans, longest_common_words) = common_spans(
+            &content_words,
+            &prompt_words,
+            self.config.min_leak_words.max(1),
+        );
+        let ngram_overlap = ngram_overlap(&content_words, &prompt_words, self.config.ngram_size);
+        // A good answer restates a short instruction ("Summarize the document.") in full,
+        // so the ratio is only meaningful for prompts that could hold a leakable fragment
+        let overlap_applies = prompt_words.len() >= self.config.min_leak_words.max(1);
+
+        let mut violations: Vec<Violation> = spans
+            .iter()
+            .map(|span| Violation {
+                rule: RULE_SYSTEM_PROMPT_LEAK.to_string(),
+                severity: self.config.severity,
+                message: format!(
+                    "Response reproduces {} words of the system prompt",
+                    span.words
+                ),
+                location: Some(format!("Position {}-{}", span.start, span.end)),
+                provider: None,
+            })
+            .collect();
+
+        if overlap_applies && ngram_overlap > self.config.max_ngram_overlap {
+            violations.push(Violation {
+                rule: RULE_SYSTEM_PROMPT_OVERLAP.to_string(),
+                severity: self.config.severity,
+                message: format!(
+                    "{:.0}% of system prompt {}-grams appear in the response (max allowed: {:.0}%)",
+                    ngram_overlap * 100.0,
+                    self.config.ngram_size,
+                    self.config.max_ngram_overlap * 100.0
+                ),
+                location: None,
+                provider: None,
+            });
+        }
+
+        let leak_result = SystemPromptLeakResult {
+            leaked_spans: spans,
+            longest_common_words,
+            ngram_overlap,
+        };
+
+        GuardrailResult::with_provider_specific(
+            violations.is_empty(),
+            violations,
+            vec![],
+            ProviderSpecificResult::SystemPromptLeak(leak_result),
+        )
+    }
+}
+
+/// Split text into lowercase alphanumeric words, keeping byte spans
+fn words(text: &str) -> Vec<Word> {
+    let mut result = Vec::new();
+    let mut start = None;
+
+    for (idx, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(idx),
+            (false, Some(s)) => {
+                result.push(Word {
+                    text: text[s..idx].to_lowercase(),
+                    start: s,
+                    end: idx,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        result.push(Word {
+            text: text[s..].to_lowercase(),
+            start: s,
+            end: text.len(),
+        });
+    }
+
+    result
+}
+
+/// Find maximal word runs shared by content and prompt that are at least `min_words` long
+///
+/// Longest-common-substring dynamic programming over words. Overlapping runs
+/// (the same content region matching several prompt regions) are merged.
+/// Returns the spans (byte offsets into the content) and the longest run length.
+fn common_spans(content: &[Word], prompt: &[Word], min_words: usize) -> (Vec<LeakedSpan>, usize) {
+    let mut runs: Vec<(usize, usize)> = Vec::new(); // (first word index, word count)
+    let mut longest = 0;
+    let mut previous = vec![0usize; prompt.len() + 1];
+    let mut current = vec![0usize; prompt.len() + 1];
+
+    for (i, content_word) in content.iter().enumerate() {
+        for (j, prompt_word) in prompt.iter().enumerate() {
+            current[j + 1] = if content_word.text == prompt_word.text {
+                previous[j] + 1
+            } else {
+                0
+            };
+            let len = current[j + 1];
+            longest = longest.max(len);
+
+            // Record a run where it ends (cannot be extended by the next word pair)
+            let extends = i + 1 < content.len()
+                && j + 1 < prompt.len()
+                && content[i + 1].text == prompt[j + 1].text;
+            if len >= min_words && !extends {
+                runs.push((i + 1 - len, len));
+            }
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    // Merge overlapping runs by content position
+    runs.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new(); // (first word index, last word index)
+    for (first, len) in runs {
+        let last = first + len - 1;
+        match merged.last_mut() {
+            Some((_, merged_last)) if first <= *merged_last => {
+                *merged_last = (*merged_last).max(last);
+            }
+            _ => merged.push((first, last)),
+        }
+    }
+
+    let spans = merged
+        .into_iter()
+        .map(|(first, last)| LeakedSpan {
+            start: content[first].start,
+            end: content[last].end,
+            words: last - first + 1,
+        })
+        .collect();
+
+    (spans, longest)
+}
+
+/// Fraction of distinct prompt n-grams that also appear in the content
+fn ngram_overlap(content: &[Word], prompt: &[Word], n: usize) -> f32 {
+    let n = n.max(1);
+    let prompt_ngrams: HashSet<Vec<&str>> = ngrams(prompt, n).collect();
+    if prompt_ngrams.is_empty() {
+        return 0.0;
+    }
+    let content_ngrams: HashSet<Vec<&str>> = ngrams(content, n).collect();
+
+    let shared = prompt_ngrams.intersection(&content_ngrams).count();
+    shared as f32 / prompt_ngrams.len() as f32
+}
+
+fn ngrams(words: &[Word], n: usize) -> impl Iterator<Item = Vec<&str>> {
+    words
+        .windows(n)
+        .map(|window| window.iter().map(|w| w.text.as_str()).collect())
+}
+
+#[async_trait]
+impl GuardrailProvider for SystemPromptLeakGuardrail {
+    async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        self.validate_with_context(content, &ValidationContext::default())
+            .await
+    }
+
+    async fn validate_with_context(
+        &self,
+        content: &str,
+        context: &ValidationContext<'_>,
+    ) -> Result<GuardrailResult, CliError> {
+        match context.system_prompt.filter(|p| !p.trim().is_empty()) {
+            Some(system_prompt) => Ok(self.check(content, system_prompt)),
+            None => {
+                log::warn!("System prompt leak guardrail has no system prompt to compare against; skipping");
+                Ok(GuardrailResult::without_quality_score(
+                    true,
+                    vec![],
+                    vec![Violation {
+                        rule: RULE_NO_SYSTEM_PROMPT.to_string(),
+                        severity: Severity::Low,
+                        message: "No system prompt available; leakage was not checked".to_string(),
+                        location: None,
+                        provider: None,
+                    }],
+                ))
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "SystemPromptLeakGuardrail"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYSTEM_PROMPT: &str = "You are AcmeBot, the support assistant for Acme Corp. \
+        Never offer refunds above 50 dollars without manager approval. \
+        Internal escalation code is ORANGE-7.";
+
+    fn context(system_prompt: &str) -> ValidationContext<'_> {
+        ValidationContext {
+            system_prompt: Some(system_prompt),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clean_response_passes() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        let result = guardrail
+            .validate_with_context(
+                "Your order shipped yesterday and should arrive on Friday.",
+                &context(SYSTEM_PROMPT),
+            )
+            .await
+            .unwrap();
+        assert!(result.passed);
+        assert!(result.violations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_verbatim_leak_reports_position() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        let response =
+            "My instructions say: never offer refunds above 50 dollars without manager approval.";
+        let result = guardrail
+            .validate_with_context(response, &context(SYSTEM_PROMPT))
+            .await
+            .unwrap();
+
+        assert!(!result.passed);
+        let leak = &result.violations[0];
+        assert_eq!(leak.rule, RULE_SYSTEM_PROMPT_LEAK);
+        let start = response.find("never").unwrap();
+        let end = response.find("approval").unwrap() + "approval".len();
+        assert_eq!(leak.location, Some(format!("Position {start}-{end}")));
+    }
+
+    #[tokio::test]
+    async fn test_near_verbatim_leak_ignores_case_and_punctuation() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        let response = "NEVER offer refunds -- above 50 dollars, without manager approval!";
+        let result = guardrail
+            .validate_with_context(response, &context(SYSTEM_PROMPT))
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, RULE_SYSTEM_PROMPT_LEAK);
+    }
+
+    #[tokio::test]
+    async fn test_short_fragment_below_threshold_passes() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        let result = guardrail
+            .validate_with_context(
+                "I am the support assistant for Acme Corp.",
+                &context(SYSTEM_PROMPT),
+            )
+            .await
+            .unwrap();
+        assert!(result.passed);
+    }
+
+    #[tokio::test]
+    async fn test_ngram_overlap_threshold() {
+        let config = SystemPromptLeakConfig {
+            min_leak_words: 10, // longer than every chunk below: no spans
+            max_ngram_overlap: 0.3,
+            ..Default::default()
+        };
+        let guardrail = SystemPromptLeakGuardrail::new(config);
+        // Reordered chunks of the prompt, none long enough to be a span on its own
+        let response = "Internal escalation code is ORANGE-7. You are AcmeBot, the support \
+            assistant for Acme Corp. Never offer refunds above 50 dollars.";
+        let result = guardrail
+            .validate_with_context(response, &context(SYSTEM_PROMPT))
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.rule == RULE_SYSTEM_PROMPT_OVERLAP));
+    }
+
+    #[tokio::test]
+    async fn test_short_system_prompt_restated_passes() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        for (system_prompt, response) in [
+            (
+                "Summarize the document.",
+                "Here I summarize the document: it covers sales.",
+            ),
+            (
+                "You are a helpful assistant.",
+                "As you are a helpful assistant, I will help.",
+            ),
+        ] {
+            let result = guardrail
+                .validate_with_context(response, &context(system_prompt))
+                .await
+                .unwrap();
+            assert!(result.passed, "{system_prompt}: {:?}", result.violations);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_system_prompt_passes_with_warning() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        let result = guardrail.validate("anything").await.unwrap();
+        assert!(result.passed);
+        assert_eq!(result.warnings[0].rule, RULE_NO_SYSTEM_PROMPT);
+    }
+
+    #[test]
+    fn test_common_spans_merges_overlapping_runs() {
+        let content = words("a b c d e f");
+        let prompt = words("a b c d x c d e f");
+        let (spans, longest) = common_spans(&content, &prompt, 3);
+        assert_eq!(longest, 4);
+        assert_eq!(
+            spans,
+            vec![LeakedSpan {
+                start: 0,
+                end: 11,
+                words: 6
+            }]
+        );
+    }
+
+    #[test]
+    fn test_provider_specific_result() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        let result = guardrail.check(SYSTEM_PROMPT, SYSTEM_PROMPT);
+        match result.provider_specific {
+            Some(ProviderSpecificResult::SystemPromptLeak(leak)) => {
+                assert_eq!(leak.leaked_spans.len(), 1);
+                assert_eq!(leak.ngram_overlap, 1.0);
+            }
+            _ => panic!("Expected SystemPromptLeak provider-specific result"),
+        }
+    }
+
+    #[test]
+    fn test_name() {
+        let guardrail = SystemPromptLeakGuardrail::new(SystemPromptLeakConfig::default());
+        assert_eq!(guardrail.name(), "SystemPromptLeakGuardrail");
+    }
+}
@@ -0,0 +1,45 @@
+//! Word-level text helpers shared by the lexical guardrails (grounding, topic)
+
+/// Common English function words never counted as content words (whitespace-separated)
+const STOPWORDS: &str =
+    "the and for are but not you your all any can had has have her his its was were one our \
+out who whom this that these those with from they them their there then than what when \
+where which while will would should could also into onto about been being does did doing \
+such only other some more most very each both over under again further here how why \
+because until upon may might must shall per via";
+
+/// Lowercase alphanumeric tokens
+pub(crate) fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+}
+
+/// Whether a token is a word of 3+ characters that is neither a common stopword
+/// nor one of the caller's `extra_stopwords` (whitespace-separated)
+pub(crate) fn is_content_word(token: &str, extra_stopwords: &str) -> bool {
+    token.chars().count() >= 3
+        && !STOPWORDS
+            .split_whitespace()
+            .chain(extra_stopwords.split_whitespace())
+            .any(|w| w == token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_lowercases_and_splits_on_punctuation() {
+        let tokens: Vec<String> = tokenize("Berlin, Madrid & LISBON's offices!").collect();
+        assert_eq!(tokens, ["berlin", "madrid", "lisbon", "s", "offices"]);
+    }
+
+    #[test]
+    fn test_is_content_word() {
+        assert!(is_content_word("revenue", ""));
+        assert!(!is_content_word("the", ""));
+        assert!(!is_content_word("ok", ""));
+        assert!(!is_content_word("please", "please tell"));
+    }
+}
@@ -0,0 +1,609 @@
+use crate::{
+    client::LlmClient,
+    error::CliError,
+    guardrails::{
+        provider::{
+            GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity, Violation,
+        },
+        text::{is_content_word, tokenize},
+    },
+    provider::InvokeParams,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+/// Rule name when content matches no allowed topic
+pub const RULE_OFF_TOPIC: &str = "OFF_TOPIC";
+/// Rule name when content matches a denied topic
+pub const RULE_DENIED_TOPIC: &str = "DENIED_TOPIC";
+
+/// Request phrasing ignored by the similarity classifier (whitespace-separated)
+const EXTRA_STOPWORDS: &str = "please tell know want need like";
+
+/// System prompt for the LLM topic classifier
+const CLASSIFIER_SYSTEM_PROMPT: &str = "You are a topic classifier. \
+Classify the MESSAGE into exactly one of the TOPICS below. \
+Answer with the topic name only, exactly as written, or NONE if no topic applies.";
+
+/// A topic described by keywords and example messages
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopicDefinition {
+    /// Topic name (reported in violations and by the LLM classifier)
+    pub name: String,
+
+    /// What the topic covers (used by the LLM classifier)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Words or phrases that identify the topic on their own (case-insensitive, whole words)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+
+    /// Example messages about the topic
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+
+    /// File with more example messages, one per line (`#` comments and blank lines skipped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples_file: Option<PathBuf>,
+}
+
+/// How content is matched against topics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TopicClassifier {
+    /// Local keyword matching and bag-of-words similarity to the examples (no network)
+    Similarity {
+        /// Minimum cosine similarity (0.0-1.0) to an example for a topic to match
+        #[serde(default = "default_min_similarity")]
+        min_similarity: f32,
+    },
+
+    /// LLM classification of the content into one of the topics
+    Llm {
+        /// API endpoint (Ollama or OpenAI-compatible)
+        api_url: String,
+
+        /// Model used to classify
+        model: String,
+
+        /// Request timeout in seconds
+        #[serde(default = "default_classifier_timeout_secs")]
+        timeout_secs: u64,
+
+        /// Optional API key for authenticated endpoints
+        #[serde(skip_serializing_if = "Option::is_none")]
+        api_key: Option<String>,
+
+        /// Optional API key environment variable name
+        #[serde(skip_serializing_if = "Option::is_none")]
+        api_key_name: Option<String>,
+    },
+}
+
+fn default_min_similarity() -> f32 {
+    0.35
+}
+
+fn default_classifier_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for TopicClassifier {
+    fn default() -> Self {
+        Self::Similarity {
+            min_similarity: default_min_similarity(),
+        }
+    }
+}
+
+/// Configuration for the topic restriction guardrail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicConfig {
+    /// Topics the content must belong to (empty = any topic that is not denied)
+    #[serde(default)]
+    pub allowed_topics: Vec<TopicDefinition>,
+
+    /// Topics the content must not belong to (checked before the allowlist)
+    #[serde(default)]
+    pub denied_topics: Vec<TopicDefinition>,
+
+    /// Classification method (default: local similarity)
+    #[serde(default)]
+    pub classifier: TopicClassifier,
+
+    /// Message returned when content is rejected
+    #[serde(default = "default_refusal_message")]
+    pub refusal_message: String,
+}
+
+fn default_refusal_message() -> String {
+    "I can only help with questions about supported topics.".to_string()
+}
+
+impl Default for TopicConfig {
+    fn default() -> Self {
+        Self {
+            allowed_topics: vec![],
+            denied_topics: vec![],
+            classifier: TopicClassifier::default(),
+            refusal_message: default_refusal_message(),
+        }
+    }
+}
+
+/// Topic guardrail specific result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicResult {
+    /// Best matching topic (allowed or denied), if any
+    pub matched_topic: Option<String>,
+    /// Whether the matched topic is a denied one
+    pub denied: bool,
+    /// Similarity score per topic (similarity classifier only)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scores: BTreeMap<String, f32>,
+    /// Refusal message for rejected content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refusal_message: Option<String>,
+}
+
+/// A topic with its examples tokenized for similarity matching
+struct PreparedTopic {
+    name: String,
+    description: Option<String>,
+    keywords: Vec<String>,
+    examples: Vec<HashMap<String, f32>>,
+}
+
+/// Topic restriction guardrail: rejects content about denied topics, or
+/// about none of the allowed topics
+pub struct TopicGuardrail {
+    config: TopicConfig,
+    allowed: Vec<PreparedTopic>,
+    denied: Vec<PreparedTopic>,
+    client: Option<LlmClient>,
+}
+
+impl TopicGuardrail {
+    /// Create the guardrail, loading example files
+    ///
+    /// Returns an error if an examples file cannot be read or no topic is configured.
+    pub fn new(config: TopicConfig) -> Result<Self, CliError> {
+        if config.allowed_topics.is_empty() && config.denied_topics.is_empty() {
+            return Err(CliError::InvalidArguments(
+                "Topic guardrail: At least one allowed or denied topic is required".to_string(),
+            ));
+        }
+        let allowed = prepare_topics(&config.allowed_topics)?;
+        let denied = prepare_topics(&config.denied_topics)?;
+        let client = match &config.classifier {
+            TopicClassifier::Llm { api_url, .. } => Some(LlmClient::new(api_url.clone(), None)),
+            TopicClassifier::Similarity { .. } => None,
+        };
+        Ok(Self {
+            config,
+            allowed,
+            denied,
+            client,
+        })
+    }
+
+    /// Best-scoring denied topic above the threshold, else best-scoring allowed topic
+    fn classify_locally(&self, content: &str, min_similarity: f32) -> TopicMatch {
+        let content_vector = term_vector(content);
+        let content_text = format!(" {} ", tokenize(content).collect::<Vec<_>>().join(" "));
+
+        let mut scores = BTreeMap::new();
+        let mut best = |topics: &[PreparedTopic]| {
+            let mut best: Option<(f32, &str)> = None;
+            for topic in topics {
+                let keyword_hit = topic
+                    .keywords
+                    .iter()
+                    .any(|keyword| content_text.contains(&format!(" {keyword} ")));
+                let score = if keyword_hit {
+                    1.0
+                } else {
+                    topic
+                        .examples
+                        .iter()
+                        .map(|example| cosine(&content_vector, example))
+                        .fold(0.0, f32::max)
+                };
+                scores.insert(topic.name.clone(), score);
+                if score >= min_similarity && best.is_none_or(|(s, _)| score > s) {
+                    best = Some((score, &topic.name));
+                }
+            }
+            best.map(|(_, name)| name.to_string())
+        };
+
+        // A denied topic rejects the content even if an allowed topic also matches
+        let denied = best(&self.denied);
+        let allowed = best(&self.allowed);
+        TopicMatch {
+            topic: denied
+                .map(|name| (name, true))
+                .or(allowed.map(|name| (name, false))),
+            scores,
+        }
+    }
+
+    /// Ask the classifier model which topic the content belongs to
+    async fn classify_with_llm(&self, content: &str) -> Result<TopicMatch, CliError> {
+        let (
+            Some(client),
+            TopicClassifier::Llm {
+                model,
+                timeout_secs,
+                api_key,
+                ..
+            },
+        ) = (&self.client, &self.config.classifier)
+        else {
+            return Err(CliError::InvalidArguments(
+                "Topic guardrail: LLM classifier is not configured".to_string(),
+            ));
+        };
+
+        let topics = self
+            .denied
+            .iter()
+            .chain(&self.allowed)
+            .map(|topic| match &topic.description {
+                Some(description) => format!("- {}: {}", topic.name, description),
+                None => format!("- {}", topic.name),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let user_prompt = format!("TOPICS:\n{topics}\n\nMESSAGE:\n{content}");
+
+        let response = client
+            .invoke(InvokeParams {
+                model,
+                system_prompt: CLASSIFIER_SYSTEM_PROMPT,
+                user_prompt: &user_prompt,
+                temperature: 0.0,     // Deterministic classification
+                max_tokens: Some(20), // Topic name only
+                seed: None,
+                api_key: api_key.as_deref(),
+                timeout_secs: *timeout_secs,
+                response_format: None,
+                history: &[],
+            })
+            .await?;
+
+        self.parse_classifier_response(&response)
+    }
+
+    /// Map the classifier's answer to a configured topic
+    fn parse_classifier_response(&self, response: &str) -> Result<TopicMatch, CliError> {
+        let answer = response
+            .trim()
+            .trim_matches(|c: char| c == '"' || c == '\'' || c == '.' || c == '`')
+            .trim();
+        if answer.eq_ignore_ascii_case("NONE") {
+            return Ok(TopicMatch::default());
+        }
+        let find = |topics: &[PreparedTopic]| {
+            topics
+                .iter()
+                .find(|topic| topic.name.eq_ignore_ascii_case(answer))
+                .map(|topic| topic.name.clone())
+        };
+        let topic = if let Some(name) = find(&self.denied) {
+            (name, true)
+        } else if let Some(name) = find(&self.allowed) {
+            (name, false)
+        } else {
+            return Err(CliError::InvalidResponse(format!(
+                "Unexpected topic classifier response: {response}"
+            )));
+        };
+        Ok(TopicMatch {
+            topic: Some(topic),
+            scores: BTreeMap::new(),
+        })
+    }
+
+    /// Turn a classification into a verdict
+    fn verdict(&self, classification: TopicMatch) -> GuardrailResult {
+        let violation = match &classification.topic {
+            Some((name, true)) => Some(Violation {
+                rule: RULE_DENIED_TOPIC.to_string(),
+                severity: Severity::High,
+                message: format!("{} (denied topic: {name})", self.config.refusal_message),
+                location: None,
+                provider: None,
+            }),
+            None if !self.allowed.is_empty() => Some(Violation {
+                rule: RULE_OFF_TOPIC.to_string(),
+                severity: Severity::Medium,
+                message: format!("{} (no allowed topic matched)", self.config.refusal_message),
+                location: None,
+                provider: None,
+            }),
+            _ => None,
+        };
+
+        let (matched_topic, denied) = match classification.topic {
+            Some((name, denied)) => (Some(name), denied),
+            None => (None, false),
+        };
+        let topic_result = TopicResult {
+            matched_topic,
+            denied,
+            scores: classification.scores,
+            refusal_message: violation
+                .as_ref()
+                .map(|_| self.config.refusal_message.clone()),
+        };
+
+        GuardrailResult {
+            passed: violation.is_none(),
+            violations: violation.into_iter().collect(),
+            warnings: vec![],
+            quality_score: None,
+            provider_specific: Some(ProviderSpecificResult::Topic(topic_result)),
+            children: vec![],
+            cache_hit: false,
+        }
+    }
+}
+
+#[async_trait]
+impl GuardrailProvider for TopicGuardrail {
+    async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        let classification = match &self.config.classifier {
+            TopicClassifier::Similarity { min_similarity } => {
+                self.classify_locally(content, *min_similarity)
+            }
+            TopicClassifier::Llm { .. } => self.classify_with_llm(content).await?,
+        };
+        Ok(self.verdict(classification))
+    }
+
+    fn name(&self) -> &str {
+        "TopicGuardrail"
+    }
+}
+
+/// Classification outcome: (topic name, is denied) and per-topic scores
+#[derive(Debug, Default)]
+struct TopicMatch {
+    topic: Option<(String, bool)>,
+    scores: BTreeMap<String, f32>,
+}
+
+fn prepare_topics(topics: &[TopicDefinition]) -> Result<Vec<PreparedTopic>, CliError> {
+    topics
+        .iter()
+        .map(|topic| {
+            let mut examples = topic.examples.clone();
+            if let Some(path) = &topic.examples_file {
+                let content = std::fs::read_to_string(path).map_err(|e| {
+                    CliError::FileNotFound(format!(
+                        "Topic guardrail: Failed to read examples file '{}': {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                examples.extend(
+                    content
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                        .map(String::from),
+                );
+            }
+            Ok(PreparedTopic {
+                name: topic.name.clone(),
+                description: topic.description.clone(),
+                keywords: topic
+                    .keywords
+                    .iter()
+                    .map(|k| tokenize(k).collect::<Vec<_>>().join(" "))
+                    .filter(|k| !k.is_empty())
+                    .collect(),
+                examples: examples.iter().map(|e| term_vector(e)).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Term frequencies of content words (3+ characters, not stopwords)
+fn term_vector(text: &str) -> HashMap<String, f32> {
+    let mut vector = HashMap::new();
+    for token in tokenize(text).filter(|t| is_content_word(t, EXTRA_STOPWORDS)) {
+        *vector.entry(token).or_insert(0.0) += 1.0;
+    }
+    vector
+}
+
+fn cosine(a: &HashMap<String, f32>, b: &HashMap<String, f32>) -> f32 {
+    let dot: f32 = a
+        .iter()
+        .filter_map(|(term, weight)| b.get(term).map(|other| weight * other))
+        .sum();
+    let norm = |v: &HashMap<String, f32>| v.values().map(|w| w * w).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topic(name: &str, keywords: &[&str], examples: &[&str]) -> TopicDefinition {
+        TopicDefinition {
+            name: name.to_string(),
+            keywords: keywords.iter().map(|s| s.to_string()).collect(),
+            examples: examples.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn router_guardrail() -> TopicGuardrail {
+        TopicGuardrail::new(TopicConfig {
+            allowed_topics: vec![topic(
+                "router support",
+                &["wifi"],
+                &[
+                    "How do I reset the password of my router?",
+                    "My router keeps dropping the internet connection",
+                ],
+            )],
+            denied_topics: vec![topic("competitors", &["netgear", "tp link"], &[])],
+            refusal_message: "I can only help with ACME routers.".to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_allowed_topic_by_similarity() {
+        let result = router_guardrail()
+            .validate("How can I reset my router password?")
+            .await
+            .unwrap();
+        assert!(result.passed);
+        match result.provider_specific {
+            Some(ProviderSpecificResult::Topic(r)) => {
+                assert_eq!(r.matched_topic.as_deref(), Some("router support"));
+                assert!(r.scores["router support"] > 0.5);
+                assert!(r.refusal_message.is_none());
+            }
+            other => panic!("Expected Topic result, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_allowed_topic_by_keyword() {
+        let result = router_guardrail()
+            .validate("The WiFi is slow upstairs")
+            .await
+            .unwrap();
+        assert!(result.passed);
+    }
+
+    #[tokio::test]
+    async fn test_off_topic_rejected_with_refusal() {
+        let result = router_guardrail()
+            .validate("Write me a poem about the sea")
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, RULE_OFF_TOPIC);
+        assert!(result.violations[0]
+            .message
+            .starts_with("I can only help with ACME routers."));
+    }
+
+    #[tokio::test]
+    async fn test_denied_topic_wins() {
+        // Mentions the router (allowed) but also a competitor (denied keyword)
+        let result = router_guardrail()
+            .validate(
+                "Is a TP-Link router better than mine? My router keeps dropping the connection",
+            )
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.violations[0].rule, RULE_DENIED_TOPIC);
+        assert!(result.violations[0].message.contains("competitors"));
+    }
+
+    #[tokio::test]
+    async fn test_denylist_only_passes_other_content() {
+        let guardrail = TopicGuardrail::new(TopicConfig {
+            denied_topics: vec![topic("politics", &["election", "senator"], &[])],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(
+            guardrail
+                .validate("What's a good pasta recipe?")
+                .await
+                .unwrap()
+                .passed
+        );
+        assert!(
+            !guardrail
+                .validate("Who won the election?")
+                .await
+                .unwrap()
+                .passed
+        );
+    }
+
+    #[test]
+    fn test_requires_a_topic() {
+        assert!(TopicGuardrail::new(TopicConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_examples_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            "# billing examples\n\nWhy was I charged twice?\n",
+        )
+        .unwrap();
+        let guardrail = TopicGuardrail::new(TopicConfig {
+            allowed_topics: vec![TopicDefinition {
+                name: "billing".to_string(),
+                examples_file: Some(file.path().to_path_buf()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(guardrail.allowed[0].examples.len(), 1);
+
+        let missing = TopicGuardrail::new(TopicConfig {
+            allowed_topics: vec![TopicDefinition {
+                name: "billing".to_string(),
+                examples_file: Some(PathBuf::from("/nonexistent/examples.txt")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        assert!(matches!(missing, Err(CliError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_parse_classifier_response() {
+        let guardrail = router_guardrail();
+
+        let matched = guardrail
+            .parse_classifier_response(" \"Router Support\".\n")
+            .unwrap();
+        assert_eq!(matched.topic, Some(("router support".to_string(), false)));
+
+        let denied = guardrail.parse_classifier_response("competitors").unwrap();
+        assert_eq!(denied.topic, Some(("competitors".to_string(), true)));
+
+        assert!(guardrail
+            .parse_classifier_response("NONE")
+            .unwrap()
+            .topic
+            .is_none());
+        assert!(guardrail.parse_classifier_response("cooking").is_err());
+    }
+
+    #[test]
+    fn test_cosine() {
+        let a = term_vector("router password reset");
+        assert!((cosine(&a, &term_vector("reset router password")) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine(&a, &term_vector("poem about the sea")), 0.0);
+        assert_eq!(cosine(&a, &HashMap::new()), 0.0);
+    }
+}
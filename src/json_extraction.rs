@@ -0,0 +1,408 @@
+//! Lenient JSON extraction from LLM responses
+//!
+//! Models frequently wrap JSON in Markdown code fences, add a chatty preamble,
+//! or emit near-JSON with trailing commas or comments. [`extract_json`] locates
+//! the first complete JSON object or array in such a response, applies a small
+//! set of conservative repairs, and records which repairs were needed.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Maximum number of candidate start positions tried by the balanced-bracket scan.
+/// Bounds the work done on pathological input (e.g. thousands of unclosed braces).
+const MAX_CANDIDATES: usize = 64;
+
+/// Code fence info strings treated as JSON (an untagged fence is also accepted)
+const JSON_FENCE_LANGUAGES: &[&str] = &["json", "jsonc", "json5"];
+
+/// A repair applied while extracting JSON from a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonRepair {
+    /// The value was taken from a Markdown code fence
+    StrippedCodeFence,
+    /// Prose before or after the value was discarded
+    StrippedSurroundingText,
+    /// `//` and `/* */` comments were removed
+    RemovedComments,
+    /// Commas before a closing `}` or `]` were removed
+    RemovedTrailingCommas,
+    /// Typographic double quotes were replaced with ASCII quotes
+    NormalizedQuotes,
+}
+
+impl fmt::Display for JsonRepair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::StrippedCodeFence => "stripped_code_fence",
+            Self::StrippedSurroundingText => "stripped_surrounding_text",
+            Self::RemovedComments => "removed_comments",
+            Self::RemovedTrailingCommas => "removed_trailing_commas",
+            Self::NormalizedQuotes => "normalized_quotes",
+        };
+        f.write_str(name)
+    }
+}
+
+/// JSON value extracted from a response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedJson {
+    /// Parsed value
+    pub value: Value,
+    /// Repaired JSON text (original formatting and key order preserved)
+    pub text: String,
+    /// Repairs applied, in the order they were applied (empty if the response was valid JSON)
+    pub repairs: Vec<JsonRepair>,
+}
+
+impl ExtractedJson {
+    /// Whether any repair was needed
+    pub fn is_repaired(&self) -> bool {
+        !self.repairs.is_empty()
+    }
+}
+
+/// Extract the first complete JSON value from a response
+///
+/// A response that is already valid JSON (ignoring surrounding whitespace) is
+/// returned unchanged with no repairs. Otherwise JSON-tagged or untagged code
+/// fences are tried first, then the first balanced `{...}` or `[...]` span that
+/// parses after repairs. Returns `None` if no JSON value can be recovered.
+///
+/// # Example
+///
+/// ```
+/// use fortified_llm_client::json_extraction::{extract_json, JsonRepair};
+///
+/// let extracted = extract_json("Sure! Here it is:\n```json\n{\"a\": 1,}\n```").unwrap();
+/// assert_eq!(extracted.value["a"], 1);
+/// assert_eq!(
+///     extracted.repairs,
+///     vec![JsonRepair::StrippedCodeFence, JsonRepair::RemovedTrailingCommas]
+/// );
+/// ```
+pub fn extract_json(content: &str) -> Option<ExtractedJson> {
+    let trimmed = content.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(ExtractedJson {
+            value,
+            text: trimmed.to_string(),
+            repairs: Vec::new(),
+        });
+    }
+
+    find_value(trimmed).or_else(|| {
+        let normalized = normalize_quotes(trimmed);
+        if normalized == trimmed {
+            return None;
+        }
+        let mut extracted = find_value(&normalized)?;
+        extracted.repairs.push(JsonRepair::NormalizedQuotes);
+        Some(extracted)
+    })
+}
+
+/// Find the first recoverable JSON value: fenced blocks first, then balanced spans
+fn find_value(text: &str) -> Option<ExtractedJson> {
+    for body in json_fences(text) {
+        if let Some(mut extracted) = repair(body.trim()) {
+            extracted.repairs.insert(0, JsonRepair::StrippedCodeFence);
+            return Some(extracted);
+        }
+    }
+
+    let starts = text
+        .char_indices()
+        .filter(|(_, c)| matches!(c, '{' | '['))
+        .map(|(i, _)| i)
+        .take(MAX_CANDIDATES);
+    for start in starts {
+        let Some(end) = balanced_end(text, start) else {
+            continue;
+        };
+        if let Some(mut extracted) = repair(&text[start..end]) {
+            if start > 0 || end < text.len() {
+                extracted
+                    .repairs
+                    .insert(0, JsonRepair::StrippedSurroundingText);
+            }
+            return Some(extracted);
+        }
+    }
+    None
+}
+
+/// Parse a candidate span, removing comments and trailing commas if needed
+fn repair(candidate: &str) -> Option<ExtractedJson> {
+    if let Ok(value) = serde_json::from_str(candidate) {
+        return Some(ExtractedJson {
+            value,
+            text: candidate.to_string(),
+            repairs: Vec::new(),
+        });
+    }
+
+    let mut repairs = Vec::new();
+    let mut text = candidate.to_string();
+    let without_comments = strip_comments(&text);
+    if without_comments != text {
+        repairs.push(JsonRepair::RemovedComments);
+        text = without_comments;
+    }
+    let without_commas = strip_trailing_commas(&text);
+    if without_commas != text {
+        repairs.push(JsonRepair::RemovedTrailingCommas);
+        text = without_commas;
+    }
+
+    let value = serde_json::from_str(&text).ok()?;
+    Some(ExtractedJson {
+        value,
+        text,
+        repairs,
+    })
+}
+
+/// Bodies of code fences tagged as JSON (or untagged), in document order
+fn json_fences(text: &str) -> Vec<&str> {
+    let mut bodies = Vec::new();
+    let mut open: Option<(bool, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            match open {
+                None => {
+                    let language = info.trim().to_ascii_lowercase();
+                    let is_json =
+                        language.is_empty() || JSON_FENCE_LANGUAGES.contains(&language.as_str());
+                    open = Some((is_json, offset + line.len()));
+                }
+                Some((is_json, body_start)) => {
+                    if is_json {
+                        bodies.push(&text[body_start..offset]);
+                    }
+                    open = None;
+                }
+            }
+        }
+        offset += line.len();
+    }
+    // An unterminated fence (truncated response) still contributes its body
+    if let Some((true, body_start)) = open {
+        bodies.push(&text[body_start..]);
+    }
+    bodies
+}
+
+/// Byte offset just past the bracket matching the one at `start`
+///
+/// String-aware: brackets inside string literals are ignored.
+fn balanced_end(text: &str, start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Remove `//` line comments and `/* */` block comments outside string literals
+fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    let mut escaped = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            out.push(c);
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                // Keep the newline so line structure is preserved
+                while chars.peek().is_some_and(|&next| next != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Remove commas directly followed (ignoring whitespace) by `}` or `]` outside string literals
+fn strip_trailing_commas(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = text[i + 1..].chars().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}' | ']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Replace typographic double quotes with ASCII quotes
+fn normalize_quotes(text: &str) -> String {
+    text.replace(['\u{201C}', '\u{201D}', '\u{201E}', '\u{201F}'], "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_json_is_returned_unchanged() {
+        let extracted = extract_json("  {\"b\": 1, \"a\": [2, 3]}\n").unwrap();
+        assert_eq!(extracted.value, json!({"b": 1, "a": [2, 3]}));
+        assert_eq!(extracted.text, "{\"b\": 1, \"a\": [2, 3]}");
+        assert!(!extracted.is_repaired());
+    }
+
+    #[test]
+    fn test_json_fence() {
+        let content = "Here is the result:\n\n```json\n{\"name\": \"Alice\"}\n```\n\nLet me know!";
+        let extracted = extract_json(content).unwrap();
+        assert_eq!(extracted.value, json!({"name": "Alice"}));
+        assert_eq!(extracted.repairs, vec![JsonRepair::StrippedCodeFence]);
+    }
+
+    #[test]
+    fn test_untagged_fence_and_other_languages() {
+        let content = "```python\nprint({'a': 1})\n```\n```\n[1, 2]\n```";
+        let extracted = extract_json(content).unwrap();
+        assert_eq!(extracted.value, json!([1, 2]));
+        assert_eq!(extracted.repairs, vec![JsonRepair::StrippedCodeFence]);
+    }
+
+    #[test]
+    fn test_unterminated_fence() {
+        let extracted = extract_json("```json\n{\"a\": true}\n").unwrap();
+        assert_eq!(extracted.value, json!({"a": true}));
+    }
+
+    #[test]
+    fn test_preamble_and_trailing_text() {
+        let content =
+            "Sure! The answer is {\"items\": [\"x\", \"}\"], \"count\": 2}. Hope it helps.";
+        let extracted = extract_json(content).unwrap();
+        assert_eq!(extracted.value, json!({"items": ["x", "}"], "count": 2}));
+        assert_eq!(extracted.repairs, vec![JsonRepair::StrippedSurroundingText]);
+    }
+
+    #[test]
+    fn test_skips_bracketed_prose() {
+        let content = "[Note] Output follows: {\"ok\": true}";
+        let extracted = extract_json(content).unwrap();
+        assert_eq!(extracted.value, json!({"ok": true}));
+    }
+
+    #[test]
+    fn test_trailing_commas_and_comments() {
+        let content = "{\n  // the user\n  \"name\": \"a,}\", /* id */ \"tags\": [1, 2,],\n}";
+        let extracted = extract_json(content).unwrap();
+        assert_eq!(extracted.value, json!({"name": "a,}", "tags": [1, 2]}));
+        assert_eq!(
+            extracted.repairs,
+            vec![
+                JsonRepair::RemovedComments,
+                JsonRepair::RemovedTrailingCommas
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comment_markers_inside_strings_are_kept() {
+        let content = "{\"url\": \"https://example.com/*x*/\",}";
+        let extracted = extract_json(content).unwrap();
+        assert_eq!(extracted.value, json!({"url": "https://example.com/*x*/"}));
+        assert_eq!(extracted.repairs, vec![JsonRepair::RemovedTrailingCommas]);
+    }
+
+    #[test]
+    fn test_smart_quotes() {
+        let extracted = extract_json("{\u{201C}a\u{201D}: \u{201C}b\u{201D}}").unwrap();
+        assert_eq!(extracted.value, json!({"a": "b"}));
+        assert_eq!(extracted.repairs, vec![JsonRepair::NormalizedQuotes]);
+    }
+
+    #[test]
+    fn test_no_json() {
+        assert!(extract_json("").is_none());
+        assert!(extract_json("I cannot help with that.").is_none());
+        assert!(extract_json("This is not valid JSON {broken syntax").is_none());
+    }
+
+    #[test]
+    fn test_repair_display_matches_serde() {
+        for repair in [
+            JsonRepair::StrippedCodeFence,
+            JsonRepair::StrippedSurroundingText,
+            JsonRepair::RemovedComments,
+            JsonRepair::RemovedTrailingCommas,
+            JsonRepair::NormalizedQuotes,
+        ] {
+            assert_eq!(
+                serde_json::to_value(repair).unwrap(),
+                json!(repair.to_string())
+            );
+        }
+    }
+}
@@ -8,12 +8,16 @@ pub mod config_builder;
 pub mod constants;
 mod error;
 pub mod guardrails;
+pub mod json_extraction;
 pub mod model_registry;
 mod models;
 mod output;
 mod pdf;
 mod provider;
 pub mod providers;
+pub mod response_schema;
+pub mod schema_compat;
+pub mod schema_dialect;
 pub mod schema_validator;
 mod token_estimator;
 
@@ -24,44 +28,106 @@ pub use guardrails::{
     create_guardrail_provider,
 
     AggregationMode,
+    BuiltinPolicy,
+    CacheBackend,
+    CachedGuardrail,
+    CanaryGuardrail,
+    CanaryPlacement,
+    CanaryToken,
+    ChunkAggregation,
+    ChunkingConfig,
+    CodeBlock,
+    CodeSafetyConfig,
+    CodeSafetyGuardrail,
+    CodeSafetyResult,
+    CompositeMemberConfig,
+    CsvColumn,
+    CsvColumnType,
+    DocumentFormat,
+    ErrorPolicy,
     ExecutionMode,
+    FormatConfig,
+    FormatGuardrail,
+    GroundingConfig,
+    GroundingEntailmentConfig,
+    GroundingGuardrail,
+    GroundingResult,
     // Configuration
     GuardrailConfig,
     // Trait types
     GuardrailProvider,
     GuardrailProviderConfig,
+    GuardrailProviderReport,
     GuardrailResult,
     HybridGuardrail,
+    InjectionHeuristic,
+    InjectionScorerConfig,
+    InjectionScorerGuardrail,
+    InjectionScorerResult,
+    InjectionSignal,
 
     InputGuardrail,
     JsonSchemaGuardrail,
+    LanguageConfig,
+    LanguageGuardrail,
+    LanguageResult,
+    // Common types
+    LeakedSpan,
     LlamaGuardCategory,
     LlamaGuardConfig,
+    LlamaGuardCustomCategory,
     LlamaGuardProvider,
     LlamaPromptGuardConfig,
     LlamaPromptGuardProvider,
     LlamaPromptGuardResult,
     OutputGuardrail,
+    PolicyFragment,
     ProviderSpecificResult,
+    ReasoningEffort,
     RegexGuardrail,
     RegexGuardrailConfig,
-
-    // Common types
+    SecretFinding,
+    SecretsConfig,
+    SecretsGuardrail,
+    SecretsResult,
     Severity,
+    SpotlightingConfig,
+
+    SystemPromptLeakConfig,
+    SystemPromptLeakGuardrail,
+    SystemPromptLeakResult,
+    TopicClassifier,
+    TopicConfig,
+    TopicDefinition,
+    TopicGuardrail,
+    TopicResult,
+    UrlPolicyConfig,
+    UrlPolicyGuardrail,
+    UrlPolicyResult,
+
+    ValidationContext,
+    ValidationStage,
     Violation,
 };
 pub use models::*;
-pub use output::{CliOutput, ErrorInfo, Metadata};
+pub use output::{
+    CliOutput, ErrorInfo, GuardrailStageReport, GuardrailsReport, Metadata, SchemaAttemptReport,
+    SchemaValidationReport,
+};
 pub use pdf::{
     extract_text_from_pdf, is_docling_available, to_markdown, ContentFormat, PdfContent,
 };
-pub use provider::{InvokeParams, LlmProvider, ProviderType};
+pub use provider::{ConversationTurn, InvokeParams, LlmProvider, ProviderType};
 pub use providers::{
     create_provider, detect_provider_type, AnthropicProvider, GeminiProvider, OllamaProvider,
     OpenAIProvider,
 };
+pub use response_schema::ResponseSchema;
+pub use schemars;
 pub use token_estimator::TokenEstimator;
 
+use guardrails::canary::RULE_CANARY_LEAKED;
+use json_extraction::JsonRepair;
 use std::{path::PathBuf, time::Instant};
 
 /// Configuration for LLM evaluation
@@ -83,6 +149,14 @@ pub struct EvaluationConfig {
     pub pdf_input: Option<PathBuf>,
     pub input_guardrails: Option<GuardrailProviderConfig>,
     pub output_guardrails: Option<GuardrailProviderConfig>,
+    /// Opt-in canary token injection (a fresh token is generated per request)
+    pub canary: Option<CanaryPlacement>,
+    /// Optional isolation transform for untrusted user content
+    pub spotlighting: Option<SpotlightingConfig>,
+    /// Extract JSON from fenced or chatty responses before validation (JSON response formats only)
+    pub lenient_json: bool,
+    /// Follow-up attempts when a response fails the json-schema response format (0 = disabled)
+    pub schema_retries: u32,
     // Source tracking for metadata (mutually exclusive with inline text)
     pub system_prompt_file: Option<PathBuf>,
     pub user_prompt_file: Option<PathBuf>,
@@ -95,6 +169,8 @@ fn create_metadata(
     tokens_estimated: usize,
     latency_ms: u64,
     output_guardrails_enabled: bool,
+    errored_providers: &[String],
+    json_repairs: &[JsonRepair],
 ) -> Metadata {
     Metadata {
         // Execution results
@@ -142,14 +218,133 @@ fn create_metadata(
         } else {
             None
         },
+        guardrail_provider_errors: if errored_providers.is_empty() {
+            None
+        } else {
+            Some(errored_providers.to_vec())
+        },
+
+        // Response processing
+        json_repairs: if json_repairs.is_empty() {
+            None
+        } else {
+            Some(json_repairs.to_vec())
+        },
+    }
+}
+
+/// Run a guardrail and record its result and latency in the stage report
+async fn run_guardrail(
+    guardrail: &dyn GuardrailProvider,
+    content: &str,
+    context: &ValidationContext<'_>,
+    stage: &mut GuardrailStageReport,
+) -> Result<GuardrailResult, CliError> {
+    let started = Instant::now();
+    let mut result = guardrail.validate_with_context(content, context).await?;
+    result.attribute_to(guardrail.name());
+    stage.push(GuardrailProviderReport {
+        provider: guardrail.name().to_string(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        result: result.clone(),
+    });
+    Ok(result)
+}
+
+/// Extract JSON from a fenced or chatty response, returning the repairs applied
+///
+/// The response is returned unchanged if no JSON value can be found.
+fn extract_lenient_json(response: String) -> (String, Vec<JsonRepair>) {
+    match json_extraction::extract_json(&response) {
+        Some(extracted) => {
+            if extracted.is_repaired() {
+                let repairs: Vec<_> = extracted.repairs.iter().map(|r| r.to_string()).collect();
+                log::warn!(
+                    "Extracted JSON from response (repairs: {})",
+                    repairs.join(", ")
+                );
+            }
+            (extracted.text, extracted.repairs)
+        }
+        None => {
+            log::warn!("Lenient JSON extraction found no JSON value in the response");
+            (response, Vec::new())
+        }
     }
 }
 
+/// Follow-up prompt asking the model to correct a response that failed the response schema
+fn schema_feedback_prompt(violations: &[Violation]) -> String {
+    let errors = violations
+        .iter()
+        .map(|v| match &v.location {
+            Some(location) => format!("- {location}: {}", v.message),
+            None => format!("- {}", v.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "Your previous response did not conform to the required JSON Schema:\n{errors}\n\n\
+        Respond again with only the corrected JSON value, without explanations or code fences."
+    )
+}
+
+/// Estimated tokens for a schema retry: the system prompt, the replayed
+/// conversation and the next messages
+fn retry_tokens_required(
+    config: &EvaluationConfig,
+    system_prompt: &str,
+    history: &[ConversationTurn],
+    next_messages: &[&str],
+) -> usize {
+    let conversation = history
+        .iter()
+        .flat_map(|turn| [turn.user.as_str(), turn.assistant.as_str()])
+        .chain(next_messages.iter().copied())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    TokenEstimator::new_for_model(
+        system_prompt,
+        &conversation,
+        config
+            .max_tokens
+            .unwrap_or(constants::llm_defaults::DEFAULT_MAX_TOKENS),
+        &config.model,
+    )
+    .total_tokens_required()
+}
+
 /// Main evaluation function with optional input and output guardrails
 pub async fn evaluate(config: EvaluationConfig) -> Result<CliOutput, CliError> {
     evaluate_internal(config).await
 }
 
+/// Evaluate with a response schema derived from `T` and return the deserialized response
+///
+/// Replaces `config.response_format` with a strict json-schema format generated from `T`
+/// (see [`response_schema::response_format`]). The response is validated against the
+/// schema and deserialized; `config.schema_retries` and `config.lenient_json` apply as usual.
+///
+/// # Errors
+///
+/// - [`CliError::EvaluationFailed`] when the evaluation produced an error output
+///   (e.g. `OUTPUT_VALIDATION_FAILED` from a guardrail or exhausted schema retries)
+/// - [`CliError::ResponseTypeMismatch`] when the response does not match `T`
+/// - any other [`CliError`] returned by [`evaluate`]
+pub async fn evaluate_typed<T: ResponseSchema>(
+    mut config: EvaluationConfig,
+) -> Result<T, CliError> {
+    config.response_format = Some(response_schema::response_format::<T>());
+    let output = evaluate_internal(config).await?;
+    if let Some(error) = output.error {
+        return Err(CliError::EvaluationFailed {
+            code: error.code,
+            message: error.message,
+        });
+    }
+    response_schema::deserialize_response(output.response.unwrap_or_default())
+}
+
 /// Internal evaluation implementation
 async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliError> {
     let start_time = Instant::now();
@@ -190,6 +385,8 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
                 0,  // No tokens estimated yet
                 start_time.elapsed().as_millis() as u64,
                 config.output_guardrails.is_some(),
+                &[],
+                &[],
             );
             return Ok(CliOutput::error(
                 "FILE_TOO_LARGE".to_string(),
@@ -236,14 +433,24 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
         config.user_prompt.clone()
     };
 
+    // Guardrail providers that errored (degraded protection, reported in metadata)
+    let mut errored_providers = Vec::new();
+    // Structured guardrail results (reported in the output)
+    let mut report = GuardrailsReport::default();
+
     // 2. Input guardrails (AFTER PDF extraction)
     // NOTE: Only validate user-provided content, NOT system prompt
     // System prompts are trusted, developer-controlled content
     if let Some(guardrail_config) = &config.input_guardrails {
         log::info!("Running input guardrails validation");
         let guardrail = create_guardrail_provider(guardrail_config)?;
-        // SECURITY: Only validate user input, not system prompt
-        let validation = guardrail.validate(&user_prompt).await?;
+        // SECURITY: Only validate user input, not system prompt (the system
+        // prompt is passed as context only)
+        let context = ValidationContext::input(&config.system_prompt, &user_prompt)
+            .with_metadata("model", &config.model);
+        let stage = report.input.get_or_insert_with(Default::default);
+        let validation = run_guardrail(guardrail.as_ref(), &user_prompt, &context, stage).await?;
+        errored_providers.extend(guardrails::errored_providers(&validation));
 
         if !validation.passed {
             log::error!("Input guardrails validation FAILED");
@@ -253,6 +460,8 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
                 0,
                 start_time.elapsed().as_millis() as u64,
                 config.output_guardrails.is_some(),
+                &errored_providers,
+                &[],
             );
 
             let error_msg = validation
@@ -268,7 +477,8 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
                 "INPUT_VALIDATION_FAILED".to_string(),
                 error_msg,
                 metadata,
-            ));
+            )
+            .with_guardrails(&report));
         }
 
         log::info!("Input guardrails validation PASSED");
@@ -279,13 +489,34 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
         }
     }
 
+    // Canary injection (AFTER input guardrails, which only see user-provided content)
+    // The token is generated per request and never stored in config or metadata
+    let canary = config
+        .canary
+        .map(|placement| (placement, CanaryToken::generate()));
+    // Spotlighting (AFTER input guardrails) marks the untrusted user content as data
+    let (system_prompt, llm_user_prompt) = match &config.spotlighting {
+        Some(spotlighting) => {
+            log::info!("Spotlighting untrusted input ({spotlighting:?})");
+            spotlighting.apply(&config.system_prompt, &user_prompt)
+        }
+        None => (config.system_prompt.clone(), user_prompt.clone()),
+    };
+    let (system_prompt, llm_user_prompt) = match &canary {
+        Some((placement, token)) => {
+            log::info!("Canary token injected ({placement:?})");
+            token.apply(*placement, &system_prompt, &llm_user_prompt)
+        }
+        None => (system_prompt, llm_user_prompt),
+    };
+
     // 3. Token validation (if enabled)
     let tokens_estimated = if config.validate_tokens {
         // Use model-specific token estimation if model is recognized
         // For estimation purposes only, use DEFAULT_MAX_TOKENS if not specified
         let estimator = TokenEstimator::new_for_model(
-            &config.system_prompt,
-            &user_prompt,
+            &system_prompt,
+            &llm_user_prompt,
             config
                 .max_tokens
                 .unwrap_or(constants::llm_defaults::DEFAULT_MAX_TOKENS),
@@ -311,6 +542,8 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
                     required,
                     start_time.elapsed().as_millis() as u64,
                     config.output_guardrails.is_some(),
+                    &errored_providers,
+                    &[],
                 );
                 return Ok(CliOutput::error(
                     "CONTEXT_LIMIT_EXCEEDED".to_string(),
@@ -319,7 +552,8 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
                         required, limit, required - limit
                     ),
                     metadata,
-                ));
+                )
+                .with_guardrails(&report));
             }
         }
         required
@@ -327,8 +561,8 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
         // Even when validation is disabled, use model-specific estimation for metadata
         // For estimation purposes only, use DEFAULT_MAX_TOKENS if not specified
         TokenEstimator::new_for_model(
-            &config.system_prompt,
-            &user_prompt,
+            &system_prompt,
+            &llm_user_prompt,
             config
                 .max_tokens
                 .unwrap_or(constants::llm_defaults::DEFAULT_MAX_TOKENS),
@@ -337,27 +571,158 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
         .total_tokens_required()
     };
 
-    // 4. LLM invocation
+    // 4. LLM invocation (with optional self-correcting retries against the response schema)
     let client = LlmClient::new(config.api_url.clone(), config.provider);
-    let response = client
-        .invoke(InvokeParams {
-            model: &config.model,
-            system_prompt: &config.system_prompt,
-            user_prompt: &user_prompt,
-            temperature: config.temperature,
-            max_tokens: config.max_tokens,
-            seed: config.seed,
-            api_key: config.api_key.as_deref(),
-            timeout_secs: config.timeout_secs,
-            response_format: config.response_format.as_ref(),
-        })
-        .await?;
+    let json_format = config
+        .response_format
+        .as_ref()
+        .is_some_and(ResponseFormat::is_json);
+    let schema_guardrail = match &config.response_format {
+        Some(ResponseFormat::JsonSchema { json_schema }) if config.schema_retries > 0 => Some(
+            JsonSchemaGuardrail::from_schema(&json_schema.name, &json_schema.schema)?,
+        ),
+        _ => None,
+    };
+    let mut schema_report = schema_guardrail
+        .as_ref()
+        .map(|_| SchemaValidationReport::default());
+    let mut history = Vec::new();
+    let mut turn_prompt = llm_user_prompt.clone();
+    let mut json_repairs = Vec::new();
+    let response = loop {
+        let raw_response = client
+            .invoke(InvokeParams {
+                model: &config.model,
+                system_prompt: &system_prompt,
+                user_prompt: &turn_prompt,
+                temperature: config.temperature,
+                max_tokens: config.max_tokens,
+                seed: config.seed,
+                api_key: config.api_key.as_deref(),
+                timeout_secs: config.timeout_secs,
+                response_format: config.response_format.as_ref(),
+                history: &history,
+            })
+            .await?;
+
+        // Lenient JSON extraction (BEFORE validation, so it sees the extracted value)
+        let response = if config.lenient_json && json_format {
+            let (response, repairs) = extract_lenient_json(raw_response.clone());
+            json_repairs = repairs;
+            response
+        } else {
+            raw_response.clone()
+        };
+
+        let (Some(guardrail), Some(attempts)) = (&schema_guardrail, schema_report.as_mut()) else {
+            break response;
+        };
+        let validation = guardrail.validate(&response).await?;
+        attempts.push(validation.violations.clone());
+        if validation.passed {
+            break response;
+        }
+
+        let mut error_msg = validation
+            .violations
+            .iter()
+            .map(|v| format!("{}: {}", v.rule, v.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let feedback = schema_feedback_prompt(&validation.violations);
+        // Each retry replays the whole conversation, so it can outgrow the context limit
+        let exceeds_limit = match config.context_limit {
+            Some(limit) if config.validate_tokens => {
+                let required = retry_tokens_required(
+                    &config,
+                    &system_prompt,
+                    &history,
+                    &[&turn_prompt, &raw_response, &feedback],
+                );
+                (required > limit).then(|| {
+                    format!(
+                        "retry context requirement ({required} tokens) exceeds model limit ({limit} tokens)"
+                    )
+                })
+            }
+            _ => None,
+        };
+        if attempts.total_attempts > config.schema_retries || exceeds_limit.is_some() {
+            log::error!(
+                "Response failed the response schema after {} attempts",
+                attempts.total_attempts
+            );
+            if let Some(reason) = exceeds_limit {
+                log::error!("Schema retries stopped: {reason}");
+                error_msg = format!("{error_msg}; schema retries stopped: {reason}");
+            }
+            let metadata = create_metadata(
+                &config,
+                &user_prompt,
+                tokens_estimated,
+                start_time.elapsed().as_millis() as u64,
+                config.output_guardrails.is_some(),
+                &errored_providers,
+                &json_repairs,
+            );
+            return Ok(CliOutput::error(
+                "OUTPUT_VALIDATION_FAILED".to_string(),
+                error_msg,
+                metadata,
+            )
+            .with_guardrails(&report)
+            .with_schema_validation(Some(attempts)));
+        }
+
+        // Feed the errors back as a follow-up turn
+        log::warn!(
+            "Response failed the response schema (attempt {} of {}), retrying: {error_msg}",
+            attempts.total_attempts,
+            config.schema_retries + 1
+        );
+        history.push(ConversationTurn {
+            user: std::mem::replace(&mut turn_prompt, feedback),
+            assistant: raw_response,
+        });
+    };
 
     // 5. Output guardrails (if enabled)
     let output_guardrails_enabled = config.output_guardrails.is_some();
+    // The extracted PDF is the only source of truth for PDF-based answers
+    let context = ValidationContext::output(&config.system_prompt, &user_prompt, &response)
+        .with_source_text(config.pdf_input.as_ref().map(|_| user_prompt.as_str()))
+        .with_canary(canary.as_ref().map(|(_, token)| token))
+        .with_metadata("model", &config.model);
+
+    // Canary check always runs in canary mode, before any configured output guardrails
+    if canary.is_some() {
+        let stage = report.output.get_or_insert_with(Default::default);
+        let validation = run_guardrail(&CanaryGuardrail::new(), &response, &context, stage).await?;
+        if !validation.passed {
+            let metadata = create_metadata(
+                &config,
+                &user_prompt,
+                tokens_estimated,
+                start_time.elapsed().as_millis() as u64,
+                output_guardrails_enabled,
+                &errored_providers,
+                &json_repairs,
+            );
+            return Ok(CliOutput::error(
+                "OUTPUT_VALIDATION_FAILED".to_string(),
+                format!("{RULE_CANARY_LEAKED}: canary leaked"),
+                metadata,
+            )
+            .with_guardrails(&report)
+            .with_schema_validation(schema_report.as_ref()));
+        }
+    }
+
     if let Some(guardrail_config) = &config.output_guardrails {
         let guardrail = create_guardrail_provider(guardrail_config)?;
-        let validation = guardrail.validate(&response).await?;
+        let stage = report.output.get_or_insert_with(Default::default);
+        let validation = run_guardrail(guardrail.as_ref(), &response, &context, stage).await?;
+        errored_providers.extend(guardrails::errored_providers(&validation));
 
         if !validation.passed {
             let metadata = create_metadata(
@@ -366,6 +731,8 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
                 tokens_estimated,
                 start_time.elapsed().as_millis() as u64,
                 true, // output guardrails are enabled (we're in this block)
+                &errored_providers,
+                &json_repairs,
             );
 
             let error_msg = validation
@@ -379,7 +746,9 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
                 "OUTPUT_VALIDATION_FAILED".to_string(),
                 error_msg,
                 metadata,
-            ));
+            )
+            .with_guardrails(&report)
+            .with_schema_validation(schema_report.as_ref()));
         }
 
         // Log quality score and warnings
@@ -400,11 +769,13 @@ async fn evaluate_internal(config: EvaluationConfig) -> Result<CliOutput, CliErr
         tokens_estimated,
         start_time.elapsed().as_millis() as u64,
         output_guardrails_enabled,
+        &errored_providers,
+        &json_repairs,
     );
 
-    Ok(CliOutput::success(
-        response,
-        metadata,
-        config.response_format.as_ref(),
-    ))
+    Ok(
+        CliOutput::success(response, metadata, config.response_format.as_ref())
+            .with_guardrails(&report)
+            .with_schema_validation(schema_report.as_ref()),
+    )
 }
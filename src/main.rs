@@ -109,6 +109,16 @@ struct Args {
     #[serde(default = "default_response_format_schema_strict")]
     response_format_schema_strict: bool,
 
+    /// Extract JSON from fenced or chatty responses before validation (JSON formats only)
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lenient_json: Option<bool>,
+
+    /// Follow-up attempts when a response fails the json-schema response format (max 10)
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_retries: Option<u32>,
+
     /// API key for authentication (direct value)
     #[arg(long, conflicts_with = "api_key_name")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -185,6 +195,8 @@ impl Default for Args {
             response_format: None,
             response_format_schema: None,
             response_format_schema_strict: true,
+            lenient_json: None,
+            schema_retries: None,
             api_key: None,
             api_key_name: None,
             timeout_secs: None,
@@ -374,6 +386,8 @@ async fn main() {
                 pdf_input: None,
                 input_guardrails_enabled: None,
                 output_guardrails_enabled: None,
+                guardrail_provider_errors: None,
+                json_repairs: None,
             };
 
             // Create error output
@@ -588,6 +602,12 @@ async fn run(args: Args) -> Result<CliOutput, CliError> {
         }
         None => {}
     }
+    if let Some(lenient_json) = merged_args.lenient_json {
+        builder = builder.lenient_json(lenient_json);
+    }
+    if let Some(schema_retries) = merged_args.schema_retries {
+        builder = builder.schema_retries(schema_retries);
+    }
 
     // Merge config file values (lower priority than CLI args)
     if let Some(file_cfg) = file_config.as_ref() {
@@ -621,6 +641,8 @@ async fn run(args: Args) -> Result<CliOutput, CliError> {
             .map(|f| f.to_string())
             .unwrap_or_else(|| "not set".to_string())
     );
+    log::debug!("Lenient JSON: {}", config.lenient_json);
+    log::debug!("Schema retries: {}", config.schema_retries);
     // Log API key source (not the actual key value)
     let api_key_source = match (
         args.api_key.as_ref(),
@@ -42,6 +42,11 @@ impl ResponseFormat {
             },
         }
     }
+
+    /// Whether the format requests a JSON response (json-object or json-schema)
+    pub fn is_json(&self) -> bool {
+        matches!(self, Self::JsonObject | Self::JsonSchema { .. })
+    }
 }
 
 impl fmt::Display for ResponseFormat {
@@ -1,4 +1,8 @@
-use crate::models::ResponseFormat;
+use crate::{
+    guardrails::{GuardrailProviderReport, Violation},
+    json_extraction::JsonRepair,
+    models::ResponseFormat,
+};
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -7,6 +11,12 @@ pub struct CliOutput {
     pub response: Option<serde_json::Value>,
     pub metadata: Metadata,
     pub error: Option<ErrorInfo>,
+    /// Structured guardrail results (only when guardrails ran)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrails: Option<GuardrailsReport>,
+    /// Response schema validation attempts (only when schema retries are enabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_validation: Option<SchemaValidationReport>,
 }
 
 #[derive(Serialize)]
@@ -50,6 +60,14 @@ pub struct Metadata {
     pub input_guardrails_enabled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_guardrails_enabled: Option<bool>,
+    /// Composite guardrail providers that errored (protection was degraded)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrail_provider_errors: Option<Vec<String>>,
+
+    // Response processing
+    /// Repairs applied by lenient JSON extraction (absent when none were needed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_repairs: Option<Vec<JsonRepair>>,
 }
 
 #[derive(Serialize)]
@@ -58,6 +76,78 @@ pub struct ErrorInfo {
     pub message: String,
 }
 
+/// Guardrail results per pipeline stage
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GuardrailsReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<GuardrailStageReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<GuardrailStageReport>,
+}
+
+impl GuardrailsReport {
+    /// Whether no guardrail stage ran
+    pub fn is_empty(&self) -> bool {
+        self.input.is_none() && self.output.is_none()
+    }
+}
+
+/// Guardrail results for one stage (input or output)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GuardrailStageReport {
+    /// Whether every provider in the stage passed
+    pub passed: bool,
+    /// Total time spent in the stage's guardrails
+    pub latency_ms: u64,
+    /// Results of the providers run at this stage, in execution order
+    pub providers: Vec<GuardrailProviderReport>,
+}
+
+impl GuardrailStageReport {
+    /// Record a provider result
+    pub fn push(&mut self, report: GuardrailProviderReport) {
+        self.passed = self.providers.iter().all(|p| p.result.passed) && report.result.passed;
+        self.latency_ms += report.latency_ms;
+        self.providers.push(report);
+    }
+}
+
+/// Response schema validation attempts made by the self-correcting retry loop
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SchemaValidationReport {
+    /// Whether the final response conformed to the schema
+    pub passed: bool,
+    /// Total LLM invocations (1 = the first response conformed)
+    pub total_attempts: u32,
+    /// Result of each attempt, in order
+    pub attempts: Vec<SchemaAttemptReport>,
+}
+
+impl SchemaValidationReport {
+    /// Record the result of an attempt
+    pub fn push(&mut self, violations: Vec<Violation>) {
+        self.total_attempts += 1;
+        self.passed = violations.is_empty();
+        self.attempts.push(SchemaAttemptReport {
+            attempt: self.total_attempts,
+            passed: self.passed,
+            violations,
+        });
+    }
+}
+
+/// Schema validation result for one attempt
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaAttemptReport {
+    /// Attempt number (1-based)
+    pub attempt: u32,
+    /// Whether the response conformed to the schema
+    pub passed: bool,
+    /// Validation errors fed back to the model (empty when passed)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
+}
+
 impl CliOutput {
     /// Create a success output
     pub fn success(
@@ -93,6 +183,8 @@ impl CliOutput {
             response: Some(parsed_response),
             metadata,
             error: None,
+            guardrails: None,
+            schema_validation: None,
         }
     }
 
@@ -108,8 +200,24 @@ impl CliOutput {
             response: None,
             metadata,
             error: Some(ErrorInfo { code, message }),
+            guardrails: None,
+            schema_validation: None,
         }
     }
+
+    /// Attach the guardrail results (ignored when no guardrail ran)
+    pub fn with_guardrails(mut self, report: &GuardrailsReport) -> Self {
+        if !report.is_empty() {
+            self.guardrails = Some(report.clone());
+        }
+        self
+    }
+
+    /// Attach the schema validation attempts (no-op when the retry loop didn't run)
+    pub fn with_schema_validation(mut self, report: Option<&SchemaValidationReport>) -> Self {
+        self.schema_validation = report.cloned();
+        self
+    }
 }
 
 #[cfg(test)]
@@ -150,6 +258,8 @@ mod tests {
             pdf_input: None,
             input_guardrails_enabled: None,
             output_guardrails_enabled: None,
+            guardrail_provider_errors: None,
+            json_repairs: None,
         }
     }
 
@@ -23,6 +23,7 @@ use async_trait::async_trait;
 ///     api_key: Some("sk-..."),
 ///     timeout_secs: 30,
 ///     response_format: None,
+///     history: &[],
 /// };
 /// ```
 #[derive(Debug, Clone)]
@@ -34,6 +35,9 @@ pub struct InvokeParams<'a> {
     pub system_prompt: &'a str,
 
     /// User prompt (the actual query/input)
+    ///
+    /// Empty after a non-empty `history`, the conversation ends on the last assistant
+    /// reply instead (for classifiers that judge that reply).
     pub user_prompt: &'a str,
 
     /// Sampling temperature (0.0 = deterministic, 2.0 = maximum randomness)
@@ -53,6 +57,25 @@ pub struct InvokeParams<'a> {
 
     /// Optional response format constraint (support varies by provider)
     pub response_format: Option<&'a ResponseFormat>,
+
+    /// Earlier exchanges sent before `user_prompt` (empty for single-turn requests)
+    pub history: &'a [ConversationTurn],
+}
+
+impl InvokeParams<'_> {
+    /// Whether `user_prompt` is sent as the final message after the history
+    pub fn has_user_turn(&self) -> bool {
+        !self.user_prompt.is_empty() || self.history.is_empty()
+    }
+}
+
+/// A completed user/assistant exchange replayed in a multi-turn request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationTurn {
+    /// User message
+    pub user: String,
+    /// Assistant reply
+    pub assistant: String,
 }
 
 /// LLM provider trait for extensibility
@@ -86,6 +109,7 @@ pub struct InvokeParams<'a> {
 ///     api_key: Some("sk-..."),
 ///     timeout_secs: 30,
 ///     response_format: None,
+///     history: &[],
 /// };
 ///
 /// let response = provider.invoke(params).await?;
@@ -4,7 +4,8 @@ use crate::{
         AnthropicContentBlock, AnthropicMessage, AnthropicOutputConfig, AnthropicOutputFormat,
         AnthropicRequest, AnthropicResponse, ResponseFormat,
     },
-    provider::{InvokeParams, LlmProvider},
+    provider::{InvokeParams, LlmProvider, ProviderType},
+    schema_dialect,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -78,7 +79,10 @@ impl LlmProvider for AnthropicProvider {
             match params.response_format {
                 Some(ResponseFormat::JsonSchema { json_schema }) => Some(AnthropicOutputConfig {
                     format: AnthropicOutputFormat::JsonSchema {
-                        schema: json_schema.schema.clone(),
+                        schema: schema_dialect::provider_schema(
+                            json_schema,
+                            ProviderType::Anthropic,
+                        ),
                     },
                 }),
                 Some(ResponseFormat::JsonObject) => {
@@ -92,16 +96,31 @@ impl LlmProvider for AnthropicProvider {
             }
         };
 
+        let mut messages = Vec::with_capacity(params.history.len() * 2 + 1);
+        for turn in params.history {
+            messages.push(AnthropicMessage {
+                role: "user".to_string(),
+                content: turn.user.clone(),
+            });
+            messages.push(AnthropicMessage {
+                role: "assistant".to_string(),
+                content: turn.assistant.clone(),
+            });
+        }
+        if params.has_user_turn() {
+            messages.push(AnthropicMessage {
+                role: "user".to_string(),
+                content: params.user_prompt.to_string(),
+            });
+        }
+
         let request = AnthropicRequest {
             model: match self.mode {
                 AnthropicMode::Direct => Some(params.model.to_string()),
                 AnthropicMode::Vertex => None,
             },
             max_tokens,
-            messages: vec![AnthropicMessage {
-                role: "user".to_string(),
-                content: params.user_prompt.to_string(),
-            }],
+            messages,
             system: if params.system_prompt.is_empty() {
                 None
             } else {
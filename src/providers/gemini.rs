@@ -4,7 +4,8 @@ use crate::{
         GeminiContent, GeminiGenerationConfig, GeminiPart, GeminiRequest, GeminiResponse,
         GeminiSystemInstruction, ResponseFormat,
     },
-    provider::{InvokeParams, LlmProvider},
+    provider::{InvokeParams, LlmProvider, ProviderType},
+    schema_dialect,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -26,6 +27,16 @@ impl GeminiProvider {
     }
 }
 
+/// Single-part text content with the given role
+fn text_content(role: &str, text: &str) -> GeminiContent {
+    GeminiContent {
+        role: Some(role.to_string()),
+        parts: vec![GeminiPart {
+            text: Some(text.to_string()),
+        }],
+    }
+}
+
 #[async_trait]
 impl LlmProvider for GeminiProvider {
     async fn invoke(&self, params: InvokeParams<'_>) -> Result<String, CliError> {
@@ -38,7 +49,10 @@ impl LlmProvider for GeminiProvider {
             Some(ResponseFormat::JsonObject) => (Some("application/json".to_string()), None),
             Some(ResponseFormat::JsonSchema { json_schema }) => (
                 Some("application/json".to_string()),
-                Some(json_schema.schema.clone()),
+                Some(schema_dialect::provider_schema(
+                    json_schema,
+                    ProviderType::Gemini,
+                )),
             ),
             Some(ResponseFormat::Text) | None => (None, None),
         };
@@ -53,14 +67,19 @@ impl LlmProvider for GeminiProvider {
             })
         };
 
+        // Gemini names the assistant role "model"
+        let mut contents = Vec::with_capacity(params.history.len() * 2 + 1);
+        for turn in params.history {
+            contents.push(text_content("user", &turn.user));
+            contents.push(text_content("model", &turn.assistant));
+        }
+        if params.has_user_turn() {
+            contents.push(text_content("user", params.user_prompt));
+        }
+
         let request = GeminiRequest {
             system_instruction,
-            contents: vec![GeminiContent {
-                role: Some("user".to_string()),
-                parts: vec![GeminiPart {
-                    text: Some(params.user_prompt.to_string()),
-                }],
-            }],
+            contents,
             generation_config: Some(GeminiGenerationConfig {
                 temperature: Some(params.temperature),
                 max_output_tokens: params.max_tokens,
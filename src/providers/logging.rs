@@ -1,5 +1,45 @@
 use crate::error::CliError;
+use once_cell::sync::Lazy;
 use serde::Serialize;
+use std::sync::Mutex;
+
+/// Per-request secrets (e.g. canary tokens) that must never reach the debug logs
+static REDACTED_VALUES: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Keeps a value redacted from request/response logs until dropped
+pub(crate) struct RedactionGuard {
+    value: String,
+}
+
+impl Drop for RedactionGuard {
+    fn drop(&mut self) {
+        if let Ok(mut values) = REDACTED_VALUES.lock() {
+            if let Some(index) = values.iter().position(|v| *v == self.value) {
+                values.swap_remove(index);
+            }
+        }
+    }
+}
+
+/// Redact `value` from request/response logs for the lifetime of the returned guard
+pub(crate) fn redact_in_logs(value: &str) -> RedactionGuard {
+    if let Ok(mut values) = REDACTED_VALUES.lock() {
+        values.push(value.to_string());
+    }
+    RedactionGuard {
+        value: value.to_string(),
+    }
+}
+
+/// Replace registered secret values with a placeholder
+fn redact(text: String) -> String {
+    let Ok(values) = REDACTED_VALUES.lock() else {
+        return text;
+    };
+    values.iter().fold(text, |text, value| {
+        text.replace(value.as_str(), "[REDACTED]")
+    })
+}
 
 /// Handle non-success HTTP responses uniformly across providers.
 ///
@@ -42,6 +82,7 @@ pub async fn handle_error_response(response: reqwest::Response) -> CliError {
 /// Log request JSON for debugging (pretty-printed if possible)
 pub fn log_request<T: Serialize>(request: &T) {
     if let Ok(request_json) = serde_json::to_string_pretty(request) {
+        let request_json = redact(request_json);
         log::debug!("Request JSON sent to API:\n{request_json}");
     }
 }
@@ -52,6 +93,7 @@ pub fn log_response(response_text: &str) {
     let display_text = serde_json::from_str::<serde_json::Value>(response_text)
         .and_then(|v| serde_json::to_string_pretty(&v))
         .unwrap_or_else(|_| response_text.to_string());
+    let display_text = redact(display_text);
     log::debug!("Response JSON received from API:\n{display_text}");
 }
 
@@ -83,6 +125,19 @@ mod tests {
         log_response(response);
     }
 
+    #[test]
+    fn test_redact_in_logs_guard_scope() {
+        let secret = "redaction-test-secret-1f3a";
+        {
+            let _guard = redact_in_logs(secret);
+            assert_eq!(
+                redact(format!("prefix {secret} suffix")),
+                "prefix [REDACTED] suffix"
+            );
+        }
+        assert_eq!(redact(secret.to_string()), secret);
+    }
+
     #[test]
     fn test_log_response_invalid_json() {
         let response = "not valid json";
@@ -1,7 +1,7 @@
 mod anthropic;
 mod detection;
 mod gemini;
-mod logging;
+pub(crate) mod logging;
 mod ollama;
 mod openai;
 
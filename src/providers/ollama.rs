@@ -1,7 +1,7 @@
 use crate::{
     error::CliError,
     models::{OllamaOptions, OllamaRequest, OllamaResponse},
-    provider::{InvokeParams, LlmProvider},
+    provider::{ConversationTurn, InvokeParams, LlmProvider},
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -23,6 +23,23 @@ impl OllamaProvider {
     }
 }
 
+/// Render earlier exchanges into the prompt (/api/generate has no message list)
+///
+/// An empty `user_prompt` ends the prompt on the last assistant reply.
+fn flatten_history(history: &[ConversationTurn], user_prompt: &str) -> String {
+    if history.is_empty() {
+        return user_prompt.to_string();
+    }
+    let mut turns: Vec<String> = history
+        .iter()
+        .map(|turn| format!("User: {}\n\nAssistant: {}", turn.user, turn.assistant))
+        .collect();
+    if !user_prompt.is_empty() {
+        turns.push(format!("User: {user_prompt}"));
+    }
+    turns.join("\n\n")
+}
+
 #[async_trait]
 impl LlmProvider for OllamaProvider {
     async fn invoke(&self, params: InvokeParams<'_>) -> Result<String, CliError> {
@@ -30,7 +47,7 @@ impl LlmProvider for OllamaProvider {
         let request = OllamaRequest {
             model: params.model.to_string(),
             system: params.system_prompt.to_string(),
-            prompt: params.user_prompt.to_string(),
+            prompt: flatten_history(params.history, params.user_prompt),
             stream: false,
             options: OllamaOptions {
                 temperature: params.temperature,
@@ -84,6 +101,24 @@ mod tests {
         assert_eq!(provider.name(), "Ollama");
     }
 
+    #[test]
+    fn test_flatten_history() {
+        assert_eq!(flatten_history(&[], "Hello"), "Hello");
+
+        let history = [ConversationTurn {
+            user: "Give me JSON".to_string(),
+            assistant: "{\"a\": }".to_string(),
+        }];
+        assert_eq!(
+            flatten_history(&history, "Fix it"),
+            "User: Give me JSON\n\nAssistant: {\"a\": }\n\nUser: Fix it"
+        );
+        assert_eq!(
+            flatten_history(&history, ""),
+            "User: Give me JSON\n\nAssistant: {\"a\": }"
+        );
+    }
+
     #[test]
     fn test_ollama_provider_supports_streaming() {
         let provider = OllamaProvider::new("http://localhost:11434/api/generate".to_string());
@@ -1,7 +1,8 @@
 use crate::{
     error::CliError,
-    models::{Message, OpenAIRequest, OpenAIResponse},
-    provider::{InvokeParams, LlmProvider},
+    models::{JsonSchemaDefinition, Message, OpenAIRequest, OpenAIResponse, ResponseFormat},
+    provider::{InvokeParams, LlmProvider, ProviderType},
+    schema_dialect,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -26,22 +27,46 @@ impl OpenAIProvider {
 #[async_trait]
 impl LlmProvider for OpenAIProvider {
     async fn invoke(&self, params: InvokeParams<'_>) -> Result<String, CliError> {
+        let mut messages = Vec::with_capacity(params.history.len() * 2 + 2);
+        // Chat templates of classifier models (e.g. Llama Guard) reject a system message
+        if !params.system_prompt.is_empty() {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: params.system_prompt.to_string(),
+            });
+        }
+        for turn in params.history {
+            messages.push(Message {
+                role: "user".to_string(),
+                content: turn.user.clone(),
+            });
+            messages.push(Message {
+                role: "assistant".to_string(),
+                content: turn.assistant.clone(),
+            });
+        }
+        if params.has_user_turn() {
+            messages.push(Message {
+                role: "user".to_string(),
+                content: params.user_prompt.to_string(),
+            });
+        }
+
         let request = OpenAIRequest {
             model: params.model.to_string(),
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: params.system_prompt.to_string(),
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: params.user_prompt.to_string(),
-                },
-            ],
+            messages,
             temperature: params.temperature,
             max_tokens: params.max_tokens,
             seed: params.seed,
-            response_format: params.response_format.cloned(),
+            response_format: params.response_format.map(|format| match format {
+                ResponseFormat::JsonSchema { json_schema } => ResponseFormat::JsonSchema {
+                    json_schema: JsonSchemaDefinition {
+                        schema: schema_dialect::provider_schema(json_schema, ProviderType::OpenAI),
+                        ..json_schema.clone()
+                    },
+                },
+                other => other.clone(),
+            }),
         };
 
         log_request(&request);
@@ -0,0 +1,160 @@
+//! Response schemas derived from Rust types
+//!
+//! [`ResponseSchema`] describes the JSON Schema of a type that an LLM response
+//! is deserialized into. It is implemented for every type deriving
+//! [`schemars::JsonSchema`] and [`serde::Deserialize`], so library users can
+//! request structured output without writing schema files:
+//!
+//! ```rust,no_run
+//! use fortified_llm_client::{evaluate_typed, schemars::JsonSchema, EvaluationConfig};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! #[schemars(crate = "fortified_llm_client::schemars")]
+//! struct Person {
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! # async fn run(config: EvaluationConfig) -> Result<(), fortified_llm_client::CliError> {
+//! let person: Person = evaluate_typed(config).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    error::CliError, guardrails::json_schema::schema_violations, schema_validator, ResponseFormat,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// A type an LLM response can be requested as and deserialized into
+///
+/// Implemented for all `schemars::JsonSchema + DeserializeOwned` types; implement it
+/// manually to supply a hand-written schema instead.
+pub trait ResponseSchema: DeserializeOwned {
+    /// Name sent with the json-schema response format
+    fn schema_name() -> String;
+
+    /// JSON Schema the response must conform to
+    fn json_schema() -> Value;
+}
+
+impl<T: schemars::JsonSchema + DeserializeOwned> ResponseSchema for T {
+    fn schema_name() -> String {
+        <T as schemars::JsonSchema>::schema_name().into_owned()
+    }
+
+    fn json_schema() -> Value {
+        schemars::schema_for!(T).to_value()
+    }
+}
+
+/// Strict json-schema response format for `T`
+///
+/// The name is reduced to the characters providers accept (`a-z`, `A-Z`, `0-9`, `_`, `-`).
+pub fn response_format<T: ResponseSchema>() -> ResponseFormat {
+    let name: String = T::schema_name()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    ResponseFormat::json_schema(name, T::json_schema(), true)
+}
+
+/// Validate a response against `T`'s schema and deserialize it
+///
+/// Both failures are reported as [`CliError::ResponseTypeMismatch`], naming the type and
+/// the offending locations.
+pub fn deserialize_response<T: ResponseSchema>(response: Value) -> Result<T, CliError> {
+    let type_name = std::any::type_name::<T>();
+    let validator = schema_validator::compile_json_schema(&T::json_schema())?;
+    let violations = schema_violations(validator.iter_errors(&response));
+    if !violations.is_empty() {
+        let details = violations
+            .iter()
+            .map(|v| format!("{}: {}", v.location.as_deref().unwrap_or("/"), v.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(CliError::ResponseTypeMismatch {
+            type_name: type_name.to_string(),
+            message: format!("response does not conform to the schema ({details})"),
+        });
+    }
+
+    serde_json::from_value(response).map_err(|e| CliError::ResponseTypeMismatch {
+        type_name: type_name.to_string(),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+        nickname: Option<String>,
+    }
+
+    #[test]
+    fn test_response_format_from_type() {
+        let ResponseFormat::JsonSchema { json_schema } = response_format::<Person>() else {
+            panic!("expected json-schema format");
+        };
+        assert_eq!(json_schema.name, "Person");
+        assert_eq!(json_schema.strict, Some(true));
+        assert_eq!(json_schema.schema["type"], "object");
+        assert_eq!(json_schema.schema["required"], json!(["name", "age"]));
+        assert!(schema_validator::validate_json_schema(&json_schema.schema).is_ok());
+    }
+
+    #[test]
+    fn test_generic_type_name_sanitized() {
+        let ResponseFormat::JsonSchema { json_schema } = response_format::<Vec<Person>>() else {
+            panic!("expected json-schema format");
+        };
+        assert!(json_schema
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+    }
+
+    #[test]
+    fn test_deserialize_valid_response() {
+        let person: Person = deserialize_response(json!({"name": "Alice", "age": 30})).unwrap();
+        assert_eq!(
+            person,
+            Person {
+                name: "Alice".to_string(),
+                age: 30,
+                nickname: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_schema_violation() {
+        let err = deserialize_response::<Person>(json!({"name": "Alice", "age": -1})).unwrap_err();
+        assert_eq!(err.code(), "RESPONSE_TYPE_MISMATCH");
+        let message = err.to_string();
+        assert!(message.contains("Person"));
+        assert!(message.contains("/age"));
+    }
+
+    #[test]
+    fn test_deserialize_non_json_response() {
+        let err = deserialize_response::<Person>(json!("Alice is 30")).unwrap_err();
+        assert!(matches!(err, CliError::ResponseTypeMismatch { .. }));
+    }
+}
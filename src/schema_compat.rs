@@ -0,0 +1,444 @@
+//! Provider compatibility checks for JSON Schema response formats
+//!
+//! Each provider's structured-output mode accepts only a subset of JSON Schema.
+//! A schema that is valid for client-side validation may still be rejected by
+//! the API, or be silently weakened. [`check_compatibility`] reports the parts
+//! of a schema a provider is known not to support, so they can be surfaced as
+//! warnings before the request is sent.
+
+use crate::ProviderType;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Keywords OpenAI strict mode rejects
+pub(crate) const OPENAI_STRICT_UNSUPPORTED: &[&str] = &[
+    "allOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "dependentRequired",
+    "dependentSchemas",
+    "patternProperties",
+    "unevaluatedProperties",
+    "propertyNames",
+    "minProperties",
+    "maxProperties",
+];
+
+/// Constraint keywords Anthropic structured outputs do not enforce
+pub(crate) const ANTHROPIC_UNSUPPORTED: &[&str] = &[
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+    "minLength",
+    "maxLength",
+];
+
+/// Keywords outside the OpenAPI schema subset accepted by Gemini
+const GEMINI_UNSUPPORTED: &[&str] = &[
+    "$ref",
+    "$defs",
+    "definitions",
+    "additionalProperties",
+    "const",
+    "oneOf",
+    "allOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "patternProperties",
+    "dependentRequired",
+    "dependentSchemas",
+    "unevaluatedProperties",
+];
+
+/// Keywords whose value is a single subschema
+const SUBSCHEMA_KEYWORDS: &[&str] = &[
+    "additionalProperties",
+    "additionalItems",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+    "contains",
+    "propertyNames",
+    "not",
+    "if",
+    "then",
+    "else",
+];
+
+/// Keywords whose value is an array of subschemas
+const SUBSCHEMA_ARRAY_KEYWORDS: &[&str] = &["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// Keywords whose value maps names to subschemas
+pub(crate) const SUBSCHEMA_MAP_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+    "definitions",
+];
+
+/// A schema construct the target provider does not support
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatibilityIssue {
+    /// JSON Pointer to the offending subschema ("" for the root)
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for CompatibilityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = if self.location.is_empty() {
+            "/"
+        } else {
+            &self.location
+        };
+        write!(f, "{location}: {}", self.message)
+    }
+}
+
+/// Check a JSON Schema against a provider's structured-output restrictions
+///
+/// `strict` is the response format's strict flag; OpenAI only restricts the
+/// schema in strict mode. Returns an empty list when no issues are known.
+pub fn check_compatibility(
+    schema: &Value,
+    provider: ProviderType,
+    strict: bool,
+) -> Vec<CompatibilityIssue> {
+    let mut issues = Vec::new();
+    match provider {
+        ProviderType::OpenAI if strict => {
+            if schema.get("type").and_then(Value::as_str) != Some("object") {
+                issues.push(issue(
+                    "",
+                    "OpenAI strict mode requires the root schema to be an object",
+                ));
+            }
+            visit(schema, "", &mut |s, loc| {
+                check_openai_strict(s, loc, &mut issues)
+            });
+        }
+        ProviderType::OpenAI => {}
+        ProviderType::Anthropic => {
+            visit(schema, "", &mut |s, loc| {
+                check_anthropic(s, loc, &mut issues)
+            });
+        }
+        ProviderType::AnthropicVertex => issues.push(issue(
+            "",
+            "Anthropic on Vertex AI does not support structured outputs; \
+            use schema_retries to enforce the schema client-side",
+        )),
+        ProviderType::Gemini => {
+            visit(schema, "", &mut |s, loc| {
+                for keyword in unsupported_keywords(s, GEMINI_UNSUPPORTED) {
+                    issues.push(issue(
+                        loc,
+                        format!("Gemini response schemas do not support '{keyword}'"),
+                    ));
+                }
+            });
+        }
+        ProviderType::Ollama => issues.push(issue(
+            "",
+            "Ollama /api/generate requests do not carry the response schema; \
+            use schema_retries to enforce it client-side",
+        )),
+    }
+    issues
+}
+
+fn check_openai_strict(
+    schema: &Map<String, Value>,
+    location: &str,
+    issues: &mut Vec<CompatibilityIssue>,
+) {
+    for keyword in unsupported_keywords(schema, OPENAI_STRICT_UNSUPPORTED) {
+        issues.push(issue(
+            location,
+            format!("OpenAI strict mode does not support '{keyword}'"),
+        ));
+    }
+    if !is_object_schema(schema) {
+        return;
+    }
+    if schema.get("additionalProperties") != Some(&Value::Bool(false)) {
+        issues.push(issue(
+            location,
+            "OpenAI strict mode requires 'additionalProperties: false' on every object",
+        ));
+    }
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let missing: Vec<&str> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|p| {
+            p.keys()
+                .map(String::as_str)
+                .filter(|name| !required.contains(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !missing.is_empty() {
+        issues.push(issue(
+            location,
+            format!(
+                "OpenAI strict mode requires all properties to be required; missing: {} \
+                (use a null union type for optional fields)",
+                missing.join(", ")
+            ),
+        ));
+    }
+}
+
+fn check_anthropic(
+    schema: &Map<String, Value>,
+    location: &str,
+    issues: &mut Vec<CompatibilityIssue>,
+) {
+    for keyword in unsupported_keywords(schema, ANTHROPIC_UNSUPPORTED) {
+        issues.push(issue(
+            location,
+            format!("Anthropic structured outputs do not support '{keyword}'"),
+        ));
+    }
+    if schema
+        .get("minItems")
+        .and_then(Value::as_u64)
+        .is_some_and(|n| n > 1)
+    {
+        issues.push(issue(
+            location,
+            "Anthropic structured outputs only support 'minItems' of 0 or 1",
+        ));
+    }
+    if is_object_schema(schema) && schema.get("additionalProperties") != Some(&Value::Bool(false)) {
+        issues.push(issue(
+            location,
+            "Anthropic structured outputs require 'additionalProperties: false' on every object",
+        ));
+    }
+}
+
+/// Keywords from `keywords` present in the schema, in list order
+fn unsupported_keywords<'a>(
+    schema: &'a Map<String, Value>,
+    keywords: &'a [&'a str],
+) -> impl Iterator<Item = &'a str> + 'a {
+    keywords.iter().copied().filter(|k| schema.contains_key(*k))
+}
+
+/// Whether a subschema describes an object (by `type` or `properties`)
+pub(crate) fn is_object_schema(schema: &Map<String, Value>) -> bool {
+    match schema.get("type") {
+        Some(Value::String(t)) => t == "object",
+        Some(Value::Array(types)) => types.iter().any(|t| t == "object"),
+        _ => schema.contains_key("properties"),
+    }
+}
+
+/// Call `check` on every subschema, depth-first, with its JSON Pointer
+fn visit(schema: &Value, location: &str, check: &mut dyn FnMut(&Map<String, Value>, &str)) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    check(object, location);
+    for (keyword, value) in object {
+        let child = format!("{location}/{}", escape_pointer(keyword));
+        let keyword = keyword.as_str();
+        if SUBSCHEMA_KEYWORDS.contains(&keyword) {
+            visit(value, &child, check);
+        } else if keyword == "items" {
+            match value {
+                Value::Array(items) => visit_all(items, &child, check),
+                _ => visit(value, &child, check),
+            }
+        } else if SUBSCHEMA_ARRAY_KEYWORDS.contains(&keyword) {
+            if let Value::Array(items) = value {
+                visit_all(items, &child, check);
+            }
+        } else if SUBSCHEMA_MAP_KEYWORDS.contains(&keyword) {
+            if let Value::Object(map) = value {
+                for (name, subschema) in map {
+                    visit(
+                        subschema,
+                        &format!("{child}/{}", escape_pointer(name)),
+                        check,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn visit_all(items: &[Value], location: &str, check: &mut dyn FnMut(&Map<String, Value>, &str)) {
+    for (index, item) in items.iter().enumerate() {
+        visit(item, &format!("{location}/{index}"), check);
+    }
+}
+
+/// Like [`visit`], but lets `rewrite` modify each subschema before its children are visited
+pub(crate) fn visit_mut(
+    schema: &mut Value,
+    location: &str,
+    rewrite: &mut dyn FnMut(&mut Map<String, Value>, &str),
+) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+    rewrite(object, location);
+    for (keyword, value) in object.iter_mut() {
+        let child = format!("{location}/{}", escape_pointer(keyword));
+        let keyword = keyword.as_str();
+        if SUBSCHEMA_KEYWORDS.contains(&keyword) || (keyword == "items" && value.is_object()) {
+            visit_mut(value, &child, rewrite);
+        } else if keyword == "items" || SUBSCHEMA_ARRAY_KEYWORDS.contains(&keyword) {
+            if let Value::Array(items) = value {
+                for (index, item) in items.iter_mut().enumerate() {
+                    visit_mut(item, &format!("{child}/{index}"), rewrite);
+                }
+            }
+        } else if SUBSCHEMA_MAP_KEYWORDS.contains(&keyword) {
+            if let Value::Object(map) = value {
+                for (name, subschema) in map.iter_mut() {
+                    visit_mut(
+                        subschema,
+                        &format!("{child}/{}", escape_pointer(name)),
+                        rewrite,
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+pub(crate) fn issue(location: &str, message: impl Into<String>) -> CompatibilityIssue {
+    CompatibilityIssue {
+        location: location.to_string(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn strict_person() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "required": ["city"],
+                    "additionalProperties": false
+                }
+            },
+            "required": ["name", "address"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn test_openai_strict_compatible_schema() {
+        assert!(check_compatibility(&strict_person(), ProviderType::OpenAI, true).is_empty());
+    }
+
+    #[test]
+    fn test_openai_strict_nested_object_rules() {
+        let mut schema = strict_person();
+        schema["properties"]["address"]
+            .as_object_mut()
+            .unwrap()
+            .remove("additionalProperties");
+        schema["properties"]["address"]["properties"]["zip"] = json!({ "type": "string" });
+
+        let issues = check_compatibility(&schema, ProviderType::OpenAI, true);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.location == "/properties/address"));
+        assert!(issues[0].message.contains("additionalProperties"));
+        assert!(issues[1].message.contains("missing: zip"));
+    }
+
+    #[test]
+    fn test_openai_strict_unsupported_keywords_and_root() {
+        let schema = json!({
+            "type": "array",
+            "items": { "oneOf": [{ "type": "string" }, { "type": "integer" }] }
+        });
+        let issues = check_compatibility(&schema, ProviderType::OpenAI, true);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].message.contains("root schema"));
+        assert_eq!(issues[1].location, "/items");
+        assert!(issues[1].message.contains("'oneOf'"));
+    }
+
+    #[test]
+    fn test_openai_non_strict_has_no_restrictions() {
+        let schema = json!({ "type": "object", "properties": { "a": { "not": {} } } });
+        assert!(check_compatibility(&schema, ProviderType::OpenAI, false).is_empty());
+    }
+
+    #[test]
+    fn test_anthropic_constraints() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "age": { "type": "integer", "minimum": 0 },
+                "tags": { "type": "array", "minItems": 2, "items": { "type": "string" } }
+            },
+            "additionalProperties": false
+        });
+        let issues = check_compatibility(&schema, ProviderType::Anthropic, true);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].location, "/properties/age");
+        assert_eq!(issues[1].location, "/properties/tags");
+    }
+
+    #[test]
+    fn test_gemini_rejects_refs() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "a": { "$ref": "#/$defs/a" } },
+            "$defs": { "a": { "type": "string" } }
+        });
+        let issues = check_compatibility(&schema, ProviderType::Gemini, false);
+        let rendered: Vec<String> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "/: Gemini response schemas do not support '$defs'",
+                "/properties/a: Gemini response schemas do not support '$ref'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_providers_without_structured_output() {
+        let schema = strict_person();
+        assert_eq!(
+            check_compatibility(&schema, ProviderType::Ollama, true).len(),
+            1
+        );
+        assert_eq!(
+            check_compatibility(&schema, ProviderType::AnthropicVertex, true).len(),
+            1
+        );
+    }
+}
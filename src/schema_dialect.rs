@@ -0,0 +1,593 @@
+//! Provider-specific JSON Schema dialects for structured output
+//!
+//! Providers accept only a subset of JSON Schema in their structured-output
+//! modes: Gemini takes an OpenAPI-style schema without references, OpenAI
+//! strict mode requires closed objects with every property required, and
+//! Anthropic ignores several constraint keywords. [`translate_schema`] rewrites
+//! a user schema into the form a provider accepts and reports every lossy
+//! change.
+//!
+//! Only the request sent to the provider uses the translated schema. Dropped
+//! keywords widen what the provider is asked to produce, so responses are still
+//! validated locally against the original schema (see `schema_retries` and the
+//! `json_schema` guardrail).
+
+use crate::{
+    models::JsonSchemaDefinition,
+    schema_compat::{
+        escape_pointer, is_object_schema, issue, visit_mut, CompatibilityIssue,
+        ANTHROPIC_UNSUPPORTED, OPENAI_STRICT_UNSUPPORTED,
+    },
+    ProviderType,
+};
+use serde_json::{json, Map, Value};
+
+/// Keywords of the OpenAPI schema subset accepted by Gemini `responseSchema`
+const GEMINI_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "properties",
+    "required",
+    "minItems",
+    "maxItems",
+    "minProperties",
+    "maxProperties",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "anyOf",
+    "default",
+    "example",
+    "propertyOrdering",
+];
+
+/// Keywords dropped for Gemini without a warning (identifiers, comments, inlined definitions)
+const GEMINI_IGNORED: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "$anchor",
+    "$defs",
+    "definitions",
+];
+
+/// A schema rewritten for a provider, with the lossy changes that were made
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslatedSchema {
+    pub schema: Value,
+    /// Keywords dropped or relaxed, located by their JSON Pointer in the original schema
+    pub changes: Vec<CompatibilityIssue>,
+}
+
+/// Rewrite a JSON Schema into the dialect a provider's structured output accepts
+///
+/// - Gemini: `$ref`s are inlined and `$defs` removed, `type: [x, "null"]` and
+///   `anyOf` null variants become `nullable: true`, `oneOf` becomes `anyOf`,
+///   string `const` becomes a single-value `enum`, and other keywords outside
+///   the OpenAPI subset are dropped.
+/// - OpenAI (strict mode only): every object gets `additionalProperties: false`
+///   and all of its properties are made required; `oneOf` becomes `anyOf` and
+///   unsupported keywords are dropped.
+/// - Anthropic: every object gets `additionalProperties: false` and unsupported
+///   numeric, length and `minItems` constraints are dropped.
+///
+/// Other providers (and OpenAI without strict mode) receive the schema unchanged.
+pub fn translate_schema(schema: &Value, provider: ProviderType, strict: bool) -> TranslatedSchema {
+    let mut changes = Vec::new();
+    let schema = match provider {
+        ProviderType::Gemini => {
+            let mut translator = GeminiTranslator {
+                root: schema,
+                inlining: Vec::new(),
+                changes: &mut changes,
+            };
+            translator.translate(schema, "")
+        }
+        ProviderType::OpenAI if strict => {
+            let mut schema = schema.clone();
+            visit_mut(&mut schema, "", &mut |object, location| {
+                restrict_openai_strict(object, location, &mut changes)
+            });
+            schema
+        }
+        ProviderType::Anthropic => {
+            let mut schema = schema.clone();
+            visit_mut(&mut schema, "", &mut |object, location| {
+                restrict_anthropic(object, location, &mut changes)
+            });
+            schema
+        }
+        _ => schema.clone(),
+    };
+    TranslatedSchema { schema, changes }
+}
+
+/// Schema to send to `provider` for a json-schema response format
+///
+/// Changes are logged at debug level; they are reported as warnings once at
+/// configuration time.
+pub(crate) fn provider_schema(definition: &JsonSchemaDefinition, provider: ProviderType) -> Value {
+    let strict = definition.strict.unwrap_or(false);
+    let translated = translate_schema(&definition.schema, provider, strict);
+    for change in &translated.changes {
+        log::debug!(
+            "Schema '{}' rewritten for {provider:?}: {change}",
+            definition.name
+        );
+    }
+    translated.schema
+}
+
+fn restrict_openai_strict(
+    object: &mut Map<String, Value>,
+    location: &str,
+    changes: &mut Vec<CompatibilityIssue>,
+) {
+    const DIALECT: &str = "OpenAI strict mode";
+    if let Some(one_of) = object.remove("oneOf") {
+        if object.contains_key("anyOf") {
+            changes.push(issue(
+                location,
+                format!("Dropped 'oneOf' (not supported by {DIALECT})"),
+            ));
+        } else {
+            object.insert("anyOf".to_string(), one_of);
+            changes.push(issue(
+                location,
+                format!("Replaced 'oneOf' with 'anyOf' (not supported by {DIALECT})"),
+            ));
+        }
+    }
+    drop_keywords(
+        object,
+        location,
+        OPENAI_STRICT_UNSUPPORTED,
+        DIALECT,
+        changes,
+    );
+    if !is_object_schema(object) {
+        return;
+    }
+    close_object(object, location, DIALECT, changes);
+
+    let Some(names) = object
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|p| p.keys().cloned().collect::<Vec<_>>())
+    else {
+        return;
+    };
+    let required = object
+        .entry("required")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Value::Array(required) = required else {
+        return;
+    };
+    let optional: Vec<String> = names
+        .into_iter()
+        .filter(|name| !required.iter().any(|r| r == name))
+        .collect();
+    if !optional.is_empty() {
+        required.extend(optional.iter().cloned().map(Value::String));
+        changes.push(issue(
+            location,
+            format!(
+                "Made optional properties required ({DIALECT} requires all properties): {}",
+                optional.join(", ")
+            ),
+        ));
+    }
+}
+
+fn restrict_anthropic(
+    object: &mut Map<String, Value>,
+    location: &str,
+    changes: &mut Vec<CompatibilityIssue>,
+) {
+    const DIALECT: &str = "Anthropic structured outputs";
+    drop_keywords(object, location, ANTHROPIC_UNSUPPORTED, DIALECT, changes);
+    if object
+        .get("minItems")
+        .and_then(Value::as_u64)
+        .is_some_and(|n| n > 1)
+    {
+        object.remove("minItems");
+        changes.push(issue(
+            location,
+            format!("Dropped 'minItems' greater than 1 (not supported by {DIALECT})"),
+        ));
+    }
+    if is_object_schema(object) {
+        close_object(object, location, DIALECT, changes);
+    }
+}
+
+/// Remove `keywords` from a subschema, recording each removal
+fn drop_keywords(
+    object: &mut Map<String, Value>,
+    location: &str,
+    keywords: &[&str],
+    dialect: &str,
+    changes: &mut Vec<CompatibilityIssue>,
+) {
+    for keyword in keywords {
+        if object.remove(*keyword).is_some() {
+            changes.push(issue(
+                location,
+                format!("Dropped '{keyword}' (not supported by {dialect})"),
+            ));
+        }
+    }
+}
+
+/// Set `additionalProperties: false`, recording a change only if another value was replaced
+fn close_object(
+    object: &mut Map<String, Value>,
+    location: &str,
+    dialect: &str,
+    changes: &mut Vec<CompatibilityIssue>,
+) {
+    let previous = object.insert("additionalProperties".to_string(), Value::Bool(false));
+    if previous.is_some_and(|v| v != Value::Bool(false)) {
+        changes.push(issue(
+            location,
+            format!("Replaced 'additionalProperties' with false (required by {dialect})"),
+        ));
+    }
+}
+
+/// Rebuilds a schema in Gemini's OpenAPI subset
+struct GeminiTranslator<'a> {
+    root: &'a Value,
+    /// References currently being inlined, to detect recursion
+    inlining: Vec<String>,
+    changes: &'a mut Vec<CompatibilityIssue>,
+}
+
+impl GeminiTranslator<'_> {
+    fn translate(&mut self, schema: &Value, location: &str) -> Value {
+        let Value::Object(object) = schema else {
+            if *schema == Value::Bool(false) {
+                self.change(
+                    location,
+                    "Replaced 'false' schema with an unconstrained schema",
+                );
+            }
+            return json!({});
+        };
+
+        let mut out = match object.get("$ref").and_then(Value::as_str) {
+            Some(reference) => self.inline(reference, location),
+            None => Map::new(),
+        };
+        for (keyword, value) in object {
+            let child = format!("{location}/{}", escape_pointer(keyword));
+            match keyword.as_str() {
+                "$ref" => {}
+                "type" => match value {
+                    Value::Array(types) => union_type(types, &mut out),
+                    _ => {
+                        out.insert(keyword.clone(), value.clone());
+                    }
+                },
+                "properties" => {
+                    let Value::Object(properties) = value else {
+                        continue;
+                    };
+                    let translated = properties
+                        .iter()
+                        .map(|(name, subschema)| {
+                            let location = format!("{child}/{}", escape_pointer(name));
+                            (name.clone(), self.translate(subschema, &location))
+                        })
+                        .collect();
+                    out.insert(keyword.clone(), Value::Object(translated));
+                }
+                "items" if !value.is_array() => {
+                    let items = self.translate(value, &child);
+                    out.insert(keyword.clone(), items);
+                }
+                "anyOf" | "oneOf" => {
+                    if keyword == "oneOf" {
+                        self.change(location, "Replaced 'oneOf' with 'anyOf'");
+                    }
+                    let Value::Array(variants) = value else {
+                        continue;
+                    };
+                    let variants = variants
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| self.translate(v, &format!("{child}/{i}")))
+                        .collect();
+                    any_of(variants, &mut out);
+                }
+                "const" => {
+                    if value.is_string() {
+                        out.insert("enum".to_string(), json!([value]));
+                    } else {
+                        self.dropped(location, keyword, " (only string constants are supported)");
+                    }
+                }
+                "enum" => {
+                    if value
+                        .as_array()
+                        .is_some_and(|v| v.iter().all(Value::is_string))
+                    {
+                        out.insert(keyword.clone(), value.clone());
+                    } else {
+                        self.dropped(location, keyword, " (only string enums are supported)");
+                    }
+                }
+                k if GEMINI_KEYWORDS.contains(&k) => {
+                    out.insert(keyword.clone(), value.clone());
+                }
+                k if GEMINI_IGNORED.contains(&k) => {}
+                _ => self.dropped(location, keyword, ""),
+            }
+        }
+        Value::Object(out)
+    }
+
+    /// Translated target of a local `$ref`, or an empty schema if it cannot be inlined
+    fn inline(&mut self, reference: &str, location: &str) -> Map<String, Value> {
+        let Some(pointer) = reference.strip_prefix('#') else {
+            self.change(location, format!("Dropped non-local '$ref' '{reference}'"));
+            return Map::new();
+        };
+        if self.inlining.iter().any(|r| r == reference) {
+            self.change(
+                location,
+                format!("Replaced recursive '$ref' '{reference}' with an unconstrained schema"),
+            );
+            return Map::new();
+        }
+        let Some(target) = self.root.pointer(pointer) else {
+            self.change(
+                location,
+                format!("Dropped unresolvable '$ref' '{reference}'"),
+            );
+            return Map::new();
+        };
+
+        self.inlining.push(reference.to_string());
+        let translated = self.translate(target, pointer);
+        self.inlining.pop();
+        match translated {
+            Value::Object(object) => object,
+            _ => Map::new(),
+        }
+    }
+
+    fn dropped(&mut self, location: &str, keyword: &str, reason: &str) {
+        self.change(
+            location,
+            format!("Dropped '{keyword}' (not supported by Gemini){reason}"),
+        );
+    }
+
+    /// Record a change once (inlined definitions are translated at every use)
+    fn change(&mut self, location: &str, message: impl Into<String>) {
+        let change = issue(location, message);
+        if !self.changes.contains(&change) {
+            self.changes.push(change);
+        }
+    }
+}
+
+/// Convert a `type` array to a single type (or `anyOf`) plus `nullable`
+fn union_type(types: &[Value], out: &mut Map<String, Value>) {
+    let non_null: Vec<&Value> = types.iter().filter(|t| *t != "null").collect();
+    if non_null.len() < types.len() {
+        out.insert("nullable".to_string(), Value::Bool(true));
+    }
+    match non_null.as_slice() {
+        [] => {}
+        [single] => {
+            out.insert("type".to_string(), (*single).clone());
+        }
+        several => {
+            let variants = several.iter().map(|t| json!({ "type": t })).collect();
+            out.insert("anyOf".to_string(), Value::Array(variants));
+        }
+    }
+}
+
+/// Add translated `anyOf` variants, folding `{"type": "null"}` into `nullable`
+fn any_of(variants: Vec<Value>, out: &mut Map<String, Value>) {
+    let null = json!({ "type": "null" });
+    let nullable = variants.contains(&null);
+    let mut variants: Vec<Value> = variants.into_iter().filter(|v| *v != null).collect();
+    if nullable {
+        out.insert("nullable".to_string(), Value::Bool(true));
+    }
+    if variants.len() == 1 {
+        if let Some(Value::Object(variant)) = variants.pop() {
+            for (keyword, value) in variant {
+                out.entry(keyword).or_insert(value);
+            }
+        }
+    } else if !variants.is_empty() {
+        out.insert("anyOf".to_string(), Value::Array(variants));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(translated: &TranslatedSchema) -> Vec<String> {
+        translated.changes.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_gemini_inlines_refs_and_nullable() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "billing": { "$ref": "#/$defs/address", "description": "Billing address" },
+                "nickname": { "type": ["string", "null"] },
+                "status": { "const": "active" }
+            },
+            "required": ["billing"],
+            "additionalProperties": false,
+            "$defs": {
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "required": ["city"]
+                }
+            }
+        });
+
+        let translated = translate_schema(&schema, ProviderType::Gemini, true);
+        assert_eq!(
+            translated.schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "billing": {
+                        "type": "object",
+                        "properties": { "city": { "type": "string" } },
+                        "required": ["city"],
+                        "description": "Billing address"
+                    },
+                    "nickname": { "type": "string", "nullable": true },
+                    "status": { "enum": ["active"] }
+                },
+                "required": ["billing"]
+            })
+        );
+        assert_eq!(
+            messages(&translated),
+            vec!["/: Dropped 'additionalProperties' (not supported by Gemini)"]
+        );
+        // The caller's schema is untouched
+        assert!(schema.get("$defs").is_some());
+    }
+
+    #[test]
+    fn test_gemini_any_of_null_and_one_of() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "age": { "anyOf": [{ "type": "integer" }, { "type": "null" }] },
+                "id": { "oneOf": [{ "type": "string" }, { "type": "integer" }] },
+                "value": { "type": ["string", "number"] }
+            }
+        });
+
+        let translated = translate_schema(&schema, ProviderType::Gemini, false);
+        let properties = &translated.schema["properties"];
+        assert_eq!(
+            properties["age"],
+            json!({ "type": "integer", "nullable": true })
+        );
+        assert_eq!(
+            properties["id"],
+            json!({ "anyOf": [{ "type": "string" }, { "type": "integer" }] })
+        );
+        assert_eq!(
+            properties["value"],
+            json!({ "anyOf": [{ "type": "string" }, { "type": "number" }] })
+        );
+        assert_eq!(
+            messages(&translated),
+            vec!["/properties/id: Replaced 'oneOf' with 'anyOf'"]
+        );
+    }
+
+    #[test]
+    fn test_gemini_recursive_ref() {
+        let schema = json!({
+            "$ref": "#/$defs/node",
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": { "children": { "type": "array", "items": { "$ref": "#/$defs/node" } } }
+                }
+            }
+        });
+
+        let translated = translate_schema(&schema, ProviderType::Gemini, false);
+        assert_eq!(
+            translated.schema["properties"]["children"]["items"],
+            json!({})
+        );
+        assert_eq!(translated.changes.len(), 1);
+        assert!(translated.changes[0].message.contains("recursive"));
+    }
+
+    #[test]
+    fn test_openai_strict_closes_objects() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "shape": { "oneOf": [{ "type": "string" }, { "type": "integer" }] }
+            },
+            "required": ["name"],
+            "minProperties": 1
+        });
+
+        let translated = translate_schema(&schema, ProviderType::OpenAI, true);
+        assert_eq!(translated.schema["additionalProperties"], false);
+        assert_eq!(
+            translated.schema["required"],
+            json!(["name", "shape", "tags"])
+        );
+        assert!(translated.schema.get("minProperties").is_none());
+        assert!(translated.schema["properties"]["shape"]
+            .get("anyOf")
+            .is_some());
+        assert!(crate::schema_compat::check_compatibility(
+            &translated.schema,
+            ProviderType::OpenAI,
+            true
+        )
+        .is_empty());
+        assert_eq!(translated.changes.len(), 3);
+    }
+
+    #[test]
+    fn test_openai_non_strict_unchanged() {
+        let schema = json!({ "type": "object", "properties": { "a": { "oneOf": [] } } });
+        let translated = translate_schema(&schema, ProviderType::OpenAI, false);
+        assert_eq!(translated.schema, schema);
+        assert!(translated.changes.is_empty());
+    }
+
+    #[test]
+    fn test_anthropic_drops_constraints() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "age": { "type": "integer", "minimum": 0 },
+                "tags": { "type": "array", "minItems": 2, "items": { "type": "string" } }
+            },
+            "additionalProperties": true
+        });
+
+        let translated = translate_schema(&schema, ProviderType::Anthropic, true);
+        assert_eq!(
+            translated.schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "age": { "type": "integer" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "additionalProperties": false
+            })
+        );
+        assert_eq!(translated.changes.len(), 3);
+    }
+}
@@ -3,14 +3,19 @@
 //! Provides validation of user-provided JSON Schemas against the JSON Schema metaschema
 //! to ensure schemas are well-formed before using them with LLM response_format.
 
-use crate::error::CliError;
+use crate::{error::CliError, schema_compat::SUBSCHEMA_MAP_KEYWORDS};
 use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+};
 
 /// Validate a JSON Schema by attempting to compile it
 ///
 /// This ensures the schema is well-formed and can be used for validation.
 /// Uses the jsonschema crate's built-in validation which checks against
-/// JSON Schema Draft 7 specification.
+/// the draft declared by `$schema` (Draft 7 if absent).
 ///
 /// # Arguments
 ///
@@ -44,35 +49,289 @@ pub fn validate_json_schema(schema: &Value) -> Result<(), CliError> {
 
 /// Compile a JSON Schema into a reusable validator.
 ///
-/// Uses JSON Schema Draft 7 specification. Returns the compiled validator
-/// on success, or a detailed error if the schema is invalid.
+/// The draft is detected from `$schema` (see [`detect_draft`]); schemas without
+/// `$schema` use Draft 7. Returns the compiled validator on success, or a
+/// detailed error if the schema is invalid.
+///
+/// Only local `file://` references are retrieved; remote `$ref`s are rejected.
+/// Use [`bundle_file_refs`] first to resolve relative references to sibling files.
 ///
 /// This is the single point of schema compilation in the codebase. Both
 /// `validate_json_schema()` and the `JsonSchemaGuardrail` constructor
-/// use this function to ensure consistent Draft version and compilation options.
+/// use this function to ensure consistent draft detection and compilation options.
 pub fn compile_json_schema(schema: &Value) -> Result<jsonschema::Validator, CliError> {
+    let draft = detect_draft(schema)?;
     jsonschema::options()
-        .with_draft(jsonschema::Draft::Draft7)
+        .with_draft(draft)
+        .with_retriever(LocalFileRetriever)
         .build(schema)
         .map_err(|e| {
             CliError::InvalidArguments(format!(
                 "JSON Schema validation failed: {e}\n\
                 \n\
-                The provided schema does not conform to JSON Schema Draft 7 specification.\n\
+                The provided schema does not conform to the JSON Schema {} specification.\n\
                 \n\
                 Common issues:\n\
                 - Invalid 'type' value (must be: object, array, string, number, integer, boolean, null)\n\
-                - Malformed '$ref' references\n\
+                - Malformed or remote '$ref' references (only local files are supported)\n\
                 - Invalid regex patterns in 'pattern' fields\n\
                 - Incorrect format for 'properties', 'items', or 'required' fields\n\
                 \n\
                 References:\n\
-                - JSON Schema specification: https://json-schema.org/draft-07/json-schema-release-notes.html\n\
-                - Schema validator: https://www.jsonschemavalidator.net/"
+                - JSON Schema specification: https://json-schema.org/specification\n\
+                - Schema validator: https://www.jsonschemavalidator.net/",
+                draft_name(draft)
             ))
         })
 }
 
+/// Detect the JSON Schema draft from the schema's `$schema` keyword
+///
+/// Schemas without `$schema` default to Draft 7. Drafts 4, 6, 7, 2019-09 and
+/// 2020-12 are recognized; any other `$schema` value (e.g. a custom meta-schema)
+/// is rejected.
+pub fn detect_draft(schema: &Value) -> Result<jsonschema::Draft, CliError> {
+    match jsonschema::Draft::Draft7.detect(schema) {
+        draft @ (jsonschema::Draft::Draft4
+        | jsonschema::Draft::Draft6
+        | jsonschema::Draft::Draft7
+        | jsonschema::Draft::Draft201909
+        | jsonschema::Draft::Draft202012) => Ok(draft),
+        _ => Err(CliError::InvalidArguments(format!(
+            "Unsupported '$schema' value {}\n\
+            Supported drafts: draft-04, draft-06, draft-07, 2019-09, 2020-12 \
+            (e.g. \"https://json-schema.org/draft/2020-12/schema\")",
+            schema.get("$schema").unwrap_or(&Value::Null)
+        ))),
+    }
+}
+
+/// Human-readable draft name for messages
+fn draft_name(draft: jsonschema::Draft) -> &'static str {
+    match draft {
+        jsonschema::Draft::Draft4 => "Draft 4",
+        jsonschema::Draft::Draft6 => "Draft 6",
+        jsonschema::Draft::Draft7 => "Draft 7",
+        jsonschema::Draft::Draft201909 => "Draft 2019-09",
+        _ => "Draft 2020-12",
+    }
+}
+
+/// Retriever that reads `file://` references and rejects everything else
+///
+/// Prevents schema compilation from making network requests for remote `$ref`s.
+struct LocalFileRetriever;
+
+impl jsonschema::Retrieve for LocalFileRetriever {
+    fn retrieve(
+        &self,
+        uri: &jsonschema::Uri<String>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        if uri.scheme().as_str() != "file" {
+            return Err(format!(
+                "remote $ref '{}' is not supported (only local schema files can be referenced)",
+                uri.as_str()
+            )
+            .into());
+        }
+        let file = std::fs::File::open(uri.path().as_str())?;
+        Ok(serde_json::from_reader(file)?)
+    }
+}
+
+/// Keywords whose values are data, not subschemas (not scanned for `$ref` in keyword position)
+const DATA_KEYWORDS: &[&str] = &["const", "default", "enum", "examples"];
+
+/// Resolve relative `$ref`s to local files by bundling them into the schema
+///
+/// Every schema file referenced (directly or transitively) through a relative
+/// `$ref` such as `"common.json#/definitions/address"` is copied under the root
+/// schema's `$defs` and the reference is rewritten to point there
+/// (`"#/$defs/common/definitions/address"`). Paths are resolved relative to the
+/// file containing the reference. The result is self-contained, so it can be
+/// compiled without a base URI and sent to providers as a response format.
+///
+/// Fragment-only references (`"#/..."`) and absolute URIs are left unchanged.
+pub fn bundle_file_refs(schema: &Value, schema_path: &Path) -> Result<Value, CliError> {
+    let root_path = schema_path.canonicalize().map_err(|e| {
+        CliError::FileNotFound(format!(
+            "Failed to resolve schema path '{}': {e}",
+            schema_path.display()
+        ))
+    })?;
+
+    let mut bundler = RefBundler::default();
+    bundler.documents.insert(root_path.clone(), None);
+    if let Some(Value::Object(defs)) = schema.get("$defs") {
+        bundler.used_keys.extend(defs.keys().cloned());
+    }
+
+    let mut root = schema.clone();
+    bundler.rewrite(&mut root, &root_path, None)?;
+
+    let mut bundled = Vec::new();
+    while let Some((key, path)) = bundler.pending.pop_front() {
+        let content = fs::read_to_string(&path).map_err(|e| {
+            CliError::FileNotFound(format!(
+                "Failed to read referenced schema file '{}': {e}",
+                path.display()
+            ))
+        })?;
+        let mut document: Value = serde_json::from_str(&content).map_err(|e| {
+            CliError::InvalidArguments(format!(
+                "Referenced schema file '{}' is not valid JSON: {e}",
+                path.display()
+            ))
+        })?;
+        // An embedded document must not change the base URI or draft of the bundle
+        if let Value::Object(object) = &mut document {
+            object.remove("$id");
+            object.remove("$schema");
+        }
+        bundler.rewrite(&mut document, &path, Some(&key))?;
+        bundled.push((key, document));
+    }
+
+    if bundled.is_empty() {
+        return Ok(root);
+    }
+    let Value::Object(root_object) = &mut root else {
+        return Err(CliError::InvalidArguments(
+            "A schema with file references must be a JSON object".to_string(),
+        ));
+    };
+    let defs = root_object
+        .entry("$defs")
+        .or_insert_with(|| Value::Object(Default::default()));
+    let Value::Object(defs) = defs else {
+        return Err(CliError::InvalidArguments(
+            "'$defs' must be an object".to_string(),
+        ));
+    };
+    for (key, document) in bundled {
+        defs.insert(key, document);
+    }
+    Ok(root)
+}
+
+/// State for [`bundle_file_refs`]
+#[derive(Default)]
+struct RefBundler {
+    /// Canonical path of every known document and its key under `$defs` (`None` = root)
+    documents: HashMap<PathBuf, Option<String>>,
+    /// Documents still to be read and rewritten
+    pending: VecDeque<(String, PathBuf)>,
+    /// `$defs` keys already taken
+    used_keys: HashSet<String>,
+}
+
+impl RefBundler {
+    /// Rewrite the `$ref`s in a document located at `path` (bundled under `key`)
+    fn rewrite(
+        &mut self,
+        value: &mut Value,
+        path: &Path,
+        key: Option<&str>,
+    ) -> Result<(), CliError> {
+        match value {
+            Value::Object(object) => {
+                if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                    if let Some(rewritten) = self.rewrite_ref(reference, path, key)? {
+                        *reference = rewritten;
+                    }
+                }
+                for (keyword, child) in object.iter_mut() {
+                    if DATA_KEYWORDS.contains(&keyword.as_str()) {
+                        continue;
+                    }
+                    // Keys of `properties`, `$defs`, ... are names, so a property named
+                    // `default` or `enum` is a subschema like any other
+                    match child {
+                        Value::Object(named)
+                            if SUBSCHEMA_MAP_KEYWORDS.contains(&keyword.as_str()) =>
+                        {
+                            for subschema in named.values_mut() {
+                                self.rewrite(subschema, path, key)?;
+                            }
+                        }
+                        _ => self.rewrite(child, path, key)?,
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.rewrite(item, path, key)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Bundled form of a reference, or `None` to leave it unchanged
+    fn rewrite_ref(
+        &mut self,
+        reference: &str,
+        path: &Path,
+        key: Option<&str>,
+    ) -> Result<Option<String>, CliError> {
+        let (file, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        // Only JSON Pointer fragments can be re-rooted (anchors are left alone)
+        if !(fragment.is_empty() || fragment.starts_with('/')) || file.contains(':') {
+            return Ok(None);
+        }
+        if file.is_empty() {
+            return Ok(key.map(|key| format!("#/$defs/{key}{fragment}")));
+        }
+
+        let directory = path.parent().unwrap_or(Path::new("."));
+        let target = directory.join(file).canonicalize().map_err(|e| {
+            CliError::FileNotFound(format!(
+                "Schema '{}' references '{reference}', which cannot be resolved: {e}",
+                path.display()
+            ))
+        })?;
+        let target_key = match self.documents.get(&target) {
+            Some(existing) => existing.clone(),
+            None => {
+                let new_key = self.unique_key(&target);
+                self.documents.insert(target.clone(), Some(new_key.clone()));
+                self.pending.push_back((new_key.clone(), target));
+                Some(new_key)
+            }
+        };
+        Ok(Some(match target_key {
+            Some(target_key) => format!("#/$defs/{target_key}{fragment}"),
+            None => format!("#{fragment}"),
+        }))
+    }
+
+    /// `$defs` key derived from the file name, made unique and JSON Pointer safe
+    fn unique_key(&mut self, path: &Path) -> String {
+        let stem: String = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("schema")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let mut key = stem.clone();
+        let mut suffix = 2;
+        while self.used_keys.contains(&key) {
+            key = format!("{stem}_{suffix}");
+            suffix += 1;
+        }
+        self.used_keys.insert(key.clone());
+        key
+    }
+}
+
 /// Perform basic sanity checks on a JSON Schema
 ///
 /// This is a lightweight check that doesn't require full metaschema validation.
@@ -315,4 +574,152 @@ mod tests {
 
         assert!(validate_json_schema(&schema).is_ok());
     }
+
+    #[test]
+    fn test_detect_draft() {
+        assert_eq!(
+            detect_draft(&json!({ "type": "object" })).unwrap(),
+            jsonschema::Draft::Draft7
+        );
+        assert_eq!(
+            detect_draft(&json!({ "$schema": "https://json-schema.org/draft/2020-12/schema" }))
+                .unwrap(),
+            jsonschema::Draft::Draft202012
+        );
+        assert_eq!(
+            detect_draft(&json!({ "$schema": "https://json-schema.org/draft/2019-09/schema" }))
+                .unwrap(),
+            jsonschema::Draft::Draft201909
+        );
+        assert!(detect_draft(&json!({ "$schema": "https://example.com/custom" })).is_err());
+    }
+
+    #[test]
+    fn test_compile_2020_12_keywords() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "array",
+            "prefixItems": [{ "type": "string" }, { "type": "integer" }],
+            "items": false
+        });
+        let validator = compile_json_schema(&schema).unwrap();
+        assert!(validator.is_valid(&json!(["a", 1])));
+        assert!(!validator.is_valid(&json!(["a", 1, true])));
+    }
+
+    #[test]
+    fn test_remote_ref_rejected() {
+        let schema = json!({ "$ref": "https://example.com/schemas/person.json" });
+        assert!(compile_json_schema(&schema).is_err());
+    }
+
+    #[test]
+    fn test_bundle_file_refs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("common")).unwrap();
+        std::fs::write(
+            dir.path().join("common/address.json"),
+            json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "definitions": {
+                    "city": { "type": "string" }
+                },
+                "type": "object",
+                "properties": { "city": { "$ref": "#/definitions/city" } },
+                "required": ["city"]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let root_path = dir.path().join("person.json");
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "home": { "$ref": "common/address.json" },
+                "city": { "$ref": "./common/address.json#/definitions/city" },
+                "self": { "$ref": "person.json" }
+            }
+        });
+        std::fs::write(&root_path, schema.to_string()).unwrap();
+
+        let bundled = bundle_file_refs(&schema, &root_path).unwrap();
+        assert_eq!(bundled["properties"]["home"]["$ref"], "#/$defs/address");
+        assert_eq!(
+            bundled["properties"]["city"]["$ref"],
+            "#/$defs/address/definitions/city"
+        );
+        assert_eq!(bundled["properties"]["self"]["$ref"], "#");
+        let address = &bundled["$defs"]["address"];
+        assert!(address.get("$schema").is_none());
+        assert_eq!(
+            address["properties"]["city"]["$ref"],
+            "#/$defs/address/definitions/city"
+        );
+
+        let validator = compile_json_schema(&bundled).unwrap();
+        assert!(validator.is_valid(&json!({ "home": { "city": "Paris" } })));
+        assert!(!validator.is_valid(&json!({ "home": {} })));
+    }
+
+    #[test]
+    fn test_bundle_refs_under_properties_named_like_keywords() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("money.json"),
+            json!({ "type": "number", "minimum": 0 }).to_string(),
+        )
+        .unwrap();
+        let root_path = dir.path().join("settings.json");
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "default": { "$ref": "money.json" },
+                "enum": { "type": "array", "items": { "$ref": "money.json" } }
+            },
+            "patternProperties": { "^const_": { "$ref": "money.json" } },
+            "$defs": { "examples": { "$ref": "money.json" } },
+            "default": { "$ref": "not-a-file.json" }
+        });
+        std::fs::write(&root_path, schema.to_string()).unwrap();
+
+        let bundled = bundle_file_refs(&schema, &root_path).unwrap();
+        assert_eq!(bundled["properties"]["default"]["$ref"], "#/$defs/money");
+        assert_eq!(
+            bundled["properties"]["enum"]["items"]["$ref"],
+            "#/$defs/money"
+        );
+        assert_eq!(
+            bundled["patternProperties"]["^const_"]["$ref"],
+            "#/$defs/money"
+        );
+        assert_eq!(bundled["$defs"]["examples"]["$ref"], "#/$defs/money");
+        // The `default` keyword's value is data, left as is
+        assert_eq!(bundled["default"]["$ref"], "not-a-file.json");
+    }
+
+    #[test]
+    fn test_bundle_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root_path = dir.path().join("root.json");
+        let schema = json!({ "$ref": "missing.json" });
+        std::fs::write(&root_path, schema.to_string()).unwrap();
+        assert!(matches!(
+            bundle_file_refs(&schema, &root_path),
+            Err(CliError::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_bundle_leaves_internal_and_remote_refs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root_path = dir.path().join("root.json");
+        let schema = json!({
+            "properties": {
+                "a": { "$ref": "#/definitions/a" },
+                "b": { "$ref": "https://example.com/b.json" }
+            }
+        });
+        std::fs::write(&root_path, schema.to_string()).unwrap();
+        assert_eq!(bundle_file_refs(&schema, &root_path).unwrap(), schema);
+    }
 }
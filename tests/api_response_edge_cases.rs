@@ -23,6 +23,10 @@ async fn create_test_config(api_url: String) -> EvaluationConfig {
         pdf_input: None,
         input_guardrails: None,
         output_guardrails: None,
+        canary: None,
+        spotlighting: None,
+        lenient_json: false,
+        schema_retries: 0,
         system_prompt_file: None,
         user_prompt_file: None,
     }
@@ -432,6 +436,10 @@ async fn create_gemini_test_config(api_url: String) -> EvaluationConfig {
         pdf_input: None,
         input_guardrails: None,
         output_guardrails: None,
+        canary: None,
+        spotlighting: None,
+        lenient_json: false,
+        schema_retries: 0,
         system_prompt_file: None,
         user_prompt_file: None,
     }
@@ -688,6 +696,10 @@ async fn create_anthropic_test_config(api_url: String) -> EvaluationConfig {
         pdf_input: None,
         input_guardrails: None,
         output_guardrails: None,
+        canary: None,
+        spotlighting: None,
+        lenient_json: false,
+        schema_retries: 0,
         system_prompt_file: None,
         user_prompt_file: None,
     }
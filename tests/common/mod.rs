@@ -0,0 +1,37 @@
+//! Helpers shared by the integration tests that mock an OpenAI-compatible endpoint
+//!
+//! Each test binary compiles this module separately and uses only part of it.
+#![allow(dead_code)]
+
+use fortified_llm_client::{config_builder::ConfigBuilder, Provider};
+use mockito::{Matcher, Mock, ServerGuard};
+
+/// OpenAI chat completion response body with a single assistant message
+pub fn chat_body(content: &str) -> String {
+    serde_json::json!({
+        "choices": [{ "message": { "role": "assistant", "content": content } }]
+    })
+    .to_string()
+}
+
+/// Mock answering chat requests whose body matches `matcher` with `content`
+pub async fn mock_chat(server: &mut ServerGuard, matcher: Matcher, content: &str) -> Mock {
+    server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(matcher)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(chat_body(content))
+        .create_async()
+        .await
+}
+
+/// Configuration asking the mocked model to extract a person as JSON
+pub fn extraction_config(server: &ServerGuard) -> ConfigBuilder {
+    ConfigBuilder::new()
+        .api_url(server.url() + "/v1/chat/completions")
+        .model("test-model")
+        .system_prompt("Extract the person as JSON")
+        .user_prompt("Alice is 30 years old")
+        .provider(Provider::OpenAI)
+}
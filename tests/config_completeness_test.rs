@@ -28,7 +28,11 @@ fn test_all_config_file_parameters_are_used() {
         "api_key": "test-api-key-12345",
         "response_format": "json-object",
         "response_format_schema": null,
-        "response_format_schema_strict": null
+        "response_format_schema_strict": null,
+        "canary": "system_prompt",
+        "spotlighting": { "mode": "delimiting" },
+        "lenient_json": true,
+        "schema_retries": 2
     }"#;
 
     let file = NamedTempFile::new().unwrap();
@@ -101,6 +105,32 @@ fn test_all_config_file_parameters_are_used() {
         ),
     }
 
+    // Verify canary mode is applied from config file
+    assert_eq!(
+        config.canary,
+        Some(fortified_llm_client::CanaryPlacement::SystemPrompt),
+        "canary not applied from config file"
+    );
+
+    // Verify spotlighting is applied from config file
+    assert_eq!(
+        config.spotlighting,
+        Some(fortified_llm_client::SpotlightingConfig::Delimiting),
+        "spotlighting not applied from config file"
+    );
+
+    // Verify lenient JSON extraction is applied from config file
+    assert!(
+        config.lenient_json,
+        "lenient_json not applied from config file"
+    );
+
+    // Verify schema retries are applied from config file
+    assert_eq!(
+        config.schema_retries, 2,
+        "schema_retries not applied from config file"
+    );
+
     // Verify provider is applied from config file
     assert_eq!(
         config.provider,
@@ -104,6 +104,10 @@ temperature = 0.0
         pdf_input: None,
         input_guardrails: None,
         output_guardrails: None,
+        canary: None,
+        spotlighting: None,
+        lenient_json: false,
+        schema_retries: 0,
         system_prompt_file: None,
         user_prompt_file: None,
     };
@@ -185,6 +189,10 @@ async fn test_end_to_end_config_with_inline_text() {
         pdf_input: None,
         input_guardrails: None,
         output_guardrails: None,
+        canary: None,
+        spotlighting: None,
+        lenient_json: false,
+        schema_retries: 0,
         system_prompt_file: None,
         user_prompt_file: None,
     };
@@ -321,6 +329,10 @@ temperature = 0.0
         pdf_input: None,
         input_guardrails: None,
         output_guardrails: None,
+        canary: None,
+        spotlighting: None,
+        lenient_json: false,
+        schema_retries: 0,
         system_prompt_file: None,
         user_prompt_file: None,
     };
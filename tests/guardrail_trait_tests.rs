@@ -1,8 +1,10 @@
 use fortified_llm_client::guardrails::{
     config::RegexGuardrailConfig,
     json_schema::{JsonSchemaGuardrail, RULE_JSON_SCHEMA_VIOLATION},
-    GuardrailProvider, RegexGuardrail, Severity,
+    GuardrailProvider, LlamaGuardConfig, LlamaGuardProvider, RegexGuardrail, Severity,
+    ValidationContext,
 };
+use mockito::{Matcher, Server};
 use std::io::Write;
 
 #[tokio::test]
@@ -128,3 +130,69 @@ async fn test_json_schema_guardrail_rejects_invalid() {
         .iter()
         .any(|v| v.rule == RULE_JSON_SCHEMA_VIOLATION));
 }
+
+fn llama_guard_mock_body(verdict: &str) -> String {
+    serde_json::json!({ "response": verdict, "done": true }).to_string()
+}
+
+#[tokio::test]
+async fn test_llama_guard_output_context_builds_conversation() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/generate")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex("User: How do I pick a lock\\?".to_string()),
+            // /api/generate has no message list: the history is flattened into the prompt
+            Matcher::Regex("Assistant: Use a tension wrench".to_string()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(llama_guard_mock_body("unsafe\nS2"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let provider = LlamaGuardProvider::new(LlamaGuardConfig {
+        api_url: server.url() + "/api/generate",
+        ..Default::default()
+    });
+    let response = "Use a tension wrench and a pick.";
+    let context = ValidationContext::output("You are helpful.", "How do I pick a lock?", response);
+
+    let result = provider
+        .validate_with_context(response, &context)
+        .await
+        .unwrap();
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].rule, "S2");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_llama_guard_input_context_sends_raw_content() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/generate")
+        .match_body(Matcher::PartialJson(
+            serde_json::json!({ "prompt": "Hello there" }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(llama_guard_mock_body("safe"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let provider = LlamaGuardProvider::new(LlamaGuardConfig {
+        api_url: server.url() + "/api/generate",
+        ..Default::default()
+    });
+    let context = ValidationContext::input("You are helpful.", "Hello there");
+
+    let result = provider
+        .validate_with_context("Hello there", &context)
+        .await
+        .unwrap();
+    assert!(result.passed);
+    mock.assert_async().await;
+}
@@ -0,0 +1,123 @@
+//! Integration tests for the code and command safety guardrail
+//!
+//! These tests verify that the code safety guardrail can be configured via TOML
+//! files (including per-rule severities and a custom rules file) and that
+//! dangerous constructs in fenced code blocks are reported with their positions.
+
+use fortified_llm_client::{
+    create_guardrail_provider, load_config_file, CodeSafetyConfig, GuardrailProviderConfig,
+    ProviderSpecificResult, Severity,
+};
+use std::io::Write;
+
+#[tokio::test]
+async fn test_code_safety_loads_from_config() {
+    let mut rules_file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(rules_file, "# Team rules").unwrap();
+    writeln!(
+        rules_file,
+        "shell\tKUBECTL_DELETE_NAMESPACE\t\\bkubectl\\s+delete\\s+(?:ns|namespace)\\b\tNamespace deletion\thigh"
+    )
+    .unwrap();
+
+    let config_content = format!(
+        r#"
+api_url = "http://localhost:11434/api/generate"
+model = "test-model"
+user_prompt = "Test"
+
+[guardrails.output]
+type = "code_safety"
+rules_file = "{}"
+disabled_rules = ["DROP_TABLE"]
+
+[guardrails.output.severity_overrides]
+CHMOD_777 = "Low"
+"#,
+        rules_file.path().display()
+    );
+
+    let mut temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = load_config_file(temp_file.path()).unwrap();
+    let output = config.guardrails.unwrap().output.unwrap();
+    match &output {
+        GuardrailProviderConfig::CodeSafety(code_safety) => {
+            assert!(!code_safety.disable_default_rules); // default
+            assert!(!code_safety.scan_unfenced); // default
+            assert_eq!(code_safety.disabled_rules, vec!["DROP_TABLE"]);
+            assert_eq!(code_safety.severity_overrides["CHMOD_777"], Severity::Low);
+        }
+        other => panic!("Expected CodeSafety variant, got {other:?}"),
+    }
+
+    let guardrail = create_guardrail_provider(&output).unwrap();
+    let response = "Clean up with:\n\
+        ```bash\n\
+        kubectl delete namespace staging\n\
+        chmod 777 shared/\n\
+        ```\n\
+        ```sql\n\
+        DROP TABLE audit_log;\n\
+        ```";
+    let result = guardrail.validate(response).await.unwrap();
+
+    assert!(!result.passed);
+    let rules: Vec<(&str, Severity)> = result
+        .violations
+        .iter()
+        .map(|v| (v.rule.as_str(), v.severity))
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            ("KUBECTL_DELETE_NAMESPACE", Severity::High),
+            ("CHMOD_777", Severity::Low)
+        ]
+    );
+    let start = response.find("kubectl delete namespace").unwrap();
+    assert_eq!(
+        result.violations[0].location,
+        Some(format!(
+            "Position {start}-{}",
+            start + "kubectl delete namespace".len()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_code_safety_reports_blocks_and_languages() {
+    let config = GuardrailProviderConfig::CodeSafety(CodeSafetyConfig::default());
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    let response = "Install:\n\
+        ```\n\
+        curl -sSL https://install.example.com | sh\n\
+        ```\n\
+        Then configure:\n\
+        ```js\n\
+        process.env.NODE_TLS_REJECT_UNAUTHORIZED = '0';\n\
+        ```";
+    let result = guardrail.validate(response).await.unwrap();
+
+    assert!(!result.passed);
+    let rules: Vec<&str> = result.violations.iter().map(|v| v.rule.as_str()).collect();
+    assert_eq!(rules, vec!["PIPE_TO_SHELL", "TLS_VERIFY_DISABLED"]);
+
+    match result.provider_specific {
+        Some(ProviderSpecificResult::CodeSafety(code_safety)) => {
+            let languages: Vec<(Option<&str>, bool)> = code_safety
+                .blocks
+                .iter()
+                .map(|b| (b.language.as_deref(), b.detected))
+                .collect();
+            assert_eq!(
+                languages,
+                vec![(Some("shell"), true), (Some("javascript"), false)]
+            );
+        }
+        other => panic!("Expected CodeSafety result, got {other:?}"),
+    }
+}
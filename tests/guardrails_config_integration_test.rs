@@ -206,6 +206,7 @@ timeout_secs = 60
             providers,
             execution,
             aggregation,
+            ..
         } => {
             assert_eq!(providers.len(), 2);
             assert_eq!(*execution, fortified_llm_client::ExecutionMode::Parallel);
@@ -215,13 +216,13 @@ timeout_secs = 60
             );
 
             // Verify first provider is Regex
-            match &providers[0] {
+            match &providers[0].provider {
                 GuardrailProviderConfig::Regex { .. } => {}
                 _ => panic!("Expected first provider to be Regex"),
             }
 
             // Verify second provider is LlamaGuard
-            match &providers[1] {
+            match &providers[1].provider {
                 GuardrailProviderConfig::LlamaGuard { .. } => {}
                 _ => panic!("Expected second provider to be LlamaGuard"),
             }
@@ -458,7 +459,7 @@ schema_file = "{}"
     assert!(guardrails.output.is_some());
 
     match guardrails.output.as_ref().unwrap() {
-        GuardrailProviderConfig::JsonSchema { schema_file } => {
+        GuardrailProviderConfig::JsonSchema { schema_file, .. } => {
             assert!(schema_file.to_str().unwrap().ends_with(".json"));
         }
         _ => panic!("Expected JsonSchema variant, got {:?}", guardrails.output),
@@ -0,0 +1,147 @@
+//! Integration tests for composite guardrail error policies
+//!
+//! These tests verify that `on_error` and `timeout_override_secs` load from
+//! config files and that errored providers are reported in the output metadata.
+
+use fortified_llm_client::{
+    config_builder::ConfigBuilder, evaluate, load_config_file, CliOutput, ErrorPolicy,
+    GuardrailProviderConfig,
+};
+use mockito::Server;
+use std::io::Write;
+
+/// Composite of a regex check and a Llama Guard server that returns HTTP 500
+async fn evaluate_with_broken_llama_guard(on_error: &str) -> CliOutput {
+    let mut server = Server::new_async().await;
+    let _llm = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"choices": [{"message": {"role": "assistant", "content": "Hello"}}]}"#)
+        .create_async()
+        .await;
+    let _guard = server
+        .mock("POST", "/guard/v1/chat/completions")
+        .with_status(500)
+        .with_body("internal error")
+        .create_async()
+        .await;
+
+    let guardrails: GuardrailProviderConfig = toml::from_str(&format!(
+        r#"
+type = "composite"
+execution = "parallel"
+aggregation = "all_must_pass"
+
+[[providers]]
+type = "regex"
+max_length_bytes = 1048576
+
+[[providers]]
+type = "llama_guard"
+api_url = "{}/guard/v1/chat/completions"
+model = "llama-guard3:8b"
+timeout_secs = 5
+on_error = "{on_error}"
+"#,
+        server.url()
+    ))
+    .unwrap();
+
+    let config = ConfigBuilder::new()
+        .api_url(server.url() + "/v1/chat/completions")
+        .model("test-model")
+        .system_prompt("You are helpful.")
+        .user_prompt("Hi there")
+        .input_guardrails(guardrails)
+        .build()
+        .unwrap();
+
+    evaluate(config).await.unwrap()
+}
+
+#[test]
+fn test_error_policy_loads_from_config() {
+    let config_content = r#"
+api_url = "http://localhost:11434/api/generate"
+model = "test-model"
+system_prompt = "Test system"
+user_prompt = "Test user"
+
+[guardrails]
+type = "composite"
+execution = "parallel"
+aggregation = "all_must_pass"
+
+[[guardrails.providers]]
+type = "regex"
+max_length_bytes = 1048576
+
+[[guardrails.providers]]
+type = "llama_guard"
+api_url = "http://localhost:11434/api/generate"
+model = "llama-guard3:8b"
+timeout_secs = 60
+on_error = "warn"
+timeout_override_secs = 5
+"#;
+
+    let mut temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = load_config_file(temp_file.path().to_str().unwrap()).unwrap();
+    let guardrails = config.guardrails.unwrap();
+    match guardrails.provider.expect("Should have guardrail config") {
+        GuardrailProviderConfig::Composite { providers, .. } => {
+            assert_eq!(providers[0].on_error, ErrorPolicy::FailClosed); // default
+            assert_eq!(providers[0].timeout_override_secs, None);
+            assert_eq!(providers[1].on_error, ErrorPolicy::Warn);
+            assert_eq!(providers[1].timeout_override_secs, Some(5));
+            // The provider's own timeout is not shadowed by the override
+            match &providers[1].provider {
+                GuardrailProviderConfig::LlamaGuard { timeout_secs, .. } => {
+                    assert_eq!(*timeout_secs, 60)
+                }
+                other => panic!("Expected LlamaGuard, got {other:?}"),
+            }
+        }
+        other => panic!("Expected Composite, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_fail_closed_blocks_and_reports_provider() {
+    let output = evaluate_with_broken_llama_guard("fail_closed").await;
+
+    assert_eq!(output.status, "error");
+    let error = output.error.unwrap();
+    assert_eq!(error.code, "INPUT_VALIDATION_FAILED");
+    assert!(error.message.contains("PROVIDER_ERROR"));
+    assert_eq!(
+        output.metadata.guardrail_provider_errors,
+        Some(vec!["LlamaGuard3".to_string()])
+    );
+}
+
+#[tokio::test]
+async fn test_fail_open_passes_and_reports_provider() {
+    let output = evaluate_with_broken_llama_guard("fail_open").await;
+
+    assert_eq!(output.status, "success");
+    assert_eq!(
+        output.metadata.guardrail_provider_errors,
+        Some(vec!["LlamaGuard3".to_string()])
+    );
+}
+
+#[tokio::test]
+async fn test_warn_passes_and_reports_provider() {
+    let output = evaluate_with_broken_llama_guard("warn").await;
+
+    assert_eq!(output.status, "success");
+    assert_eq!(
+        output.metadata.guardrail_provider_errors,
+        Some(vec!["LlamaGuard3".to_string()])
+    );
+}
@@ -0,0 +1,182 @@
+//! Integration tests for the document format guardrail
+//!
+//! These tests verify that the format guardrail can be configured via TOML
+//! files for each format and that parse errors and structural violations are
+//! reported with their line and column.
+
+use fortified_llm_client::{
+    create_guardrail_provider, load_config_file, CsvColumnType, DocumentFormat,
+    GuardrailProviderConfig,
+};
+use std::io::Write;
+
+fn load_output_guardrail(guardrail_section: &str) -> GuardrailProviderConfig {
+    let config_content = format!(
+        r#"
+api_url = "http://localhost:11434/api/generate"
+model = "test-model"
+user_prompt = "Test"
+
+{guardrail_section}
+"#
+    );
+    let mut temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = load_config_file(temp_file.path()).unwrap();
+    config.guardrails.unwrap().output.unwrap()
+}
+
+#[tokio::test]
+async fn test_yaml_with_schema_from_config() {
+    let mut schema_file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+    schema_file
+        .write_all(
+            br#"{
+                "type": "object",
+                "required": ["services"],
+                "properties": {
+                    "services": {
+                        "type": "array",
+                        "items": { "type": "object", "required": ["name", "port"] }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+    schema_file.flush().unwrap();
+
+    let output = load_output_guardrail(&format!(
+        r#"
+[guardrails.output]
+type = "format"
+format = "yaml"
+schema_file = "{}"
+"#,
+        schema_file.path().display()
+    ));
+    match &output {
+        GuardrailProviderConfig::Format(format) => {
+            assert_eq!(format.format, DocumentFormat::Yaml);
+            assert!(format.schema_file.is_some());
+        }
+        other => panic!("Expected Format variant, got {other:?}"),
+    }
+
+    let guardrail = create_guardrail_provider(&output).unwrap();
+    let valid = "services:\n  - name: api\n    port: 8080\n";
+    assert!(guardrail.validate(valid).await.unwrap().passed);
+
+    let result = guardrail
+        .validate("services:\n  - name: api\n")
+        .await
+        .unwrap();
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].rule, "JSON_SCHEMA_VIOLATION");
+    assert_eq!(
+        result.violations[0].location.as_deref(),
+        Some("/services/0")
+    );
+
+    let result = guardrail
+        .validate("services:\n  - name: api\n   port: 8080\n")
+        .await
+        .unwrap();
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].rule, "FORMAT_PARSE_ERROR");
+    assert!(result.violations[0]
+        .location
+        .as_deref()
+        .unwrap()
+        .starts_with("Line 3, column"));
+}
+
+#[tokio::test]
+async fn test_csv_columns_from_config() {
+    let output = load_output_guardrail(
+        r#"
+[guardrails.output]
+type = "format"
+format = "csv"
+allow_extra_columns = false
+
+[[guardrails.output.columns]]
+name = "sku"
+
+[[guardrails.output.columns]]
+name = "quantity"
+type = "integer"
+"#,
+    );
+    match &output {
+        GuardrailProviderConfig::Format(format) => {
+            assert_eq!(format.delimiter, ','); // default
+            assert_eq!(format.columns.len(), 2);
+            assert_eq!(format.columns[0].column_type, CsvColumnType::String); // default
+            assert_eq!(format.columns[1].column_type, CsvColumnType::Integer);
+            assert!(format.columns[1].required); // default
+        }
+        other => panic!("Expected Format variant, got {other:?}"),
+    }
+
+    let guardrail = create_guardrail_provider(&output).unwrap();
+    assert!(
+        guardrail
+            .validate("sku,quantity\nA-1,3\nB-2,10\n")
+            .await
+            .unwrap()
+            .passed
+    );
+
+    let result = guardrail
+        .validate("sku,quantity\nA-1,3\nB-2,ten\n")
+        .await
+        .unwrap();
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].rule, "CSV_TYPE_MISMATCH");
+    assert_eq!(
+        result.violations[0].location.as_deref(),
+        Some("Line 3, field 2")
+    );
+}
+
+#[tokio::test]
+async fn test_xml_and_markdown_from_config() {
+    let xml = create_guardrail_provider(&load_output_guardrail(
+        r#"
+[guardrails.output]
+type = "format"
+format = "xml"
+root_element = "report"
+"#,
+    ))
+    .unwrap();
+    assert!(
+        xml.validate("<report><item/></report>")
+            .await
+            .unwrap()
+            .passed
+    );
+    let result = xml.validate("<report>\n<item>\n</report>").await.unwrap();
+    assert_eq!(result.violations[0].rule, "FORMAT_PARSE_ERROR");
+
+    let markdown = create_guardrail_provider(&load_output_guardrail(
+        r#"
+[guardrails.output]
+type = "format"
+format = "markdown"
+required_headings = ["Risks"]
+"#,
+    ))
+    .unwrap();
+    let result = markdown
+        .validate("# Plan\n\n```bash\nmake deploy\n")
+        .await
+        .unwrap();
+    let rules: Vec<&str> = result.violations.iter().map(|v| v.rule.as_str()).collect();
+    assert_eq!(
+        rules,
+        vec!["MARKDOWN_UNCLOSED_FENCE", "MARKDOWN_MISSING_HEADING"]
+    );
+}
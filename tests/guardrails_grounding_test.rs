@@ -0,0 +1,256 @@
+//! Integration tests for the grounding guardrail
+//!
+//! These tests verify that the grounding guardrail can be configured via TOML files,
+//! receives the source document through the context-aware validation entry point
+//! (including through composite guardrails), and consults the optional LLM
+//! entailment check for statements the lexical check cannot confirm.
+
+mod common;
+
+use fortified_llm_client::{
+    create_guardrail_provider, load_config_file, AggregationMode, ExecutionMode, GroundingConfig,
+    GroundingEntailmentConfig, GuardrailProviderConfig, RegexGuardrailConfig, ValidationContext,
+};
+use mockito::{Matcher, Server};
+use std::io::Write;
+
+const SOURCE: &str = "Quarterly revenue grew to 12 million dollars. \
+    The company opened three new offices in Berlin, Madrid and Lisbon.";
+
+fn entailment_config(server: &Server) -> GroundingEntailmentConfig {
+    GroundingEntailmentConfig {
+        api_url: server.url() + "/v1/chat/completions",
+        model: "judge".to_string(),
+        timeout_secs: 5,
+        api_key: None,
+        api_key_name: None,
+        max_parallel: 2,
+        max_source_tokens: 256,
+    }
+}
+
+#[test]
+fn test_grounding_loads_from_config() {
+    let config_content = r#"
+api_url = "http://localhost:11434/api/generate"
+model = "test-model"
+system_prompt = "Test system"
+pdf_file = "document.pdf"
+
+[guardrails.output]
+type = "grounding"
+max_unsupported_ratio = 0.1
+min_quote_chars = 20
+
+[guardrails.output.entailment]
+api_url = "http://localhost:11434/v1/chat/completions"
+model = "llama3"
+"#;
+
+    let mut temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = load_config_file(temp_file.path()).unwrap();
+    let output = config.guardrails.unwrap().output.unwrap();
+
+    match output {
+        GuardrailProviderConfig::Grounding(grounding) => {
+            assert_eq!(grounding.max_unsupported_ratio, 0.1);
+            assert_eq!(grounding.min_quote_chars, 20);
+            assert_eq!(grounding.min_token_overlap, 0.6); // default
+            let entailment = grounding.entailment.unwrap();
+            assert_eq!(entailment.model, "llama3");
+            assert_eq!(entailment.timeout_secs, 30); // default
+            assert_eq!(entailment.max_parallel, 4); // default
+            assert_eq!(entailment.max_source_tokens, 4096); // default
+        }
+        _ => panic!("Expected Grounding variant"),
+    }
+}
+
+#[tokio::test]
+async fn test_grounding_receives_context_through_composite() {
+    let config = GuardrailProviderConfig::Composite {
+        providers: vec![
+            GuardrailProviderConfig::Regex(RegexGuardrailConfig::default()).into(),
+            GuardrailProviderConfig::Grounding(GroundingConfig::default()).into(),
+        ],
+        execution: ExecutionMode::Parallel,
+        aggregation: AggregationMode::AllMustPass,
+        fail_on_critical: false,
+    };
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    let context = ValidationContext {
+        source_text: Some(SOURCE),
+        ..Default::default()
+    };
+    let result = guardrail
+        .validate_with_context("Quarterly revenue grew to 45 million dollars.", &context)
+        .await
+        .unwrap();
+
+    assert!(!result.passed);
+    assert!(result
+        .violations
+        .iter()
+        .any(|v| v.rule == "UNGROUNDED_CONTENT"));
+}
+
+#[tokio::test]
+async fn test_grounding_entailment_rescues_paraphrase() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body("SUPPORTED"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let config = GuardrailProviderConfig::Grounding(GroundingConfig {
+        entailment: Some(entailment_config(&server)),
+        ..Default::default()
+    });
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    // Paraphrase: lexical overlap too low, but the entailment model confirms it
+    let response = "Quarterly revenue grew to 12 million dollars. \
+        Expansion brought fresh branches across Europe's capitals.";
+    let context = ValidationContext {
+        source_text: Some(SOURCE),
+        ..Default::default()
+    };
+    let result = guardrail
+        .validate_with_context(response, &context)
+        .await
+        .unwrap();
+
+    assert!(result.passed);
+    assert!(result.warnings.is_empty());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_grounding_entailment_confirms_unsupported() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body("UNSUPPORTED"))
+        .create_async()
+        .await;
+
+    let config = GuardrailProviderConfig::Grounding(GroundingConfig {
+        entailment: Some(entailment_config(&server)),
+        ..Default::default()
+    });
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    let context = ValidationContext {
+        source_text: Some(SOURCE),
+        ..Default::default()
+    };
+    let result = guardrail
+        .validate_with_context("The CEO resigned after a public scandal.", &context)
+        .await
+        .unwrap();
+
+    assert!(!result.passed);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_grounding_entailment_failure_keeps_lexical_verdict() {
+    let mut server = Server::new_async().await;
+    let failing = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::Regex("CEO".to_string()))
+        .with_status(500)
+        .create_async()
+        .await;
+    let supported = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body("SUPPORTED"))
+        .create_async()
+        .await;
+
+    let config = GuardrailProviderConfig::Grounding(GroundingConfig {
+        entailment: Some(entailment_config(&server)),
+        ..Default::default()
+    });
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    let response = "Expansion brought fresh branches across Europe's capitals. \
+        The CEO resigned after a public scandal.";
+    let context = ValidationContext {
+        source_text: Some(SOURCE),
+        ..Default::default()
+    };
+    let result = guardrail
+        .validate_with_context(response, &context)
+        .await
+        .unwrap();
+
+    // The paraphrase is confirmed; the statement whose judgement failed stays unsupported
+    assert!(!result.passed);
+    let statements: Vec<_> = result
+        .violations
+        .iter()
+        .filter(|v| v.rule == "UNSUPPORTED_STATEMENT")
+        .collect();
+    assert_eq!(statements.len(), 1);
+    assert!(statements[0].message.contains("CEO"));
+    assert!(result.warnings.iter().any(|w| w.rule == "PROVIDER_ERROR"));
+    failing.assert_async().await;
+    supported.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_grounding_entailment_sends_relevant_source_excerpt() {
+    let mut server = Server::new_async().await;
+    // Each request carries a bounded excerpt: the part of the source about the claim
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_request(|request| {
+            let body = String::from_utf8_lossy(request.body().unwrap()).into_owned();
+            body.len() < 2_500 && body.contains("three new offices")
+        })
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body("SUPPORTED"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let config = GuardrailProviderConfig::Grounding(GroundingConfig {
+        entailment: Some(entailment_config(&server)),
+        ..Default::default()
+    });
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    let source = format!(
+        "{}{SOURCE} {}",
+        "Unrelated background about the market is given here. ".repeat(100),
+        "Further unrelated notes close the report. ".repeat(100)
+    );
+    let context = ValidationContext {
+        source_text: Some(&source),
+        ..Default::default()
+    };
+    let result = guardrail
+        .validate_with_context(
+            "The company expanded with fresh branches in Berlin and Madrid.",
+            &context,
+        )
+        .await
+        .unwrap();
+
+    assert!(result.passed);
+    mock.assert_async().await;
+}
@@ -186,6 +186,49 @@ async fn test_nested_object_with_refs() {
     assert!(!result.passed);
 }
 
+#[tokio::test]
+async fn test_relative_ref_to_sibling_file() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("address.json"),
+        r#"{
+            "type": "object",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"]
+        }"#,
+    )
+    .unwrap();
+    let schema_path = dir.path().join("order.json");
+    std::fs::write(
+        &schema_path,
+        r#"{
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": { "shipping": { "$ref": "address.json" } },
+            "required": ["shipping"]
+        }"#,
+    )
+    .unwrap();
+    let guardrail = JsonSchemaGuardrail::new(schema_path).unwrap();
+
+    let result = guardrail
+        .validate(r#"{"shipping": {"city": "Springfield"}}"#)
+        .await
+        .unwrap();
+    assert!(result.passed);
+
+    let result = guardrail.validate(r#"{"shipping": {}}"#).await.unwrap();
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].location.as_deref(), Some("/shipping"));
+}
+
+#[test]
+fn test_relative_ref_to_missing_file() {
+    let schema_file = create_temp_schema(r#"{ "$ref": "does-not-exist.json" }"#);
+    let err = JsonSchemaGuardrail::new(schema_file.path().to_path_buf()).unwrap_err();
+    assert!(err.to_string().contains("does-not-exist.json"));
+}
+
 #[tokio::test]
 async fn test_agent_sentinel_requirements_schema() {
     let schema = r#"{
@@ -259,6 +302,7 @@ async fn test_output_guardrail_rejects_non_conforming_llm_response() {
         .user_prompt("test input")
         .output_guardrails(GuardrailProviderConfig::JsonSchema {
             schema_file: schema_file.path().to_path_buf(),
+            lenient: false,
         })
         .build()
         .unwrap();
@@ -294,6 +338,7 @@ async fn test_output_guardrail_passes_conforming_llm_response() {
         .user_prompt("test input")
         .output_guardrails(GuardrailProviderConfig::JsonSchema {
             schema_file: schema_file.path().to_path_buf(),
+            lenient: false,
         })
         .build()
         .unwrap();
@@ -335,13 +380,17 @@ async fn test_composite_regex_plus_json_schema() {
                     max_length_bytes: 100000,
                     patterns_file: None,
                     severity_threshold: GuardrailSeverity::Medium,
-                }),
+                })
+                .into(),
                 GuardrailProviderConfig::JsonSchema {
                     schema_file: schema_file.path().to_path_buf(),
-                },
+                    lenient: false,
+                }
+                .into(),
             ],
             execution: ExecutionMode::Sequential,
             aggregation: AggregationMode::AllMustPass,
+            fail_on_critical: false,
         })
         .build()
         .unwrap();
@@ -383,13 +432,17 @@ async fn test_composite_json_schema_fails_within_composite() {
                     max_length_bytes: 100000,
                     patterns_file: None,
                     severity_threshold: GuardrailSeverity::Medium,
-                }),
+                })
+                .into(),
                 GuardrailProviderConfig::JsonSchema {
                     schema_file: schema_file.path().to_path_buf(),
-                },
+                    lenient: false,
+                }
+                .into(),
             ],
             execution: ExecutionMode::Sequential,
             aggregation: AggregationMode::AllMustPass,
+            fail_on_critical: false,
         })
         .build()
         .unwrap();
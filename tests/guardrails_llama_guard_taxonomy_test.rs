@@ -0,0 +1,116 @@
+//! Integration tests for custom Llama Guard taxonomies
+//!
+//! These tests verify that custom categories load from config files, are
+//! rendered into the request prompt, and carry their configured severities,
+//! and that without them the conversation is sent as chat messages.
+
+use fortified_llm_client::{
+    create_guardrail_provider,
+    guardrails::{LlamaGuardConfig, LlamaGuardProvider, Severity, ValidationContext},
+    GuardrailProvider, GuardrailProviderConfig,
+};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+fn taxonomy_config(api_url: &str) -> GuardrailProviderConfig {
+    toml::from_str(&format!(
+        r#"
+type = "llama_guard"
+api_url = "{api_url}"
+model = "llama-guard3:8b"
+timeout_secs = 5
+enabled_categories = ["S1"]
+
+[category_severities]
+S1 = "High"
+
+[[custom_categories]]
+code = "S15"
+name = "Competitor Mentions"
+description = "Responses should not recommend products of ACME's competitors."
+severity = "Low"
+"#
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_custom_categories_load_from_config() {
+    match taxonomy_config("http://localhost:11434/api/generate") {
+        GuardrailProviderConfig::LlamaGuard {
+            custom_categories,
+            category_severities,
+            ..
+        } => {
+            assert_eq!(custom_categories.len(), 1);
+            assert_eq!(custom_categories[0].code, "S15");
+            assert_eq!(custom_categories[0].name, "Competitor Mentions");
+            assert_eq!(custom_categories[0].severity, Severity::Low);
+            assert_eq!(category_severities.get("S1"), Some(&Severity::High));
+        }
+        other => panic!("Expected LlamaGuard, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_custom_categories_sent_in_prompt() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/generate")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex("S1: Violent Crimes\\.".to_string()),
+            Matcher::Regex("S15: Competitor Mentions\\.".to_string()),
+            Matcher::Regex("User: Is BetaCorp better\\?".to_string()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(serde_json::json!({ "response": "unsafe\nS15", "done": true }).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+
+    let provider =
+        create_guardrail_provider(&taxonomy_config(&(server.url() + "/api/generate"))).unwrap();
+    let result = provider.validate("Is BetaCorp better?").await.unwrap();
+
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].rule, "S15");
+    assert_eq!(result.violations[0].severity, Severity::Low);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_output_conversation_sent_as_chat_messages() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::PartialJson(json!({
+            "messages": [
+                { "role": "user", "content": "How do I cook rice?" },
+                { "role": "assistant", "content": "Boil it in salted water." }
+            ]
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "choices": [{ "message": { "role": "assistant", "content": "safe" } }] })
+                .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let provider = LlamaGuardProvider::new(LlamaGuardConfig {
+        api_url: server.url() + "/v1/chat/completions",
+        ..Default::default()
+    });
+    let response = "Boil it in salted water.";
+    let context = ValidationContext::output("You are a chef.", "How do I cook rice?", response);
+    let result = provider
+        .validate_with_context(response, &context)
+        .await
+        .unwrap();
+
+    assert!(result.passed);
+    mock.assert_async().await;
+}
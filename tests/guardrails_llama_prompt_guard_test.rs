@@ -107,6 +107,7 @@ fn test_llama_prompt_guard_provider_creation() {
         threshold: 0.5,
         api_key: None,
         api_key_name: None,
+        chunking: None,
     };
 
     let provider = create_guardrail_provider(&config);
@@ -163,19 +164,20 @@ check_content_filters = true
             providers,
             execution,
             aggregation,
+            ..
         } => {
             assert_eq!(providers.len(), 2);
             assert_eq!(*execution, ExecutionMode::Sequential);
             assert_eq!(*aggregation, AggregationMode::AllMustPass);
 
             // Verify first provider is LlamaPromptGuard
-            match &providers[0] {
+            match &providers[0].provider {
                 GuardrailProviderConfig::LlamaPromptGuard { .. } => {}
                 _ => panic!("Expected first provider to be LlamaPromptGuard"),
             }
 
             // Verify second provider is Regex
-            match &providers[1] {
+            match &providers[1].provider {
                 GuardrailProviderConfig::Regex { .. } => {}
                 _ => panic!("Expected second provider to be Regex"),
             }
@@ -194,6 +196,7 @@ fn test_llama_prompt_guard_config_struct() {
         threshold: 0.3,
         api_key: None,
         api_key_name: None,
+        chunking: LlamaPromptGuardConfig::default_chunking(),
     };
 
     assert_eq!(
@@ -260,7 +263,7 @@ timeout_secs = 30
             assert_eq!(providers.len(), 2);
 
             // Verify first is LlamaPromptGuard
-            match &providers[0] {
+            match &providers[0].provider {
                 GuardrailProviderConfig::LlamaPromptGuard { model, .. } => {
                     assert_eq!(model, "llama-prompt-guard-2-22m");
                 }
@@ -268,7 +271,7 @@ timeout_secs = 30
             }
 
             // Verify second is LlamaGuard
-            match &providers[1] {
+            match &providers[1].provider {
                 GuardrailProviderConfig::LlamaGuard { model, .. } => {
                     assert_eq!(model, "llama-guard3:8b");
                 }
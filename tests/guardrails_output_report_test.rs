@@ -0,0 +1,136 @@
+//! Integration tests for the structured `guardrails` section of CliOutput
+//!
+//! These tests verify that per-stage, per-provider guardrail results are
+//! serialised on both success and failure, including raw classifier output.
+
+use fortified_llm_client::{
+    config_builder::ConfigBuilder, evaluate, guardrails::config::RegexGuardrailConfig,
+    GuardrailProviderConfig, LlamaGuardCategory,
+};
+use mockito::Server;
+
+/// Run an evaluation with a regex input guardrail and a Llama Guard output guardrail
+async fn evaluate_with_verdict(user_prompt: &str, verdict: &str) -> serde_json::Value {
+    let mut server = Server::new_async().await;
+    let _llm = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"choices": [{"message": {"role": "assistant", "content": "Hello"}}]}"#)
+        .create_async()
+        .await;
+    let _guard = server
+        .mock("POST", "/api/generate")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(serde_json::json!({ "response": verdict, "done": true }).to_string())
+        .create_async()
+        .await;
+
+    let config = ConfigBuilder::new()
+        .api_url(server.url() + "/v1/chat/completions")
+        .model("test-model")
+        .system_prompt("You are helpful.")
+        .user_prompt(user_prompt)
+        .input_guardrails(GuardrailProviderConfig::Regex(RegexGuardrailConfig {
+            max_length_bytes: 20,
+            ..Default::default()
+        }))
+        .output_guardrails(GuardrailProviderConfig::LlamaGuard {
+            api_url: server.url() + "/api/generate",
+            model: "llama-guard3:8b".to_string(),
+            timeout_secs: 5,
+            enabled_categories: LlamaGuardCategory::all(),
+            custom_categories: vec![],
+            category_severities: Default::default(),
+            api_key: None,
+            api_key_name: None,
+            chunking: None,
+        })
+        .build()
+        .unwrap();
+
+    let output = evaluate(config).await.unwrap();
+    serde_json::to_value(&output).unwrap()
+}
+
+#[tokio::test]
+async fn test_guardrails_section_on_success() {
+    let output = evaluate_with_verdict("Hi there", "safe").await;
+
+    assert_eq!(output["status"], "success");
+    let guardrails = &output["guardrails"];
+
+    let input = &guardrails["input"];
+    assert_eq!(input["passed"], true);
+    assert!(input["latency_ms"].is_u64());
+    assert_eq!(input["providers"][0]["provider"], "RegexGuardrail");
+    assert_eq!(input["providers"][0]["passed"], true);
+
+    let output_stage = &guardrails["output"];
+    assert_eq!(output_stage["passed"], true);
+    let llama_guard = &output_stage["providers"][0];
+    assert_eq!(llama_guard["provider"], "LlamaGuard3");
+    assert!(llama_guard["latency_ms"].is_u64());
+    assert_eq!(
+        llama_guard["provider_specific"]["llama_guard"]["raw_response"],
+        "safe"
+    );
+}
+
+#[tokio::test]
+async fn test_guardrails_section_on_output_failure() {
+    let output = evaluate_with_verdict("Hi there", "unsafe\nS1").await;
+
+    assert_eq!(output["status"], "error");
+    assert_eq!(output["error"]["code"], "OUTPUT_VALIDATION_FAILED");
+
+    let llama_guard = &output["guardrails"]["output"]["providers"][0];
+    assert_eq!(output["guardrails"]["output"]["passed"], false);
+    assert_eq!(llama_guard["passed"], false);
+    assert_eq!(llama_guard["violations"][0]["rule"], "S1");
+    assert_eq!(llama_guard["violations"][0]["provider"], "LlamaGuard3");
+    assert!(llama_guard["violations"][0]["severity"].is_string());
+    assert_eq!(
+        llama_guard["provider_specific"]["llama_guard"]["violated_categories"][0],
+        "S1"
+    );
+}
+
+#[tokio::test]
+async fn test_guardrails_section_on_input_failure() {
+    let output = evaluate_with_verdict("This prompt is far too long", "safe").await;
+
+    assert_eq!(output["error"]["code"], "INPUT_VALIDATION_FAILED");
+    let input = &output["guardrails"]["input"];
+    assert_eq!(input["passed"], false);
+    assert!(!input["providers"][0]["violations"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    // Output stage never ran
+    assert!(output["guardrails"].get("output").is_none());
+}
+
+#[tokio::test]
+async fn test_no_guardrails_section_without_guardrails() {
+    let mut server = Server::new_async().await;
+    let _llm = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"choices": [{"message": {"role": "assistant", "content": "Hello"}}]}"#)
+        .create_async()
+        .await;
+
+    let config = ConfigBuilder::new()
+        .api_url(server.url() + "/v1/chat/completions")
+        .model("test-model")
+        .system_prompt("You are helpful.")
+        .user_prompt("Hi")
+        .build()
+        .unwrap();
+
+    let output = serde_json::to_value(evaluate(config).await.unwrap()).unwrap();
+    assert!(output.get("guardrails").is_none());
+}
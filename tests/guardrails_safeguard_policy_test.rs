@@ -0,0 +1,117 @@
+//! Integration tests for GPT-OSS-Safeguard policy composition
+//!
+//! These tests verify that policies compose from built-ins, files and inline
+//! text with variables, that reasoning effort reaches the model, and that the
+//! policy hash is recorded in the result.
+
+mod common;
+
+use fortified_llm_client::{
+    create_guardrail_provider,
+    guardrails::{safeguard_policy::policy_hash, BuiltinPolicy},
+    GuardrailProviderConfig, ProviderSpecificResult,
+};
+use mockito::{Matcher, Server};
+use std::io::Write;
+
+#[tokio::test]
+async fn test_composed_policy_sent_and_hashed() {
+    let mut policy_file = tempfile::NamedTempFile::new().unwrap();
+    write!(policy_file, "Never mention {{{{competitor}}}} products.").unwrap();
+
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex("Reasoning: high".to_string()),
+            Matcher::Regex("ACME support assistant".to_string()),
+            Matcher::Regex("Never mention BetaCorp products".to_string()),
+            Matcher::Regex("Personal Data \\(PII\\)".to_string()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body(
+            r#"{"violation": 1, "category": "PII", "rationale": "Discloses an SSN"}"#,
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let config: GuardrailProviderConfig = toml::from_str(&format!(
+        r#"
+type = "gpt_oss_safeguard"
+api_url = "{}/v1/chat/completions"
+model = "gpt-oss-safeguard-20b"
+timeout_secs = 5
+reasoning_effort = "high"
+policy = "You moderate content for the {{{{company}}}} support assistant."
+
+[policy_variables]
+company = "ACME"
+competitor = "BetaCorp"
+
+[[policy_fragments]]
+type = "file"
+path = "{}"
+
+[[policy_fragments]]
+type = "builtin"
+name = "pii"
+"#,
+        server.url(),
+        policy_file.path().display()
+    ))
+    .unwrap();
+
+    let provider = create_guardrail_provider(&config).unwrap();
+    let result = provider.validate("My SSN is 123-45-6789").await.unwrap();
+
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].rule, "PII");
+    let expected_policy = format!(
+        "You moderate content for the ACME support assistant.\n\n\
+         Never mention BetaCorp products.\n\n{}",
+        BuiltinPolicy::Pii.text()
+    );
+    match result.provider_specific {
+        Some(ProviderSpecificResult::GptOssSafeguard(r)) => {
+            assert_eq!(r.policy_hash, Some(policy_hash(&expected_policy)));
+        }
+        other => panic!("Expected GptOssSafeguard result, got {other:?}"),
+    }
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_missing_policy_variable_fails_creation() {
+    let config: GuardrailProviderConfig = toml::from_str(
+        r#"
+type = "gpt_oss_safeguard"
+api_url = "http://localhost:1234/v1/chat/completions"
+model = "gpt-oss-safeguard-20b"
+timeout_secs = 5
+policy = "Rules for {{company}}."
+"#,
+    )
+    .unwrap();
+
+    let err = create_guardrail_provider(&config).err().unwrap();
+    assert!(err.to_string().contains("company"));
+}
+
+#[test]
+fn test_missing_policy_file_fails_creation() {
+    let config: GuardrailProviderConfig = toml::from_str(
+        r#"
+type = "gpt_oss_safeguard"
+api_url = "http://localhost:1234/v1/chat/completions"
+model = "gpt-oss-safeguard-20b"
+timeout_secs = 5
+policy_fragments = [{ type = "file", path = "/nonexistent/policy.txt" }]
+"#,
+    )
+    .unwrap();
+
+    let err = create_guardrail_provider(&config).err().unwrap();
+    assert!(err.to_string().contains("/nonexistent/policy.txt"));
+}
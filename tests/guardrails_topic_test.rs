@@ -0,0 +1,164 @@
+//! Integration tests for the topic guardrail
+//!
+//! These tests verify that allowed and denied topics can be configured via TOML
+//! files, that off-topic content is rejected with the configured refusal
+//! message, and that the optional LLM classifier maps the model's answer to a
+//! configured topic.
+
+mod common;
+
+use fortified_llm_client::{
+    create_guardrail_provider, load_config_file, GuardrailProviderConfig, ProviderSpecificResult,
+    TopicClassifier, TopicConfig, TopicDefinition,
+};
+use mockito::{Matcher, Server};
+use std::io::Write;
+
+fn topic(name: &str, description: &str) -> TopicDefinition {
+    TopicDefinition {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_topic_loads_from_config_and_rejects_off_topic_input() {
+    let config_content = r#"
+api_url = "http://localhost:11434/api/generate"
+model = "test-model"
+user_prompt = "Test"
+
+[guardrails.input]
+type = "topic"
+refusal_message = "I can only answer questions about ACME routers."
+
+[[guardrails.input.allowed_topics]]
+name = "router support"
+keywords = ["router", "wifi"]
+examples = ["How do I update the firmware?"]
+
+[[guardrails.input.denied_topics]]
+name = "legal advice"
+keywords = ["lawsuit", "sue"]
+"#;
+
+    let mut temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = load_config_file(temp_file.path()).unwrap();
+    let input = config.guardrails.unwrap().input.unwrap();
+    match &input {
+        GuardrailProviderConfig::Topic(topic) => {
+            assert_eq!(topic.allowed_topics.len(), 1);
+            assert_eq!(topic.denied_topics[0].keywords, vec!["lawsuit", "sue"]);
+            assert!(matches!(
+                topic.classifier,
+                TopicClassifier::Similarity { min_similarity } if min_similarity == 0.35
+            ));
+        }
+        other => panic!("Expected Topic variant, got {other:?}"),
+    }
+
+    let guardrail = create_guardrail_provider(&input).unwrap();
+    assert!(
+        guardrail
+            .validate("How do I update the firmware on my device?")
+            .await
+            .unwrap()
+            .passed
+    );
+
+    let off_topic = guardrail
+        .validate("What's the capital of France?")
+        .await
+        .unwrap();
+    assert!(!off_topic.passed);
+    assert_eq!(off_topic.violations[0].rule, "OFF_TOPIC");
+    match off_topic.provider_specific {
+        Some(ProviderSpecificResult::Topic(result)) => assert_eq!(
+            result.refusal_message.as_deref(),
+            Some("I can only answer questions about ACME routers.")
+        ),
+        other => panic!("Expected Topic result, got {other:?}"),
+    }
+
+    let denied = guardrail
+        .validate("Can I sue the company because my router broke?")
+        .await
+        .unwrap();
+    assert!(!denied.passed);
+    assert_eq!(denied.violations[0].rule, "DENIED_TOPIC");
+}
+
+#[tokio::test]
+async fn test_topic_llm_classifier() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex("billing: Invoices and payments".to_string()),
+            Matcher::Regex("politics".to_string()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body("Politics"))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let config = GuardrailProviderConfig::Topic(TopicConfig {
+        allowed_topics: vec![topic("billing", "Invoices and payments")],
+        denied_topics: vec![topic("politics", "Elections, parties and politicians")],
+        classifier: TopicClassifier::Llm {
+            api_url: server.url() + "/v1/chat/completions",
+            model: "classifier".to_string(),
+            timeout_secs: 5,
+            api_key: None,
+            api_key_name: None,
+        },
+        ..Default::default()
+    });
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    let result = guardrail.validate("Who should I vote for?").await.unwrap();
+
+    assert!(!result.passed);
+    assert_eq!(result.violations[0].rule, "DENIED_TOPIC");
+    assert!(result.violations[0].message.contains("politics"));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_topic_llm_classifier_unexpected_answer_is_error() {
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body("I think this is about cooking"))
+        .create_async()
+        .await;
+
+    let config = GuardrailProviderConfig::Topic(TopicConfig {
+        allowed_topics: vec![topic("billing", "Invoices and payments")],
+        classifier: TopicClassifier::Llm {
+            api_url: server.url() + "/v1/chat/completions",
+            model: "classifier".to_string(),
+            timeout_secs: 5,
+            api_key: None,
+            api_key_name: None,
+        },
+        ..Default::default()
+    });
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    assert!(guardrail.validate("Pasta recipe?").await.is_err());
+}
+
+#[test]
+fn test_topic_requires_a_topic() {
+    let config = GuardrailProviderConfig::Topic(TopicConfig::default());
+    assert!(create_guardrail_provider(&config).is_err());
+}
@@ -0,0 +1,85 @@
+//! Integration tests for the URL and domain policy guardrail
+//!
+//! These tests verify that the URL policy can be configured via TOML files and
+//! that markdown image exfiltration, unlisted domains and spoofed hosts in LLM
+//! responses are reported with their positions.
+
+use fortified_llm_client::{
+    create_guardrail_provider, load_config_file, GuardrailProviderConfig, ProviderSpecificResult,
+    UrlPolicyConfig,
+};
+use std::io::Write;
+
+#[tokio::test]
+async fn test_url_policy_loads_from_config() {
+    let config_content = r#"
+api_url = "http://localhost:11434/api/generate"
+model = "test-model"
+user_prompt = "Test"
+
+[guardrails.output]
+type = "url_policy"
+allowed_domains = ["example.com", "*.github.com"]
+flag_punycode = false
+"#;
+
+    let mut temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = load_config_file(temp_file.path()).unwrap();
+    let output = config.guardrails.unwrap().output.unwrap();
+    match &output {
+        GuardrailProviderConfig::UrlPolicy(url_policy) => {
+            assert_eq!(url_policy.allowed_domains.len(), 2);
+            assert!(url_policy.denied_domains.is_empty());
+            assert!(!url_policy.flag_punycode);
+            assert!(url_policy.block_image_query_strings); // default
+            assert!(url_policy.detect_bare_hosts); // default
+        }
+        other => panic!("Expected UrlPolicy variant, got {other:?}"),
+    }
+
+    let guardrail = create_guardrail_provider(&output).unwrap();
+    let result = guardrail
+        .validate("See https://docs.github.com/en and [the site](https://example.com/about).")
+        .await
+        .unwrap();
+    assert!(result.passed);
+}
+
+#[tokio::test]
+async fn test_url_policy_reports_exfiltration_and_positions() {
+    let config = GuardrailProviderConfig::UrlPolicy(UrlPolicyConfig {
+        allowed_domains: vec!["example.com".to_string()],
+        ..Default::default()
+    });
+    let guardrail = create_guardrail_provider(&config).unwrap();
+
+    let response = "Here is your summary.\n\n\
+        ![status](https://example.com/pixel.gif?session=abc123)\n\
+        More at phishing-login.net/reset";
+    let result = guardrail.validate(response).await.unwrap();
+
+    assert!(!result.passed);
+    let rules: Vec<&str> = result.violations.iter().map(|v| v.rule.as_str()).collect();
+    assert_eq!(rules, vec!["IMAGE_QUERY_STRING", "DOMAIN_NOT_ALLOWED"]);
+
+    let image_start = response.find("https://example.com/pixel.gif").unwrap();
+    let image_end = response.find(")\n").unwrap();
+    assert_eq!(
+        result.violations[0].location,
+        Some(format!("Position {image_start}-{image_end}"))
+    );
+
+    match result.provider_specific {
+        Some(ProviderSpecificResult::UrlPolicy(url_result)) => {
+            assert_eq!(url_result.urls.len(), 2);
+            assert_eq!(
+                url_result.urls[1].host.as_deref(),
+                Some("phishing-login.net")
+            );
+        }
+        other => panic!("Expected UrlPolicy result, got {other:?}"),
+    }
+}
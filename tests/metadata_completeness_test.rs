@@ -334,6 +334,8 @@ async fn test_metadata_with_pdf_input() {
         pdf_input: Some("/path/to/file.pdf".to_string()),
         input_guardrails_enabled: None,
         output_guardrails_enabled: None,
+        guardrail_provider_errors: None,
+        json_repairs: None,
     };
 
     // Verify pdf_input field exists and can be set
@@ -493,6 +495,8 @@ fn test_metadata_struct_completeness_compile_check() {
         // Guardrails
         input_guardrails_enabled: None,
         output_guardrails_enabled: None,
+        guardrail_provider_errors: None,
+        json_repairs: None,
     };
 
     // If this compiles, all expected fields are present
@@ -41,6 +41,7 @@ async fn test_mock_provider() {
             api_key: None,
             timeout_secs: 300,
             response_format: None,
+            history: &[],
         })
         .await;
 
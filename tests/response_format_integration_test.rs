@@ -4,7 +4,8 @@
 // formats and wrapped as string for text format.
 
 use fortified_llm_client::{
-    evaluate, EvaluationConfig, JsonSchemaDefinition, Provider, ResponseFormat,
+    config_builder::load_json_schema, evaluate, EvaluationConfig, JsonSchemaDefinition, Provider,
+    ResponseFormat,
 };
 use mockito::Server;
 use serde_json::json;
@@ -27,6 +28,10 @@ async fn create_test_config(api_url: String) -> EvaluationConfig {
         pdf_input: None,
         input_guardrails: None,
         output_guardrails: None,
+        canary: None,
+        spotlighting: None,
+        lenient_json: false,
+        schema_retries: 0,
         system_prompt_file: None,
         user_prompt_file: None,
     }
@@ -334,3 +339,147 @@ async fn test_serialized_output_structure() {
 
     mock.assert_async().await;
 }
+
+#[tokio::test]
+async fn test_lenient_json_extracts_fenced_response() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "choices": [{
+                    "message": {
+                        "role": "assistant",
+                        "content": "Sure! Here is the JSON:\n```json\n{\"name\": \"Bob\", \"tags\": [\"a\", \"b\",],}\n```"
+                    }
+                }]
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut config = create_test_config(server.url() + "/v1/chat/completions").await;
+    config.response_format = Some(ResponseFormat::JsonObject);
+    config.lenient_json = true;
+
+    let output = evaluate(config).await.unwrap();
+
+    let response = output.response.as_ref().unwrap();
+    assert!(
+        response.is_object(),
+        "Extracted JSON should be emitted natively"
+    );
+    assert_eq!(response["name"], "Bob");
+    assert_eq!(response["tags"], json!(["a", "b"]));
+
+    // The repairs are recorded in metadata
+    let serialized = serde_json::to_value(&output).unwrap();
+    assert_eq!(
+        serialized["metadata"]["json_repairs"],
+        json!(["stripped_code_fence", "removed_trailing_commas"])
+    );
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_fenced_response_without_lenient_json_falls_back_to_string() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "choices": [{
+                    "message": {
+                        "role": "assistant",
+                        "content": "```json\n{\"name\": \"Bob\"}\n```"
+                    }
+                }]
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut config = create_test_config(server.url() + "/v1/chat/completions").await;
+    config.response_format = Some(ResponseFormat::JsonObject);
+
+    let output = evaluate(config).await.unwrap();
+
+    assert!(output.response.as_ref().unwrap().is_string());
+    assert!(output.metadata.json_repairs.is_none());
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_lenient_json_ignored_for_text_format() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "choices": [{
+                    "message": {
+                        "role": "assistant",
+                        "content": "The answer is {\"x\": 1}."
+                    }
+                }]
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut config = create_test_config(server.url() + "/v1/chat/completions").await;
+    config.response_format = Some(ResponseFormat::Text);
+    config.lenient_json = true;
+
+    let output = evaluate(config).await.unwrap();
+
+    assert_eq!(
+        output.response.as_ref().unwrap().as_str().unwrap(),
+        "The answer is {\"x\": 1}."
+    );
+    assert!(output.metadata.json_repairs.is_none());
+
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_load_json_schema_bundles_sibling_refs() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("item.json"),
+        r#"{ "type": "object", "properties": { "sku": { "type": "string" } } }"#,
+    )
+    .unwrap();
+    let schema_path = dir.path().join("order.json");
+    std::fs::write(
+        &schema_path,
+        r#"{
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "type": "object",
+            "properties": {
+                "items": { "type": "array", "items": { "$ref": "item.json" } }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let format = load_json_schema(&schema_path, true).unwrap();
+    let ResponseFormat::JsonSchema { json_schema } = format else {
+        panic!("expected json-schema format");
+    };
+    assert_eq!(json_schema.name, "order");
+    // The provider receives a self-contained schema
+    assert_eq!(
+        json_schema.schema["properties"]["items"]["items"]["$ref"],
+        "#/$defs/item"
+    );
+    assert_eq!(json_schema.schema["$defs"]["item"]["type"], "object");
+}
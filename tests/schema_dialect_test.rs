@@ -0,0 +1,173 @@
+// Provider schema dialect tests
+//
+// Verifies that providers receive a schema rewritten for their structured-output mode,
+// while responses are still validated locally against the original schema.
+
+use fortified_llm_client::{config_builder::ConfigBuilder, evaluate, Provider, ResponseFormat};
+use mockito::{Matcher, Server, ServerGuard};
+use serde_json::{json, Value};
+
+const GEMINI_PATH: &str =
+    "/v1/projects/p/locations/l/publishers/google/models/gemini:generateContent";
+
+fn order_format() -> ResponseFormat {
+    ResponseFormat::json_schema(
+        "order".to_string(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "shipping": { "$ref": "#/$defs/address" },
+                "note": { "type": ["string", "null"] }
+            },
+            "required": ["shipping"],
+            "additionalProperties": false,
+            "$defs": {
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "required": ["city"],
+                    "additionalProperties": false
+                }
+            }
+        }),
+        true,
+    )
+}
+
+fn gemini_body(text: &str) -> String {
+    json!({
+        "candidates": [{ "content": { "role": "model", "parts": [{ "text": text }] } }]
+    })
+    .to_string()
+}
+
+fn request_body(request: &mockito::Request) -> Value {
+    serde_json::from_slice(request.body().unwrap()).unwrap()
+}
+
+fn gemini_config(server: &ServerGuard) -> ConfigBuilder {
+    ConfigBuilder::new()
+        .api_url(server.url() + GEMINI_PATH)
+        .model("gemini")
+        .system_prompt("Extract the order as JSON")
+        .user_prompt("Ship to Paris")
+        .provider(Provider::Gemini)
+        .response_format(order_format())
+}
+
+#[tokio::test]
+async fn test_gemini_receives_translated_schema() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", GEMINI_PATH)
+        .match_request(|request| {
+            request_body(request)["generationConfig"]["responseSchema"]
+                == json!({
+                    "type": "object",
+                    "properties": {
+                        "shipping": {
+                            "type": "object",
+                            "properties": { "city": { "type": "string" } },
+                            "required": ["city"]
+                        },
+                        "note": { "type": "string", "nullable": true }
+                    },
+                    "required": ["shipping"]
+                })
+        })
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(gemini_body(
+            r#"{"shipping": {"city": "Paris"}, "note": null}"#,
+        ))
+        .create_async()
+        .await;
+
+    let output = evaluate(gemini_config(&server).build().unwrap())
+        .await
+        .unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(output.status, "success");
+    assert_eq!(
+        output.response.as_ref().unwrap()["shipping"]["city"],
+        "Paris"
+    );
+}
+
+#[tokio::test]
+async fn test_original_schema_validates_gemini_response() {
+    let mut server = Server::new_async().await;
+    // additionalProperties is dropped for Gemini, so the model may add a property
+    let first = server
+        .mock("POST", GEMINI_PATH)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(gemini_body(
+            r#"{"shipping": {"city": "Paris", "zip": "75001"}}"#,
+        ))
+        .create_async()
+        .await;
+    let retry = server
+        .mock("POST", GEMINI_PATH)
+        .match_body(Matcher::Regex("did not conform".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(gemini_body(r#"{"shipping": {"city": "Paris"}}"#))
+        .create_async()
+        .await;
+
+    let config = gemini_config(&server).schema_retries(1).build().unwrap();
+    let output = evaluate(config).await.unwrap();
+
+    first.assert_async().await;
+    retry.assert_async().await;
+    assert_eq!(output.status, "success");
+    let report = output.schema_validation.as_ref().unwrap();
+    assert_eq!(report.total_attempts, 2);
+    assert_eq!(
+        report.attempts[0].violations[0].location.as_deref(),
+        Some("/shipping")
+    );
+}
+
+#[tokio::test]
+async fn test_openai_strict_receives_closed_schema() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_request(|request| {
+            let schema = &request_body(request)["response_format"]["json_schema"]["schema"];
+            // $defs are kept for OpenAI; the optional property is made required
+            schema["required"] == json!(["shipping", "note"])
+                && schema["$defs"]["address"]["additionalProperties"] == json!(false)
+        })
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "choices": [{ "message": {
+                    "role": "assistant",
+                    "content": r#"{"shipping": {"city": "Paris"}, "note": null}"#
+                } }]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let config = ConfigBuilder::new()
+        .api_url(server.url() + "/v1/chat/completions")
+        .model("test-model")
+        .system_prompt("Extract the order as JSON")
+        .user_prompt("Ship to Paris")
+        .provider(Provider::OpenAI)
+        .response_format(order_format())
+        .build()
+        .unwrap();
+    let output = evaluate(config).await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(output.status, "success");
+}
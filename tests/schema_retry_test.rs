@@ -0,0 +1,203 @@
+// Self-correcting schema retry tests
+//
+// Verifies that a response failing the json-schema response format is fed back to the
+// model as a follow-up turn, and that every attempt is recorded in the output.
+
+mod common;
+
+use fortified_llm_client::{config_builder::ConfigBuilder, evaluate, ResponseFormat};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use serde_json::json;
+
+const FEEDBACK: &str = "did not conform to the required JSON Schema";
+
+fn person_format() -> ResponseFormat {
+    ResponseFormat::json_schema(
+        "person".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" }
+            },
+            "required": ["name", "age"]
+        }),
+        true,
+    )
+}
+
+/// Mock answering the first request (any body) or retries (bodies containing feedback)
+///
+/// Create the first-turn mock before the retry mock: once it has been hit, mockito
+/// prefers the retry mock for requests both match.
+async fn mock_turn(server: &mut ServerGuard, is_retry: bool, content: &str) -> Mock {
+    let matcher = if is_retry {
+        Matcher::Regex(FEEDBACK.to_string())
+    } else {
+        Matcher::Any
+    };
+    common::mock_chat(server, matcher, content).await
+}
+
+fn config(server: &ServerGuard, schema_retries: u32) -> ConfigBuilder {
+    common::extraction_config(server)
+        .response_format(person_format())
+        .schema_retries(schema_retries)
+}
+
+#[tokio::test]
+async fn test_retry_corrects_invalid_response() {
+    let mut server = Server::new_async().await;
+    let first = mock_turn(&mut server, false, r#"{"name": "Alice"}"#).await;
+    let retry = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(FEEDBACK.to_string()),
+            // The failed response is replayed as the assistant turn, with the errors
+            Matcher::Regex(
+                r#""role":"assistant","content":"\{\\"name\\": \\"Alice\\"\}""#.to_string(),
+            ),
+            Matcher::Regex("age".to_string()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body(r#"{"name": "Alice", "age": 30}"#))
+        .create_async()
+        .await;
+
+    let output = evaluate(config(&server, 2).build().unwrap()).await.unwrap();
+
+    first.assert_async().await;
+    retry.assert_async().await;
+    assert_eq!(output.status, "success");
+    assert_eq!(output.response.as_ref().unwrap()["age"], 30);
+
+    let report = output.schema_validation.as_ref().unwrap();
+    assert!(report.passed);
+    assert_eq!(report.total_attempts, 2);
+    assert!(!report.attempts[0].passed);
+    assert_eq!(
+        report.attempts[0].violations[0].rule,
+        "JSON_SCHEMA_VIOLATION"
+    );
+    assert!(report.attempts[1].passed);
+    assert!(report.attempts[1].violations.is_empty());
+}
+
+#[tokio::test]
+async fn test_retries_exhausted() {
+    let mut server = Server::new_async().await;
+    let first = mock_turn(&mut server, false, "not json").await;
+    let retries = mock_turn(&mut server, true, r#"{"name": 42}"#)
+        .await
+        .expect(2);
+
+    let output = evaluate(config(&server, 2).build().unwrap()).await.unwrap();
+
+    first.assert_async().await;
+    retries.assert_async().await;
+    assert_eq!(output.status, "error");
+    assert_eq!(output.error_code(), Some("OUTPUT_VALIDATION_FAILED"));
+
+    let report = output.schema_validation.as_ref().unwrap();
+    assert!(!report.passed);
+    assert_eq!(report.total_attempts, 3);
+    assert_eq!(report.attempts[0].violations[0].rule, "JSON_PARSE_ERROR");
+    assert_eq!(
+        report.attempts[2].violations[0].rule,
+        "JSON_SCHEMA_VIOLATION"
+    );
+
+    let serialized = serde_json::to_value(&output).unwrap();
+    assert_eq!(serialized["schema_validation"]["total_attempts"], 3);
+    assert_eq!(serialized["schema_validation"]["attempts"][2]["attempt"], 3);
+}
+
+#[tokio::test]
+async fn test_retry_stops_when_conversation_exceeds_context_limit() {
+    let mut server = Server::new_async().await;
+    // The first turn fits the limit; replaying this response in a retry does not
+    let long_name = "A".repeat(2000);
+    let first = mock_turn(&mut server, false, &format!(r#"{{"name": "{long_name}"}}"#)).await;
+    let retries = mock_turn(&mut server, true, r#"{"name": "Alice", "age": 30}"#)
+        .await
+        .expect(0);
+
+    let config = config(&server, 2)
+        .max_tokens(100)
+        .validate_tokens(true)
+        .context_limit(300)
+        .build()
+        .unwrap();
+    let output = evaluate(config).await.unwrap();
+
+    first.assert_async().await;
+    retries.assert_async().await;
+    assert_eq!(output.error_code(), Some("OUTPUT_VALIDATION_FAILED"));
+    assert!(output
+        .error
+        .as_ref()
+        .unwrap()
+        .message
+        .contains("exceeds model limit (300 tokens)"));
+    assert_eq!(output.schema_validation.as_ref().unwrap().total_attempts, 1);
+}
+
+#[tokio::test]
+async fn test_valid_first_response_needs_one_attempt() {
+    let mut server = Server::new_async().await;
+    let mock = mock_turn(&mut server, false, r#"{"name": "Alice", "age": 30}"#).await;
+
+    let output = evaluate(config(&server, 3).build().unwrap()).await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(output.status, "success");
+    let report = output.schema_validation.as_ref().unwrap();
+    assert!(report.passed);
+    assert_eq!(report.total_attempts, 1);
+}
+
+#[tokio::test]
+async fn test_retries_work_with_lenient_json() {
+    let mut server = Server::new_async().await;
+    let mock = mock_turn(
+        &mut server,
+        false,
+        "Here you go:\n```json\n{\"name\": \"Alice\", \"age\": 30}\n```",
+    )
+    .await;
+
+    let config = config(&server, 1).lenient_json(true).build().unwrap();
+    let output = evaluate(config).await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(output.status, "success");
+    assert_eq!(output.schema_validation.as_ref().unwrap().total_attempts, 1);
+    assert!(output.metadata.json_repairs.is_some());
+}
+
+#[tokio::test]
+async fn test_retries_disabled_by_default() {
+    let mut server = Server::new_async().await;
+    let mock = mock_turn(&mut server, false, r#"{"name": "Alice"}"#).await;
+
+    let output = evaluate(config(&server, 0).build().unwrap()).await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(output.status, "success");
+    assert!(output.schema_validation.is_none());
+    let serialized = serde_json::to_value(&output).unwrap();
+    assert!(serialized.get("schema_validation").is_none());
+}
+
+#[test]
+fn test_schema_retries_limit() {
+    let result = ConfigBuilder::new()
+        .api_url("http://localhost/v1/chat/completions")
+        .model("test-model")
+        .system_prompt("System")
+        .user_prompt("User")
+        .schema_retries(11)
+        .build();
+    assert!(result.is_err());
+}
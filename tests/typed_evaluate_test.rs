@@ -0,0 +1,107 @@
+// Typed evaluation tests
+//
+// Verifies that evaluate_typed() requests a schema derived from the Rust type and
+// returns the deserialized response, or a typed error.
+
+mod common;
+
+use fortified_llm_client::{evaluate_typed, schemars::JsonSchema, CliError};
+use mockito::{Matcher, Server};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+#[schemars(crate = "fortified_llm_client::schemars")]
+struct Person {
+    name: String,
+    age: u32,
+}
+
+#[tokio::test]
+async fn test_typed_response() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::PartialJson(json!({
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": { "name": "Person", "strict": true }
+            }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(common::chat_body(r#"{"name": "Alice", "age": 30}"#))
+        .create_async()
+        .await;
+
+    let person: Person = evaluate_typed(common::extraction_config(&server).build().unwrap())
+        .await
+        .unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(
+        person,
+        Person {
+            name: "Alice".to_string(),
+            age: 30
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_typed_response_mismatch() {
+    let mut server = Server::new_async().await;
+    let mock = common::mock_chat(&mut server, Matcher::Any, r#"{"name": "Alice"}"#).await;
+
+    let err = evaluate_typed::<Person>(common::extraction_config(&server).build().unwrap())
+        .await
+        .unwrap_err();
+
+    mock.assert_async().await;
+    let CliError::ResponseTypeMismatch { type_name, message } = &err else {
+        panic!("expected ResponseTypeMismatch, got {err:?}");
+    };
+    assert!(type_name.ends_with("Person"));
+    assert!(message.contains("age"));
+}
+
+#[tokio::test]
+async fn test_typed_response_with_lenient_json() {
+    let mut server = Server::new_async().await;
+    let mock = common::mock_chat(
+        &mut server,
+        Matcher::Any,
+        "Sure:\n```json\n{\"name\": \"Bob\", \"age\": 41}\n```",
+    )
+    .await;
+
+    let config = common::extraction_config(&server)
+        .lenient_json(true)
+        .build()
+        .unwrap();
+    let person: Person = evaluate_typed(config).await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(person.name, "Bob");
+}
+
+#[tokio::test]
+async fn test_typed_retries_exhausted() {
+    let mut server = Server::new_async().await;
+    let mock = common::mock_chat(&mut server, Matcher::Any, "not json")
+        .await
+        .expect(2);
+
+    let config = common::extraction_config(&server)
+        .schema_retries(1)
+        .build()
+        .unwrap();
+    let err = evaluate_typed::<Person>(config).await.unwrap_err();
+
+    mock.assert_async().await;
+    let CliError::EvaluationFailed { code, .. } = &err else {
+        panic!("expected EvaluationFailed, got {err:?}");
+    };
+    assert_eq!(code, "OUTPUT_VALIDATION_FAILED");
+    assert_eq!(err.code(), "EVALUATION_FAILED");
+}